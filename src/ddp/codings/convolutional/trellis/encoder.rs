@@ -27,7 +27,9 @@ pub struct ConvolutionalEncoder {
 }
 
 impl PipelineStep<Vec<u8>, Vec<u8>> for ConvolutionalEncoder {
-    fn run_SISO(&mut self, input: Vec<u8>) -> Result<ODFormat<Vec<u8>>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: Vec<u8>) -> Result<ODFormat<Vec<u8>>, StepError> {
         // Remove Option handling and use input directly
         Ok(ODFormat::Standard(self.consumer.consume(&input)))
     }
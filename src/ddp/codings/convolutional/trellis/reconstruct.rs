@@ -25,7 +25,9 @@ impl ConvolutionalReassembler {
 }
 
 impl PipelineStep<Vec<u8>, Vec<u8>> for ConvolutionalReassembler {
-    fn run_SISO(&mut self, input: Vec<u8>) -> Result<ODFormat<Vec<u8>>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: Vec<u8>) -> Result<ODFormat<Vec<u8>>, StepError> {
         let mut output: Vec<u8> = vec![0; input.len()];
         self.compute_input_vector(&input, &mut output);
         Ok(ODFormat::Standard(output))
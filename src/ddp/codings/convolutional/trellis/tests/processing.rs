@@ -3,6 +3,7 @@ pub mod convolutional_tests {
     use crate::ddp::codings::convolutional::trellis::{
         params::ConvolutionalParams, reconstruct::ConvolutionalReassembler, trellis::{ConvolutionalEncoderLookup, ConvolutionalLookupGenerator, TrellisStateChangeEncode}, viterbi::ViterbiOpCore
     };
+    use crate::pipeline::api::{Checkpointable, ByteReader, ByteWriter};
     use std::collections::HashMap;
     use rand::Rng;
     extern crate test;
@@ -111,6 +112,29 @@ pub mod convolutional_tests {
         dbg!("{}", &input_sequence);
         assert!(input_sequence == valid_input_sequence);
     }
+
+    #[test]
+    fn viterbi_checkpoint_test() {
+        let test_params1 = ConvolutionalParams::new(
+            2, 
+            1, 
+            vec![1, 3]);
+
+        let test_trellis: ConvolutionalEncoderLookup = ConvolutionalLookupGenerator::generate_encoding_lookup(&test_params1.unwrap());
+        let mut viterbi: ViterbiOpCore = ViterbiOpCore::new(5, &test_trellis);
+        viterbi.viterbi(&vec![0b00, 0b11, 0b01, 0b10, 0b00]);
+        let mut state = ByteWriter::new();
+        viterbi.save_state(&mut state);
+        let state = state.into_bytes();
+
+        let mut restored: ViterbiOpCore = ViterbiOpCore::new(5, &test_trellis);
+        restored.restore_state(&mut ByteReader::new(&state)).unwrap();
+        let next_block: Vec<u8> = vec![0b11, 0b10, 0b00, 0b00, 0b11];
+        assert!(restored.viterbi(&next_block) == viterbi.viterbi(&next_block));
+
+        let mut longer: ViterbiOpCore = ViterbiOpCore::new(8, &test_trellis);
+        assert!(longer.restore_state(&mut ByteReader::new(&state)).is_err());
+    }
     
     #[bench]
     fn viterbi_bench(b: &mut test::Bencher) {
@@ -164,7 +164,42 @@ impl ViterbiOpCore {
 }
 
 impl PipelineStep<Vec<u8>, Vec<u8>> for ViterbiOpCore {
-    fn run_SISO(&mut self, value: Vec<u8>) -> Result<ODFormat<Vec<u8>>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, value: Vec<u8>) -> Result<ODFormat<Vec<u8>>, StepError> {
         Ok(ODFormat::Standard(self.viterbi(&value).1))
     }
+
+    fn checkpointable(&mut self) -> Option<&mut dyn Checkpointable> { Some(self) }
+}
+
+impl Checkpointable for ViterbiOpCore {
+    // the path metrics and survivor paths, whose shapes are fixed by the buffer size and trellis the decoder is built with
+    fn save_state(&self, state: &mut ByteWriter) {
+        state.put_bool(matches!(self.state_machine, ViterbiState::Standard));
+        self.viterbi_matrix.iter().for_each(|metrics| state.put_u32s(metrics));
+        self.state_paths.iter().for_each(|path| state.put_bytes(path));
+        self.newpath_buffer.iter().for_each(|path| state.put_bytes(path));
+    }
+    fn restore_state(&mut self, state: &mut ByteReader) -> Result<(), String> {
+        let started = state.bool()?;
+        let viterbi_matrix = Self::read_matching(&self.viterbi_matrix, || state.u32s())?;
+        let state_paths = Self::read_matching(&self.state_paths, || state.bytes().map(|path| path.to_vec()))?;
+        let newpath_buffer = Self::read_matching(&self.newpath_buffer, || state.bytes().map(|path| path.to_vec()))?;
+
+        self.state_machine = if started { ViterbiState::Standard } else { ViterbiState::NotStarted };
+        self.viterbi_matrix = viterbi_matrix;
+        self.state_paths = state_paths;
+        self.newpath_buffer = newpath_buffer;
+        Ok(())
+    }
+}
+
+impl ViterbiOpCore {
+    fn read_matching<V>(current: &[Vec<V>], mut read: impl FnMut() -> Result<Vec<V>, String>) -> Result<Vec<Vec<V>>, String> {
+        current.iter().map(|row| match read()? {
+            restored if restored.len() == row.len() => Ok(restored),
+            restored => Err(format!("a row of {} values does not fit the decoder, which has {}", restored.len(), row.len()))
+        }).collect()
+    }
 }
\ No newline at end of file
@@ -1,12 +1,14 @@
 use num::Complex;
-use crate::pipeline::api::{ODFormat, PipelineStep};
+use crate::pipeline::api::{ODFormat, PipelineStep, StepError, StepHandler, ValidFloat};
 
 
 pub struct ComplexCaster {}
-impl PipelineStep<Vec<f32>, Vec<Complex<f32>>> for ComplexCaster {
-    fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<Vec<Complex<f32>>>, String> {
+impl<T: ValidFloat> PipelineStep<Vec<T>, Vec<Complex<T>>> for ComplexCaster {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: Vec<T>) -> Result<ODFormat<Vec<Complex<T>>>, StepError> {
         let result = input.iter()
-            .map(|x| Complex::new(*x, 0.0))
+            .map(|x| Complex::new(*x, T::zero()))
             .collect();
         
         Ok(ODFormat::Standard(result))
@@ -14,8 +16,10 @@ impl PipelineStep<Vec<f32>, Vec<Complex<f32>>> for ComplexCaster {
 }
 
 pub struct RealCaster {}
-impl PipelineStep<Vec<Complex<f32>>, Vec<f32>> for RealCaster {
-    fn run_SISO(&mut self, input: Vec<Complex<f32>>) -> Result<ODFormat<Vec<f32>>, String> {
+impl<T: ValidFloat> PipelineStep<Vec<Complex<T>>, Vec<T>> for RealCaster {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: Vec<Complex<T>>) -> Result<ODFormat<Vec<T>>, StepError> {
         let result = input.iter()
             .map(|x| x.re)
             .collect();
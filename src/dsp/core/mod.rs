@@ -1,2 +1,2 @@
 pub mod pointwise_arithmetic;
-mod power;
\ No newline at end of file
+pub mod power;
\ No newline at end of file
@@ -39,10 +39,19 @@ impl PointwiseAdder {
     }
 }
 impl PipelineStep<Vec<f32>, Vec<f32>> for PointwiseAdder {
-    fn run_MISO(&mut self, input: Vec<Vec<f32>>) -> Result<ODFormat<Vec<f32>>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::MISO] }
+
+    fn run_MISO(&mut self, input: Vec<Vec<f32>>) -> Result<ODFormat<Vec<f32>>, StepError> {
         let result_vector = pointwise_arithmetic(input, |x, y| (x + y) * self.constant_coefficient);
         Ok(ODFormat::Standard(result_vector))
     }
+
+    fn handle_control(&mut self, message: &ControlMessage) -> Result<(), String> {
+        match message.key.as_str() {
+            "coefficient" => { self.constant_coefficient = message.get_f32()?; Ok(()) },
+            _ => Err(format!("step has no control '{}'", message.key))
+        }
+    }
 }
 
 
@@ -53,7 +62,9 @@ impl PointwiseSubtractor {
     }
 }
 impl PipelineStep<Vec<f32>, Vec<f32>> for PointwiseSubtractor {
-    fn run_MISO(&mut self, input: Vec<Vec<f32>>) -> Result<ODFormat<Vec<f32>>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::MISO] }
+
+    fn run_MISO(&mut self, input: Vec<Vec<f32>>) -> Result<ODFormat<Vec<f32>>, StepError> {
         let result_vector = pointwise_arithmetic(input, |x, y| x - y);
 
         Ok(ODFormat::Standard(result_vector))
@@ -68,7 +79,9 @@ impl PointwiseMultiplier {
     }
 }
 impl PipelineStep<Vec<f32>, Vec<f32>> for PointwiseMultiplier {
-    fn run_MISO(&mut self, input: Vec<Vec<f32>>) -> Result<ODFormat<Vec<f32>>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::MISO] }
+
+    fn run_MISO(&mut self, input: Vec<Vec<f32>>) -> Result<ODFormat<Vec<f32>>, StepError> {
         let result_vector = pointwise_arithmetic(input, |x, y| x * y);
 
         Ok(ODFormat::Standard(result_vector))
@@ -83,7 +96,9 @@ impl PointwiseDivider {
     }
 }
 impl PipelineStep<Vec<f32>, Vec<f32>> for PointwiseDivider {
-    fn run_MISO(&mut self, input: Vec<Vec<f32>>) -> Result<ODFormat<Vec<f32>>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::MISO] }
+
+    fn run_MISO(&mut self, input: Vec<Vec<f32>>) -> Result<ODFormat<Vec<f32>>, StepError> {
         let result_vector = pointwise_arithmetic(input, |x, y| x / y);
 
         Ok(ODFormat::Standard(result_vector))
@@ -1,10 +1,12 @@
 use num::Complex;
 use crate::dsp::sampling::sampling_formulas::index_from_frequeny;
-use crate::pipeline::api::{ODFormat, PipelineStep};
+use crate::pipeline::api::{ODFormat, PipelineStep, StepError, StepHandler, ControlMessage};
 
 pub struct PowerCalculatorTD {}
 impl PipelineStep<Vec<f32>, f32> for PowerCalculatorTD {
-    fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<f32>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<f32>, StepError> {
         let input_size = input.len();
         let sum: f32 = input.iter()
             .map(|x| x.abs().powf(2.0))
@@ -17,7 +19,9 @@ impl PipelineStep<Vec<f32>, f32> for PowerCalculatorTD {
 
 pub struct PowerCalculatorFD {} // parseval's relation is a wonderful thing
 impl PipelineStep<Vec<Complex<f32>>, f32> for PowerCalculatorFD {
-    fn run_SISO(&mut self, input: Vec<Complex<f32>>) -> Result<ODFormat<f32>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: Vec<Complex<f32>>) -> Result<ODFormat<f32>, StepError> {
         let input_size = input.len();
         let sum: f32 = input.iter()
             .map(|x| x.norm().powf(2.0))
@@ -29,19 +33,39 @@ impl PipelineStep<Vec<Complex<f32>>, f32> for PowerCalculatorFD {
 }
 
 pub struct PowerAtFrequency {
-    frequency_domain_index: usize
+    frequency_domain_index: usize,
+    sample_rate: f32, // kept so the target frequency can be changed while running
+    buffer_size: usize
 }
 impl PowerAtFrequency {
     pub fn new(frequency: f32, sample_rate: f32, buffer_size: usize) -> Self {
         Self {
-            frequency_domain_index: index_from_frequeny(frequency, sample_rate, buffer_size)
+            frequency_domain_index: index_from_frequeny(frequency, sample_rate, buffer_size),
+            sample_rate,
+            buffer_size
         }
     }
 }
 impl PipelineStep<Vec<Complex<f32>>, f32> for PowerAtFrequency {
-    fn run_SISO(&mut self, input: Vec<Complex<f32>>) -> Result<ODFormat<f32>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: Vec<Complex<f32>>) -> Result<ODFormat<f32>, StepError> {
         Ok(ODFormat::Standard(input[self.frequency_domain_index].norm().powf(2.0)))
     }
+
+    fn handle_control(&mut self, message: &ControlMessage) -> Result<(), String> {
+        match message.key.as_str() {
+            "frequency" => {
+                let frequency = message.get_f32()?;
+                if frequency < 0.0 || frequency > self.sample_rate / 2.0 {
+                    return Err(format!("frequency {} is outside 0 to {} (half the sample rate)", frequency, self.sample_rate / 2.0));
+                }
+                self.frequency_domain_index = index_from_frequeny(frequency, self.sample_rate, self.buffer_size);
+                Ok(())
+            },
+            _ => Err(format!("step has no control '{}'", message.key))
+        }
+    }
 }
 
 pub fn calculate_gain_watt(input_power: f32, output_power: f32) -> f32 {
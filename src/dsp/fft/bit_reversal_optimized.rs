@@ -1,5 +1,4 @@
 use num::Complex;
-use std::f32::{consts::{PI}};
 use std::usize::MAX;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
@@ -10,27 +9,30 @@ use crate::pipeline::api::*;
 
 
 // static twiddle computation is less time efficient, apparently. Cache really makes a difference
-pub struct FFTBitReversal { // log_2(n) levels to the n sized fft for radix 2, nlogn total space in the vector
+pub struct FFTBitReversal<T: ValidFloat = f32> { // log_2(n) levels to the n sized fft for radix 2, nlogn total space in the vector
     bit_reversal_mapping: Vec<usize>,
     fft_size: usize,
-    twiddle_factors: Vec<Complex<f32>>,
+    twiddle_factors: Vec<Complex<T>>,
     index_bits_needed: usize,
     strides: Vec<usize>,
     is_ifft: bool
 }
 
-impl FFTBitReversal {
-    pub fn new(buffer_size: usize, is_ifft: bool) -> Self {
+impl<T: ValidFloat> FFTBitReversal<T> {
+    pub fn new(buffer_size: usize, is_ifft: bool) -> Result<Self, String> {
+        if !buffer_size.is_power_of_two() {
+            return Err(format!("the fft buffer size must be a power of two, not {}", buffer_size));
+        }
         let index_bits_needed = (buffer_size as f64).log2() as usize;
         
-        FFTBitReversal { 
+        Ok(FFTBitReversal { 
             bit_reversal_mapping: Self::generate_bit_reversal_mapping(buffer_size, index_bits_needed),
             fft_size: buffer_size,
             twiddle_factors: Self::compute_twiddle_factors(buffer_size),
             index_bits_needed,
             is_ifft,
             strides: Self::compute_strides(buffer_size)
-        }
+        })
     }
 
     fn compute_strides(buffer_size: usize) -> Vec<usize> {
@@ -46,12 +48,13 @@ impl FFTBitReversal {
         return strides;
     }
 
-    fn compute_twiddle_factors(buffer_size: usize) -> Vec<Complex<f32>> {
-        let mut twiddles: Vec<Complex<f32>> = Vec::with_capacity(buffer_size / 2);
+    fn compute_twiddle_factors(buffer_size: usize) -> Vec<Complex<T>> {
+        let mut twiddles: Vec<Complex<T>> = Vec::with_capacity(buffer_size / 2);
 
         for index in 0..(buffer_size / 2) {
-            let real_comp = (-2.0 * PI * index as f32 / buffer_size as f32).cos();
-            let imag_comp = (-2.0 * PI * index as f32 / buffer_size as f32).sin();
+            let angle = T::constant(-2.0) * T::PI() * T::constant(index as f64) / T::constant(buffer_size as f64);
+            let real_comp = angle.cos();
+            let imag_comp = angle.sin();
 
             twiddles.push(Complex::new(real_comp, imag_comp));
         };
@@ -84,7 +87,7 @@ impl FFTBitReversal {
 
         for start_index in 0..buffer_size {
             if reversal_map[start_index] != usize::MAX {
-                let reversed_index = FFTBitReversal::get_bit_reversal(start_index, index_bits_needed);
+                let reversed_index = Self::get_bit_reversal(start_index, index_bits_needed);
 
                 if reversed_index != start_index {
                     reversal_map[start_index] = reversed_index;
@@ -99,7 +102,7 @@ impl FFTBitReversal {
         return reversal_map;
     }
 
-    fn bit_reversal_in_place(&self, buffer: &mut [Complex<f32>]) {
+    fn bit_reversal_in_place(&self, buffer: &mut [Complex<T>]) {
         for first_index in 0..buffer.len() {
             match self.bit_reversal_mapping.get(first_index) {
                 None => {},
@@ -114,11 +117,11 @@ impl FFTBitReversal {
         }
     }
 
-    fn get_proper_twiddle_factor(&self, compute_index: usize, stride: usize) -> Complex<f32> {
+    fn get_proper_twiddle_factor(&self, compute_index: usize, stride: usize) -> Complex<T> {
         return self.twiddle_factors[compute_index * (stride / 2)];
     }
 
-    fn compute_single_butterfly(&self, butterfly_size: usize, butterfly_index: usize, buffer: &mut[Complex<f32>], stride: usize) {
+    fn compute_single_butterfly(&self, butterfly_size: usize, butterfly_index: usize, buffer: &mut[Complex<T>], stride: usize) {
         //println!("TWIDDLES: {:?}, STRIDE: {}", &self.twiddle_factors, stride);
         let half_size = (butterfly_size / 2);
         for compute_index in 0..half_size {
@@ -134,7 +137,7 @@ impl FFTBitReversal {
         }
     }
 
-    fn compute_fft(&self, buffer: &mut [Complex<f32>]) { // iterative version
+    fn compute_fft(&self, buffer: &mut [Complex<T>]) { // iterative version
         self.bit_reversal_in_place(buffer);
 
         for fft_stage in 1..self.index_bits_needed + 1 {
@@ -151,12 +154,12 @@ impl FFTBitReversal {
         }
     }
 
-    pub fn fft(&mut self, mut buffer: Vec<Complex<f32>>) -> Vec<Complex<f32>> {
+    pub fn fft(&mut self, mut buffer: Vec<Complex<T>>) -> Vec<Complex<T>> {
         self.compute_fft(&mut buffer);
         return buffer
     }
 
-    pub fn ifft(&mut self, mut buffer: Vec<Complex<f32>>) -> Vec<Complex<f32>> {
+    pub fn ifft(&mut self, mut buffer: Vec<Complex<T>>) -> Vec<Complex<T>> {
         for value in buffer.iter_mut() {
             *value = value.conj();
         }
@@ -165,7 +168,7 @@ impl FFTBitReversal {
 
         let length = buffer.len();
         for value in buffer.iter_mut() {
-            *value = *value / length as f32;
+            *value = *value / T::constant(length as f64);
         }
 
         return buffer;
@@ -173,8 +176,10 @@ impl FFTBitReversal {
 }
 
 
-impl PipelineStep<Vec<Complex<f32>>, Vec<Complex<f32>>> for FFTBitReversal {
-    fn run_SISO(&mut self, input: Vec<Complex<f32>>) -> Result<ODFormat<Vec<Complex<f32>>>, String> {
+impl<T: ValidFloat> PipelineStep<Vec<Complex<T>>, Vec<Complex<T>>> for FFTBitReversal<T> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: Vec<Complex<T>>) -> Result<ODFormat<Vec<Complex<T>>>, StepError> {
         if self.is_ifft {
             Ok(ODFormat::Standard(self.ifft(input)))
         }
@@ -1,5 +1,6 @@
 use std::mem;
 use num::Complex;
+use crate::pipeline::api::ValidFloat;
 
 
 pub fn fft_shift<T>(buffer: &mut Vec<T>) {
@@ -11,15 +12,16 @@ pub fn fft_shift<T>(buffer: &mut Vec<T>) {
     }
 }
 
-pub fn generate_frequency_axis(sampling_rate: f32, buffer_size: usize) -> Vec<f32> {
+pub fn generate_frequency_axis<T: ValidFloat>(sampling_rate: T, buffer_size: usize) -> Vec<T> {
     let mut frequency_axis = Vec::with_capacity(buffer_size);
     let mut negative_buffer = Vec::with_capacity(buffer_size / 2);
+    let size = T::constant(buffer_size as f64);
 
     for bin_num in 0..buffer_size / 2 {
-        frequency_axis.push(bin_num as f32 * sampling_rate / buffer_size as f32);
+        frequency_axis.push(T::constant(bin_num as f64) * sampling_rate / size);
     }
     for bin_num in 1..(buffer_size / 2) + 1 {
-        negative_buffer.push(-(bin_num as f32) * sampling_rate / buffer_size as f32);
+        negative_buffer.push(-T::constant(bin_num as f64) * sampling_rate / size);
     }
 
     negative_buffer.reverse();
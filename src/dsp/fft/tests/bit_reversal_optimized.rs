@@ -17,7 +17,7 @@ pub mod bit_reversal_fft_optimized {
     }
 
     fn test_fft_bit_reversal(input_buffer: Vec<Complex<f32>>, true_f_domain: &Vec<Complex<f32>>) {
-        let mut fft_computer = FFTBitReversal::new(input_buffer.len(), false);
+        let mut fft_computer = FFTBitReversal::new(input_buffer.len(), false).unwrap();
 
         let original_buffer = input_buffer.clone();
 
@@ -35,7 +35,7 @@ pub mod bit_reversal_fft_optimized {
     }
 
     fn test_fft_bit_reversal_comparative(input_buffer: Vec<Complex<f32>>) {
-        let mut fft_computer = FFTBitReversal::new(input_buffer.len(), false);
+        let mut fft_computer = FFTBitReversal::new(input_buffer.len(), false).unwrap();
 
         let original_buffer = input_buffer.clone();
 
@@ -60,7 +60,7 @@ pub mod bit_reversal_fft_optimized {
 
     fn bit_reversal_tester(inputs_outputs: Vec<(usize, usize, usize)>) {
         for (input, string_size, expected_output) in inputs_outputs.iter() {
-            assert!(FFTBitReversal::get_bit_reversal(*input, *string_size) == * expected_output);
+            assert!(FFTBitReversal::<f32>::get_bit_reversal(*input, *string_size) == * expected_output);
         }
     }
 
@@ -111,6 +111,26 @@ pub mod bit_reversal_fft_optimized {
     );
         
     }
+
+    #[test]
+    pub fn test_optimized_fft_f64() {
+        let input_buffer: Vec<Complex<f64>> = (0..4096).map(|x| Complex::new((x as f64 * 0.37).sin(), 0.0)).collect();
+        let mut true_f_domain = input_buffer.clone();
+        FftPlanner::new().plan_fft_forward(input_buffer.len()).process(&mut true_f_domain);
+
+        let mut fft_computer = FFTBitReversal::<f64>::new(input_buffer.len(), false).unwrap();
+        let result = fft_computer.fft(input_buffer.clone());
+
+        for (computed, expected) in result.iter().zip(true_f_domain.iter()) {
+            assert!((computed - expected).norm() < 1e-9);
+        }
+
+        let original = fft_computer.ifft(result);
+
+        for (computed, expected) in original.iter().zip(input_buffer.iter()) {
+            assert!((computed - expected).norm() < 1e-12);
+        }
+    }
     
     #[bench]
     fn optimized_fft_bench(b: &mut test::Bencher) {
@@ -1,81 +1,84 @@
-use std::f32::consts::PI;
+use crate::pipeline::api::ValidFloat;
 
 use super::window::*;
 
 
-pub struct GaussianWindow {
-    sigma: f32
+pub struct GaussianWindow<T: ValidFloat = f32> {
+    sigma: T
 }
-impl GaussianWindow {
-    pub fn new(sigma: f32) -> Self {
-        assert!(sigma <= 0.5);
+impl<T: ValidFloat> GaussianWindow<T> {
+    pub fn new(sigma: T) -> Self {
+        assert!(sigma <= T::constant(0.5));
         GaussianWindow {sigma}
     }
 }
-impl WindowFunction for GaussianWindow {
-    fn window_function(&self, sample: u32, window_size: usize) -> f32 {
-        let half_window = window_size as f32 / 2.0;
-        let square_term = ((sample as f32 - half_window) / (self.sigma * half_window)).powf(2.0);
+impl<T: ValidFloat> WindowFunction<T> for GaussianWindow<T> {
+    fn window_function(&self, sample: u32, window_size: usize) -> T {
+        let half_window = T::constant(window_size as f64) / T::constant(2.0);
+        let square_term = ((T::constant(sample as f64) - half_window) / (self.sigma * half_window)).powf(T::constant(2.0));
 
-        return (-0.5 * square_term).exp();
+        return (T::constant(-0.5) * square_term).exp();
     }
 }
 
 
-pub struct ConfinedGaussianWindow {
-    sigma: f32
+pub struct ConfinedGaussianWindow<T: ValidFloat = f32> {
+    sigma: T
 }
-impl ConfinedGaussianWindow {
-    pub fn new(sigma: f32) -> Self {
-        assert!(sigma < 0.14);
+impl<T: ValidFloat> ConfinedGaussianWindow<T> {
+    pub fn new(sigma: T) -> Self {
+        assert!(sigma < T::constant(0.14));
         ConfinedGaussianWindow { sigma }
     }
 
-    fn gaussian_function(&self, input: f32, window_size: f32) -> f32 {
-        let const_l = window_size + 1.0;
-        return (-((input - (window_size / 2.0)) / (2.0 * const_l * self.sigma)).powf(2.0)).exp()
+    fn gaussian_function(&self, input: T, window_size: T) -> T {
+        let const_l = window_size + T::one();
+        return (-((input - (window_size / T::constant(2.0))) / (T::constant(2.0) * const_l * self.sigma)).powf(T::constant(2.0))).exp()
     }
 }
-impl WindowFunction for ConfinedGaussianWindow {
-    fn window_function(&self, sample: u32, window_size: usize) -> f32 {
-        let window_size = window_size as f32;
-        let sample = sample as f32;
+impl<T: ValidFloat> WindowFunction<T> for ConfinedGaussianWindow<T> {
+    fn window_function(&self, sample: u32, window_size: usize) -> T {
+        let window_size = T::constant(window_size as f64);
+        let sample = T::constant(sample as f64);
+        let half = T::constant(0.5);
         
-        let numerator = self.gaussian_function(-0.5, window_size) * (
-            self.gaussian_function(sample + window_size + 1.0, window_size) +
-            self.gaussian_function(sample - window_size - 1.0, window_size)
+        let numerator = self.gaussian_function(-half, window_size) * (
+            self.gaussian_function(sample + window_size + T::one(), window_size) +
+            self.gaussian_function(sample - window_size - T::one(), window_size)
         );
 
-        let denominator = self.gaussian_function(-0.5 + window_size + 1.0, window_size) +
-            self.gaussian_function(-0.5 - window_size - 1.0, window_size);
+        let denominator = self.gaussian_function(-half + window_size + T::one(), window_size) +
+            self.gaussian_function(-half - window_size - T::one(), window_size);
 
         return numerator / denominator;
     }
 }
 
 
-pub struct TukeyWindow {
-    alpha: f32
+pub struct TukeyWindow<T: ValidFloat = f32> {
+    alpha: T
 }
-impl TukeyWindow {
-    pub fn new(alpha: f32) -> Self {
-        assert!(alpha <= 1.0 && alpha >= 0.0);
+impl<T: ValidFloat> TukeyWindow<T> {
+    pub fn new(alpha: T) -> Self {
+        assert!(alpha <= T::one() && alpha >= T::zero());
         TukeyWindow{alpha}
     }
 }
-impl WindowFunction for TukeyWindow { // remember the rule on wikipedia! this isnt the full story!
-    fn window_function(&self, sample: u32, window_size: usize) -> f32 {
-        let window_size = window_size as f32;
-        let sample = sample as f32;
+impl<T: ValidFloat> WindowFunction<T> for TukeyWindow<T> { // remember the rule on wikipedia! this isnt the full story!
+    fn window_function(&self, sample: u32, window_size: usize) -> T {
+        let window_size = T::constant(window_size as f64);
+        let sample = T::constant(sample as f64);
+        let two = T::constant(2.0);
+        let half = T::constant(0.5);
         
-        if 0.0 <= sample && sample < (self.alpha * window_size / 2.0) {
-            return 0.5 * (1.0 - (2.0 * PI * sample / (self.alpha * window_size)));
+        if T::zero() <= sample && sample < (self.alpha * window_size / two) {
+            return half * (T::one() - (two * T::PI() * sample / (self.alpha * window_size)));
         }
-        else if (self.alpha * window_size / 2.0) <= sample && sample <= window_size / 2.0 {
-            return 1.0;
+        else if (self.alpha * window_size / two) <= sample && sample <= window_size / two {
+            return T::one();
         }
         else {
-            return 0.5 * (1.0 - (2.0 * PI * sample / (self.alpha * window_size)));
+            return half * (T::one() - (two * T::PI() * sample / (self.alpha * window_size)));
         }
     }
 }
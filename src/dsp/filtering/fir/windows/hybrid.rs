@@ -1,21 +1,24 @@
-use std::f32::consts::PI;
+use crate::pipeline::api::ValidFloat;
 use super::window::*;
 
 
-pub struct BartlettHann {
-    coefficients: [f32; 3]
+pub struct BartlettHann<T: ValidFloat = f32> {
+    coefficients: [T; 3]
 }
-impl BartlettHann {
+impl<T: ValidFloat> BartlettHann<T> {
     pub fn new() -> Self {
-        let coefficients: [f32; 3] = [0.62, 0.48, 0.38];
+        let coefficients: [T; 3] = [T::constant(0.62), T::constant(0.48), T::constant(0.38)];
         BartlettHann {coefficients}
     }
 }
-impl WindowFunction for BartlettHann {
-    fn window_function(&self, sample: u32, window_size: usize) -> f32 {
+impl<T: ValidFloat> WindowFunction<T> for BartlettHann<T> {
+    fn window_function(&self, sample: u32, window_size: usize) -> T {
+        let sample = T::constant(sample as f64);
+        let window_size = T::constant(window_size as f64);
+
         return self.coefficients[0] - 
-            (self.coefficients[1] * ((sample as f32 / window_size as f32) - 0.5).abs()) - 
-            (self.coefficients[2] * (2.0 * PI * sample as f32 / window_size as f32).cos())
+            (self.coefficients[1] * ((sample / window_size) - T::constant(0.5)).abs()) - 
+            (self.coefficients[2] * (T::constant(2.0) * T::PI() * sample / window_size).cos())
     }
 }
 
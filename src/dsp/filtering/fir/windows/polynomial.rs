@@ -1,27 +1,29 @@
+use crate::pipeline::api::ValidFloat;
 use super::window::*;
 
 
 pub struct RectangularWindow {}
-impl WindowFunction for RectangularWindow {
-    fn window_function(&self, sample: u32, window_size: usize) -> f32 {
-        return 1.0;
+impl<T: ValidFloat> WindowFunction<T> for RectangularWindow {
+    fn window_function(&self, sample: u32, window_size: usize) -> T {
+        return T::one();
     }
 }
 
 
 pub struct TriangularWindow {}
-impl WindowFunction for TriangularWindow {
-    fn window_function(&self, sample: u32, window_size: usize) -> f32 {
-        return 1.0 - ((sample as f32 - (window_size as f32 / 2.0)) / (window_size as f32 / 2.0)).abs();
+impl<T: ValidFloat> WindowFunction<T> for TriangularWindow {
+    fn window_function(&self, sample: u32, window_size: usize) -> T {
+        let size_by_2 = T::constant(window_size as f64) / T::constant(2.0);
+        return T::one() - ((T::constant(sample as f64) - size_by_2) / size_by_2).abs();
     }
 }
 
 
 pub struct WelchWindow {}
-impl WindowFunction for WelchWindow {
-    fn window_function(&self, sample: u32, window_size: usize) -> f32 {
-        let size_by_2 = window_size as f32 / 2.0;
-        let inner_term = (sample as f32 - size_by_2) / size_by_2;
-        return 1.0 - (inner_term).powf(2.0);
+impl<T: ValidFloat> WindowFunction<T> for WelchWindow {
+    fn window_function(&self, sample: u32, window_size: usize) -> T {
+        let size_by_2 = T::constant(window_size as f64) / T::constant(2.0);
+        let inner_term = (T::constant(sample as f64) - size_by_2) / size_by_2;
+        return T::one() - (inner_term).powf(T::constant(2.0));
     }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use std::f32::consts::PI;
+use crate::pipeline::api::ValidFloat;
 use super::window::*;
 
 
@@ -8,24 +8,24 @@ pub enum RaisedCosineType {
 }
 
 
-pub struct RaisedCosineWindow {
-    cosine_coefficient: f32
+pub struct RaisedCosineWindow<T: ValidFloat = f32> {
+    cosine_coefficient: T
 }
-impl RaisedCosineWindow {
+impl<T: ValidFloat> RaisedCosineWindow<T> {
     fn new(cosine_type: RaisedCosineType) -> Self {
         let cosine_coefficient = 
             match cosine_type {
-                RaisedCosineType::Hann => 0.5,
-                RaisedCosineType::Hamming => 25.0 / 46.0
+                RaisedCosineType::Hann => T::constant(0.5),
+                RaisedCosineType::Hamming => T::constant(25.0 / 46.0)
             };
 
         return RaisedCosineWindow {cosine_coefficient};
     }
 }
-impl WindowFunction for RaisedCosineWindow {
-    fn window_function(&self, sample: u32, window_size: usize) -> f32 {
-        let cosine_term = ((2.0 * PI * sample as f32) / window_size as f32).cos();
-        return self.cosine_coefficient - ((1.0 - self.cosine_coefficient) * cosine_term);
+impl<T: ValidFloat> WindowFunction<T> for RaisedCosineWindow<T> {
+    fn window_function(&self, sample: u32, window_size: usize) -> T {
+        let cosine_term = ((T::constant(2.0) * T::PI() * T::constant(sample as f64)) / T::constant(window_size as f64)).cos();
+        return self.cosine_coefficient - ((T::one() - self.cosine_coefficient) * cosine_term);
     }
 }
 
@@ -43,12 +43,12 @@ pub enum CosineSumType {
 }
 
 
-pub struct CosineSumWindow {
-    coefficients: Vec<f32>,
+pub struct CosineSumWindow<T: ValidFloat = f32> {
+    coefficients: Vec<T>,
 }
-impl CosineSumWindow {
+impl<T: ValidFloat> CosineSumWindow<T> {
     pub fn new(cosine_sum_type: CosineSumType) -> Self {
-        let coefficients: Vec<f32> = match cosine_sum_type {
+        let coefficients: Vec<f64> = match cosine_sum_type {
             CosineSumType::Blackman => vec![0.42, 0.5, 0.08],
             CosineSumType::Nuttall => vec![0.355768, 0.487396, 0.144232, 0.012604],
             CosineSumType::BlackmanNuttall => vec![0.3635819, 0.4891775, 0.1365995, 0.0106411],
@@ -59,17 +59,18 @@ impl CosineSumWindow {
             CosineSumType::SixthPower => vec![0.3125, 0.46875, 0.1875, 0.03125],
             CosineSumType::EighthPower => vec![0.2734375, 0.4375, 0.21875, 0.0625, 7.8125E-3]
         };
+        let coefficients = coefficients.into_iter().map(T::constant).collect();
 
         return CosineSumWindow { coefficients };
     }
 }
-impl WindowFunction for CosineSumWindow {
-    fn window_function(&self, sample: u32, window_size: usize) -> f32 {
+impl<T: ValidFloat> WindowFunction<T> for CosineSumWindow<T> {
+    fn window_function(&self, sample: u32, window_size: usize) -> T {
         let mut sample_result = self.coefficients[0];
         let mut subtract = true;
 
         for (index, coefficient) in self.coefficients.iter().enumerate().skip(0) {
-            let term = coefficient * ((2.0 * index as f32 * PI * sample as f32) / window_size as f32).cos();
+            let term = *coefficient * ((T::constant(2.0 * index as f64) * T::PI() * T::constant(sample as f64)) / T::constant(window_size as f64)).cos();
             if subtract {
                 sample_result -= term;
                 subtract = false;
@@ -86,9 +87,9 @@ impl WindowFunction for CosineSumWindow {
 
 
 pub struct SineWindow {}
-impl WindowFunction for SineWindow {
-    fn window_function(&self, sample: u32, window_size: usize) -> f32 {
-        return (PI * sample as f32 / window_size as f32).sin();
+impl<T: ValidFloat> WindowFunction<T> for SineWindow {
+    fn window_function(&self, sample: u32, window_size: usize) -> T {
+        return (T::PI() * T::constant(sample as f64) / T::constant(window_size as f64)).sin();
     }
 }
 
@@ -1,13 +1,16 @@
-pub trait WindowFunction {
-    fn window_function(&self, sample: u32, window_size: usize) -> f32;
+use crate::pipeline::api::ValidFloat;
+
+
+pub trait WindowFunction<T: ValidFloat = f32> {
+    fn window_function(&self, sample: u32, window_size: usize) -> T;
 }
 
-pub fn apply_window<T: WindowFunction>(impulse_response: Vec<f32>, window: T) -> Vec<f32> { // apply window to ideal impulse response in time domain
+pub fn apply_window<T: ValidFloat, W: WindowFunction<T>>(impulse_response: Vec<T>, window: W) -> Vec<T> { // apply window to ideal impulse response in time domain
     let mut windowed_samples = Vec::new();
 
     for (index, sample) in impulse_response.iter().enumerate() {
         let impulse_response_size = impulse_response.len();
-        windowed_samples.push(sample * window.window_function(index as u32, impulse_response_size));
+        windowed_samples.push(*sample * window.window_function(index as u32, impulse_response_size));
     }
 
     return windowed_samples;
@@ -1,6 +1,7 @@
 // need a way to represent characteristics of an IIR filter
 
 use std::collections::VecDeque;
+use crate::pipeline::api::{Checkpointable, ByteReader, ByteWriter, ValidFloat};
 
 
 pub enum FilterType {
@@ -19,26 +20,26 @@ pub struct FilterParameters {
 }
 
 
-pub struct ZDomainCoefficients {
+pub struct ZDomainCoefficients<T: ValidFloat = f32> {
     pub order: u8,
-    pub numerator: Vec<f32>, // organized in ascending order: z^0 ... z^-n
-    pub denominator: Vec<f32>
+    pub numerator: Vec<T>, // organized in ascending order: z^0 ... z^-n
+    pub denominator: Vec<T>
 }
 
 
-pub struct IIRFilterRunner {
-    previous_inputs: VecDeque<f32>,
-    previous_outputs: VecDeque<f32>,
-    z_domain_coefficients: ZDomainCoefficients
+pub struct IIRFilterRunner<T: ValidFloat = f32> { // run high order designs in f64, the feedback terms accumulate f32 rounding error quickly
+    previous_inputs: VecDeque<T>,
+    previous_outputs: VecDeque<T>,
+    z_domain_coefficients: ZDomainCoefficients<T>
 }
 
-impl IIRFilterRunner {
-    pub fn new(coefficients: ZDomainCoefficients) -> Self {
-        let mut input_deque: VecDeque<f32> = VecDeque::new();
-        let mut output_deque: VecDeque<f32> = VecDeque::new();
+impl<T: ValidFloat> IIRFilterRunner<T> {
+    pub fn new(coefficients: ZDomainCoefficients<T>) -> Self {
+        let mut input_deque: VecDeque<T> = VecDeque::new();
+        let mut output_deque: VecDeque<T> = VecDeque::new();
 
-        IIRFilterRunner::zero_convolutional_buffers(&mut input_deque, &(coefficients.numerator.len() - 1));
-        IIRFilterRunner::zero_convolutional_buffers(&mut output_deque, &(coefficients.denominator.len() - 1));
+        Self::zero_convolutional_buffers(&mut input_deque, &(coefficients.numerator.len() - 1));
+        Self::zero_convolutional_buffers(&mut output_deque, &(coefficients.denominator.len() - 1));
 
         IIRFilterRunner {
             previous_inputs: input_deque,
@@ -47,19 +48,19 @@ impl IIRFilterRunner {
         }
     }
 
-    fn zero_convolutional_buffers(deque: &mut VecDeque<f32>, size: &usize) {
+    fn zero_convolutional_buffers(deque: &mut VecDeque<T>, size: &usize) {
         deque.clear();
 
         let mut index = 0;
 
         while index < *size {
-            deque.push_back(0.0);
+            deque.push_back(T::zero());
             index += 1;
         }
     }
 
-    fn apply_iir_coefficients(&self, input_point: &f32) -> f32 {
-        let mut complete_value = self.z_domain_coefficients.numerator[0] * input_point;
+    fn apply_iir_coefficients(&self, input_point: &T) -> T {
+        let mut complete_value = self.z_domain_coefficients.numerator[0] * *input_point;
 
         for(index, nume_deno_coefficients) in 
             self.z_domain_coefficients.numerator
@@ -70,15 +71,15 @@ impl IIRFilterRunner {
             
             let (numerator, denominator) = nume_deno_coefficients;
 
-            complete_value += (self.previous_inputs[index - 1] * numerator) + (self.previous_outputs[index - 1] * denominator);
+            complete_value += (self.previous_inputs[index - 1] * *numerator) + (self.previous_outputs[index - 1] * *denominator);
         }
 
         return complete_value;
     }
 
-    pub fn run_iir_filter(&mut self, input: Vec<f32>) -> Vec<f32> {
+    pub fn run_iir_filter(&mut self, input: Vec<T>) -> Vec<T> {
         let mut filter_value;
-        let mut filtered_output: Vec<f32> = Vec::with_capacity(input.len());
+        let mut filtered_output: Vec<T> = Vec::with_capacity(input.len());
 
         for input_point in input.iter() {
 
@@ -103,4 +104,21 @@ impl IIRFilterRunner {
 pub trait Filter {
     fn find_order(&mut self) -> f32;
     fn find_gain(&mut self) -> f32;
-}
\ No newline at end of file
+}
+
+impl<T: ValidFloat> Checkpointable for IIRFilterRunner<T> {
+    // the delay lines, saved as f64 so a checkpoint of an f32 runner can be restored into an f64 one and the other way round
+    fn save_state(&self, state: &mut ByteWriter) {
+        state.put_f64s(&self.previous_inputs.iter().map(|value| value.to_f64().unwrap()).collect::<Vec<f64>>());
+        state.put_f64s(&self.previous_outputs.iter().map(|value| value.to_f64().unwrap()).collect::<Vec<f64>>());
+    }
+    fn restore_state(&mut self, state: &mut ByteReader) -> Result<(), String> {
+        let (previous_inputs, previous_outputs) = (state.f64s()?, state.f64s()?);
+        if previous_inputs.len() != self.previous_inputs.len() || previous_outputs.len() != self.previous_outputs.len() {
+            return Err(format!("delay lines of {} and {} values do not fit a filter of order {}", previous_inputs.len(), previous_outputs.len(), self.z_domain_coefficients.order));
+        }
+        self.previous_inputs = previous_inputs.into_iter().map(T::constant).collect();
+        self.previous_outputs = previous_outputs.into_iter().map(T::constant).collect();
+        Ok(())
+    }
+}
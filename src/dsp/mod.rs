@@ -6,4 +6,5 @@ pub mod classification;
 pub mod system_response;
 pub mod pll;
 pub mod core;
-pub mod casting;
\ No newline at end of file
+pub mod casting;
+pub mod registry;
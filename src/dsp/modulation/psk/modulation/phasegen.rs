@@ -1,5 +1,4 @@
 use crate::pipeline::api::*;
-use std::f32;
 
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -10,31 +9,32 @@ pub enum PSKPoint {
 }
 
 #[derive(Clone, PartialEq, Debug)]
-pub struct PhaseVectorGenerator {
+pub struct PhaseVectorGenerator<T: ValidFloat = f32> {
     point: PSKPoint,
-    symbol_table: Vec<f32>, // only needs a vec since they are indexed in order anyway
+    symbol_table: Vec<T>, // only needs a vec since they are indexed in order anyway
     byte_unpack_window: u8,
     byte_unpack_window_size: u8
 }
 
-impl PhaseVectorGenerator {
+impl<T: ValidFloat> PhaseVectorGenerator<T> {
     // maximum phase must be in RADIANS!
-    fn generate_symbol_table(point: PSKPoint, maximum_phase: f32) -> Vec<f32> { // point corresponds to how many poles the psk has. you can encode log_2(n) symbols for n poles
+    fn generate_symbol_table(point: PSKPoint, maximum_phase: T) -> Vec<T> { // point corresponds to how many poles the psk has. you can encode log_2(n) symbols for n poles
         let mut lookup_table = Vec::with_capacity(point.clone() as usize);
 
-        let mut current_symbol: f32 = 0.0;
-        let phase_interval =  maximum_phase / (point as i32 as f32 - 1.0);
+        let point_count = T::constant(point as i32 as f64);
+        let mut current_symbol: T = T::zero();
+        let phase_interval =  maximum_phase / (point_count - T::one());
 
-        while current_symbol < point as i32 as f32 {
+        while current_symbol < point_count {
             lookup_table.push(current_symbol * phase_interval);
-            current_symbol += 1.0;
+            current_symbol += T::one();
         }
 
         return lookup_table;
     }
 
-    pub fn new(point: PSKPoint , maximum_phase: f32) -> PhaseVectorGenerator {// 1 symbol is 1 full period, can be caluclated
-        let symbol_table = PhaseVectorGenerator::generate_symbol_table(point, maximum_phase);
+    pub fn new(point: PSKPoint , maximum_phase: T) -> PhaseVectorGenerator<T> {// 1 symbol is 1 full period, can be caluclated
+        let symbol_table = Self::generate_symbol_table(point, maximum_phase);
         let _table_size = symbol_table.len();
 
         PhaseVectorGenerator {
@@ -45,14 +45,14 @@ impl PhaseVectorGenerator {
         }
     }
 
-    fn unpack_byte(&self, mut byte: u8, phase_buffer: &mut Vec<f32>) {
+    fn unpack_byte(&self, mut byte: u8, phase_buffer: &mut Vec<T>) {
         let mut byte_index: u8 = 0;
 
         while byte_index < (8 / (self.point as u8 / 2)) {
             let unpacked_baseband_datapoint: usize = (byte & self.byte_unpack_window) as usize;
             byte = byte >> self.byte_unpack_window_size;
 
-            let acquired_phase: &f32 = self.symbol_table.get(unpacked_baseband_datapoint).unwrap();
+            let acquired_phase: &T = self.symbol_table.get(unpacked_baseband_datapoint).unwrap();
 
             phase_buffer.push(acquired_phase.clone());
 
@@ -60,8 +60,8 @@ impl PhaseVectorGenerator {
         }
     }
 
-    fn generate_phase(&self, input: &Vec<u8>) -> Vec<f32> {
-        let mut psk_buffer: Vec<f32> = Vec::with_capacity(((input.len() as u32) * (8 / self.byte_unpack_window_size) as u32) as usize);
+    fn generate_phase(&self, input: &Vec<u8>) -> Vec<T> {
+        let mut psk_buffer: Vec<T> = Vec::with_capacity(((input.len() as u32) * (8 / self.byte_unpack_window_size) as u32) as usize);
 
         for data_byte in input.iter() {
             self.unpack_byte(data_byte.clone(), &mut psk_buffer);
@@ -73,8 +73,10 @@ impl PhaseVectorGenerator {
     }
 }
 
-impl PipelineStep<Vec<u8>, Vec<f32>> for PhaseVectorGenerator {
-    fn run_SISO(&mut self, input: Vec<u8>) -> Result<ODFormat<Vec<f32>>, String> {
+impl<T: ValidFloat> PipelineStep<Vec<u8>, Vec<T>> for PhaseVectorGenerator<T> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: Vec<u8>) -> Result<ODFormat<Vec<T>>, StepError> {
         Ok(ODFormat::Standard(self.generate_phase(&input)))
     }
 }
\ No newline at end of file
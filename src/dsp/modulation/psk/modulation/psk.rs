@@ -1,5 +1,4 @@
 use crate::pipeline::api::*;
-use std::f32;
 
 
 #[derive(Clone, PartialEq, Debug)]
@@ -10,41 +9,41 @@ pub enum BasisType {
 
 
 #[derive(Clone, PartialEq, Debug)]
-pub struct PSKModulator {
-    samples_per_symbol: f32,
+pub struct PSKModulator<T: ValidFloat = f32> {
+    samples_per_symbol: T,
     basis_type: BasisType
 }
 
-impl PSKModulator {
-    pub fn new(samples_per_symbol: f32, basis_type: BasisType) -> Self {
+impl<T: ValidFloat> PSKModulator<T> {
+    pub fn new(samples_per_symbol: T, basis_type: BasisType) -> Self {
         PSKModulator {
             samples_per_symbol,
             basis_type
         }
     }
 
-    fn execute_basis(&self, time_value: f32) -> f32 {
+    fn execute_basis(&self, time_value: T) -> T {
         match &self.basis_type {
-            BasisType::COSINE => f32::cos(time_value),
-            BasisType::SINE => f32::sin(time_value)
+            BasisType::COSINE => time_value.cos(),
+            BasisType::SINE => time_value.sin()
         }
     }
 
-    fn generate_symbol(&self, phase_offset: &f32, output_buffer: &mut Vec<f32>) {
-        let mut index: f32 = 0.0;
-        let mut time_x_value = 0.0;
-        let time_interval = 2.0 * f32::consts::PI / (self.samples_per_symbol - 1.0);
+    fn generate_symbol(&self, phase_offset: &T, output_buffer: &mut Vec<T>) {
+        let mut index: T = T::zero();
+        let mut time_x_value = T::zero();
+        let time_interval = T::constant(2.0) * T::PI() / (self.samples_per_symbol - T::one());
 
-        while index < self.samples_per_symbol - 1.0 {
-            output_buffer.push(self.execute_basis(time_x_value + phase_offset));
+        while index < self.samples_per_symbol - T::one() {
+            output_buffer.push(self.execute_basis(time_x_value + *phase_offset));
 
-            index += 1.0;
+            index += T::one();
             time_x_value += time_interval;
         }
     }
 
-    fn psk_modulate(&self, input: &Vec<f32>) -> Vec<f32> {
-        let mut output_buffer: Vec<f32> = Vec::with_capacity(input.len() * self.samples_per_symbol as usize);
+    fn psk_modulate(&self, input: &Vec<T>) -> Vec<T> {
+        let mut output_buffer: Vec<T> = Vec::with_capacity(input.len() * self.samples_per_symbol.to_usize().unwrap());
 
         for input_phase in input.iter() {
             self.generate_symbol(input_phase, &mut output_buffer);
@@ -54,8 +53,10 @@ impl PSKModulator {
     }
 }
 
-impl PipelineStep<Vec<f32>, Vec<f32>> for PSKModulator {
-    fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<Vec<f32>>, String> {
+impl<T: ValidFloat> PipelineStep<Vec<T>, Vec<T>> for PSKModulator<T> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: Vec<T>) -> Result<ODFormat<Vec<T>>, StepError> {
         Ok(ODFormat::Standard(self.psk_modulate(&input)))
     }
 }
\ No newline at end of file
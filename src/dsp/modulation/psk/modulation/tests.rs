@@ -8,7 +8,7 @@ pub mod psk_tests {
     #[test]
     fn phasegen_test() {
         let mut phase_vector_generator: PhaseVectorGenerator = PhaseVectorGenerator::new(PSKPoint::BPSK, PI);
-        let result: Result<ODFormat<Vec<f32>>, String> = phase_vector_generator.run_SISO(vec![210]);
+        let result: Result<ODFormat<Vec<f32>>, StepError> = phase_vector_generator.run_SISO(vec![210]);
 
         let golden_reference = vec![0.0, PI, 0.0, 0.0, PI, 0.0, PI, PI];
         
@@ -19,7 +19,7 @@ pub mod psk_tests {
         }
 
         let mut phase_vector_generator: PhaseVectorGenerator = PhaseVectorGenerator::new(PSKPoint::QPSK, 3.0 * PI / 2.0);
-        let result: Result<ODFormat<Vec<f32>>, String> = phase_vector_generator.run_SISO(vec![210]);
+        let result: Result<ODFormat<Vec<f32>>, StepError> = phase_vector_generator.run_SISO(vec![210]);
 
         let golden_reference = vec![PI, 0.0, PI / 2.0, 3.0 * PI / 2.0];
 
@@ -33,7 +33,7 @@ pub mod psk_tests {
     #[test]
     fn psk_test() {
         let mut phase_vector_generator: PhaseVectorGenerator = PhaseVectorGenerator::new(PSKPoint::BPSK, PI);
-        let result: Result<ODFormat<Vec<f32>>, String> = phase_vector_generator.run_SISO(vec![210]);
+        let result: Result<ODFormat<Vec<f32>>, StepError> = phase_vector_generator.run_SISO(vec![210]);
 
         let result = result.unwrap();
         let result = match result {
@@ -24,7 +24,9 @@ impl VCO {
 }
 
 impl PipelineStep<Vec<f32>, Vec<f32>> for VCO {
-    fn run_SISO(&mut self, mut input: Vec<f32>) -> Result<ODFormat<Vec<f32>>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, mut input: Vec<f32>) -> Result<ODFormat<Vec<f32>>, StepError> {
         self.process_input_vector(&mut input);
         Ok(ODFormat::Standard(input))
     }
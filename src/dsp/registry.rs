@@ -0,0 +1,45 @@
+use num::Complex;
+use crate::pipeline::api::*;
+use crate::pipeline::sources::audio_file_source::AudioFileSource;
+use crate::pipeline::sources::sinusoid::SinusoidalSource;
+use super::casting::{ComplexCaster, RealCaster};
+use super::core::pointwise_arithmetic::{PointwiseAdder, PointwiseSubtractor, PointwiseMultiplier, PointwiseDivider};
+use super::core::power::{PowerCalculatorTD, PowerCalculatorFD, PowerAtFrequency};
+use super::fft::bit_reversal_optimized::FFTBitReversal;
+use super::sampling::decimation::Decimator;
+use super::sampling::upsample::Upsampler;
+use super::system_response::discrete_td_convolution::DiscreteConvolution;
+use super::system_response::system_functions::ImpulseResponse;
+
+
+pub fn register_default_steps(registry: &mut StepRegistry) {
+    // the names used for the built in steps in pipeline definition files
+    registry.register::<(), Vec<f32>, _>("sinusoid", |params| Ok(SinusoidalSource::new(params.get_f32("frequency")?, params.get_f32("sampling_frequency")?, params.get_f32("phase")?, params.get_usize("buffer_size")?)));
+    registry.register::<(), Vec<f32>, _>("audio_file", |params| Ok(AudioFileSource::new(&params.get_string("filename")?, params.get_usize("buffer_size")?, params.get_usize("read_retries")?)));
+
+    registry.register::<Vec<f32>, Vec<Complex<f32>>, _>("complex_cast", |_| Ok(ComplexCaster {}));
+    registry.register::<Vec<Complex<f32>>, Vec<f32>, _>("real_cast", |_| Ok(RealCaster {}));
+
+    registry.register::<Vec<f32>, Vec<f32>, _>("pointwise_add", |params| Ok(PointwiseAdder::new(params.get_f32("coefficient")?)));
+    registry.register::<Vec<f32>, Vec<f32>, _>("pointwise_subtract", |_| Ok(PointwiseSubtractor {}));
+    registry.register::<Vec<f32>, Vec<f32>, _>("pointwise_multiply", |_| Ok(PointwiseMultiplier {}));
+    registry.register::<Vec<f32>, Vec<f32>, _>("pointwise_divide", |_| Ok(PointwiseDivider {}));
+
+    registry.register::<Vec<f32>, f32, _>("power_td", |_| Ok(PowerCalculatorTD {}));
+    registry.register::<Vec<Complex<f32>>, f32, _>("power_fd", |_| Ok(PowerCalculatorFD {}));
+    registry.register::<Vec<Complex<f32>>, f32, _>("power_at_frequency", |params| Ok(PowerAtFrequency::new(params.get_f32("frequency")?, params.get_f32("sample_rate")?, params.get_usize("buffer_size")?)));
+
+    registry.register::<Vec<Complex<f32>>, Vec<Complex<f32>>, _>("fft", |params| {
+        let is_ifft = if params.contains("is_ifft") { params.get_bool("is_ifft")? } else { false };
+        FFTBitReversal::new(params.get_usize("buffer_size")?, is_ifft)
+    });
+
+    registry.register::<Vec<f32>, Vec<f32>, _>("decimator", |params| Decimator::new(params.get_usize("sampling_period")? as u32));
+    registry.register::<Vec<f32>, Vec<f32>, _>("upsampler", |params| Upsampler::new(params.get_usize("upsample_factor")?));
+
+    registry.register::<Vec<f32>, Vec<f32>, _>("convolution", |params| {
+        let impulse_response = params.get_f32_vec("impulse_response")?;
+        let impulse_response_length = impulse_response.len();
+        DiscreteConvolution::new(params.get_usize("input_size")?, impulse_response_length, Some(ImpulseResponse::new_configured(impulse_response)))
+    });
+}
@@ -4,8 +4,11 @@ pub struct Decimator {
     sampling_period: u32 // every nth sample is kept
 }
 impl Decimator {
-    pub fn new(sampling_period: u32) -> Decimator {
-        Decimator { sampling_period }
+    pub fn new(sampling_period: u32) -> Result<Decimator, String> {
+        if sampling_period == 0 {
+            return Err(String::from("the sampling period must be at least 1"));
+        }
+        Ok(Decimator { sampling_period })
     }
     pub fn decimate(&self, samples: &Vec<f32>) -> Vec<f32> {
         let mut output_vector = Vec::with_capacity(samples.len() / self.sampling_period as usize);
@@ -18,7 +21,11 @@ impl Decimator {
     }
 }
 impl PipelineStep<Vec<f32>, Vec<f32>> for Decimator {
-    fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<Vec<f32>>, String> { 
-        Ok(ODFormat::Standard(input))
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<Vec<f32>>, StepError> { 
+        Ok(ODFormat::Standard(self.decimate(&input)))
     }
+
+    fn tag_rate(&self) -> (usize, usize) { (1, self.sampling_period as usize) }
 }
\ No newline at end of file
@@ -6,10 +6,13 @@ pub struct Upsampler {
     upsample_factor: usize // there will be n * upsample_factor samples after. For every sample add upsample_factor - 1 samples
 }
 impl Upsampler { // note, I only insert the 0 samples. Still need a low pass filter
-    pub fn new(upsample_factor: usize) -> Upsampler {
-        Upsampler {
-            upsample_factor
+    pub fn new(upsample_factor: usize) -> Result<Upsampler, String> {
+        if upsample_factor == 0 {
+            return Err(String::from("the upsample factor must be at least 1"));
         }
+        Ok(Upsampler {
+            upsample_factor
+        })
     }
     
     pub fn insert_0_samples(&self, samples: &Vec<f32>) -> Vec<f32> {
@@ -24,7 +27,11 @@ impl Upsampler { // note, I only insert the 0 samples. Still need a low pass fil
     }
 }
 impl PipelineStep<Vec<f32>, Vec<f32>> for Upsampler {
-    fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<Vec<f32>>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<Vec<f32>>, StepError> {
         Ok(ODFormat::Standard(self.insert_0_samples(&input)))
     }
+
+    fn tag_rate(&self) -> (usize, usize) { (self.upsample_factor, 1) }
 }
\ No newline at end of file
@@ -1,17 +1,22 @@
 use crate::dsp::fft::bit_reversal_optimized::*;
 use crate::pipeline::api::*;
+use std::fmt;
+use std::marker::PhantomData;
 use num::Complex;
+use rustfft::FftPlanner;
+use crate::dsp::casting::{ComplexCaster, RealCaster};
 use crate::dsp::system_response::system_functions::{ImpulseResponse, TransferFunction};
 
-pub struct OverlapAddChunker {
+pub struct OverlapAddChunker<T: ValidFloat = f32> {
     input_size: usize,
     chunk_size: usize,
     padding_size: usize,
     num_chunks: usize,
+    sample_type: PhantomData<T>
 }
 
 
-impl OverlapAddChunker { // take in vec and turn it to vec of vec for overlap save system_response
+impl<T: ValidFloat> OverlapAddChunker<T> { // take in vec and turn it to vec of vec for overlap save system_response
     pub fn new(input_size: usize, impulse_response_size: usize, chunk_size: usize) -> Self {
         assert_eq!(input_size % chunk_size, 0);
         assert!(impulse_response_size <= chunk_size);
@@ -22,38 +27,55 @@ impl OverlapAddChunker { // take in vec and turn it to vec of vec for overlap sa
             chunk_size: chunk_size,
             padding_size: impulse_response_size - 1,
             num_chunks: input_size / chunk_size,
+            sample_type: PhantomData
         }
     }
-    fn generate_chunks(&self, mut data: Vec<f32>) -> Vec<Vec<f32>> {
-        let mut output: Vec<Vec<f32>> = vec![Vec::with_capacity(self.chunk_size + self.padding_size); self.num_chunks];
-        let chunked_input = data.chunks_mut(self.chunk_size);
+    fn generate_chunks(&self, data: Vec<T>) -> Vec<Vec<T>> {
+        data.chunks(self.chunk_size)
+            .map(|input_chunk| {
+                let mut output_chunk = Vec::with_capacity(self.chunk_size + self.padding_size);
+                output_chunk.extend_from_slice(input_chunk);
+                output_chunk.extend(vec![T::zero(); self.padding_size]);
+                output_chunk
+            })
+            .collect()
+    }
+}
 
-        for (input_chunk, output_chunk) in chunked_input.zip(output.iter_mut()) {
-            for index in 0..self.chunk_size {
-                output_chunk[index] = input_chunk[index];
-            }
+impl<T: ValidFloat> PipelineStep<Vec<T>, Vec<Vec<T>>> for OverlapAddChunker<T> { // need some way for elegant chunk processing. This is something ill be doing often
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
 
-            output_chunk.extend(vec![0.0; self.padding_size]);
-        }
-        
-        output
+    fn run_SISO(&mut self, input: Vec<T>) -> Result<ODFormat<Vec<Vec<T>>>, StepError> {
+        Ok(ODFormat::Standard(self.generate_chunks(input)))
     }
 }
 
-impl PipelineStep<Vec<f32>, Vec<Vec<f32>>> for OverlapAddChunker { // need some way for elegant chunk processing. This is something ill be doing often
-    fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<Vec<Vec<f32>>>, String> {
-        Ok(ODFormat::Standard(self.generate_chunks(input)))
+
+pub struct ChunkSeries<T: ValidFloat = f32> { // sends every chunk on its own as a series, so the steps after it run per chunk until a reassembler
+    sample_type: PhantomData<T>
+}
+impl<T: ValidFloat> ChunkSeries<T> {
+    pub fn new() -> Self {
+        Self { sample_type: PhantomData }
+    }
+}
+impl<T: ValidFloat> PipelineStep<Vec<Vec<T>>, Vec<T>> for ChunkSeries<T> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: Vec<Vec<T>>) -> Result<ODFormat<Vec<T>>, StepError> {
+        Ok(ODFormat::Series(input))
     }
 }
 
 
-pub struct OverlapAddCombiner {
+pub struct OverlapAddCombiner<T: ValidFloat = f32> {
     input_size: usize,
     chunk_size: usize,
     padding_size: usize,
     num_chunks: usize,
+    tail: Vec<T>, // the part of the last block which overlaps the next one, emitted by flush once the stream ends
 }
-impl OverlapAddCombiner {
+impl<T: ValidFloat> OverlapAddCombiner<T> {
     pub fn new(input_size: usize, impulse_response_size: usize, chunk_size: usize) -> Self {
         assert_eq!(input_size % chunk_size, 0);
         assert!(impulse_response_size <= chunk_size);
@@ -64,9 +86,10 @@ impl OverlapAddCombiner {
             chunk_size: chunk_size,
             padding_size: impulse_response_size - 1,
             num_chunks: input_size / chunk_size,
+            tail: Vec::new(),
         }
     }
-    fn recombine_chunks(&self, mut data: Vec<Vec<f32>>) -> Vec<f32> {
+    fn recombine_chunks(&self, mut data: Vec<Vec<T>>) -> Vec<T> {
         let mut output = Vec::with_capacity(self.num_chunks * self.chunk_size);
         
         output.extend_from_slice(data[0].as_slice());
@@ -85,21 +108,43 @@ impl OverlapAddCombiner {
         
         output
     }
+    fn overlap_previous_block(&mut self, mut output: Vec<T>) -> Vec<T> {
+        // the tail of the previous block is added onto the start of this one, and this block's own tail is held back for the next
+        for (output_value, tail_value) in output.iter_mut().zip(self.tail.iter()) {
+            *output_value += *tail_value;
+        }
+        self.tail = output.split_off(output.len() - self.padding_size);
+        output
+    }
 }
-impl PipelineStep<Vec<f32>, Vec<f32>> for OverlapAddCombiner { // need some way for elegant chunk processing. This is something ill be doing often
-    fn run_REASO(&mut self, input: Vec<Vec<f32>>) -> Result<ODFormat<Vec<f32>>, String> {
-        Ok(ODFormat::Standard(self.recombine_chunks(input)))   
+impl<T: ValidFloat> PipelineStep<Vec<T>, Vec<T>> for OverlapAddCombiner<T> { // need some way for elegant chunk processing. This is something ill be doing often
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::REASO] }
+
+    fn run_REASO(&mut self, input: Vec<Vec<T>>) -> Result<ODFormat<Vec<T>>, StepError> {
+        let output = self.recombine_chunks(input);
+        Ok(ODFormat::Standard(self.overlap_previous_block(output)))
+    }
+
+    fn flush(&mut self) -> Option<ODFormat<Vec<T>>> {
+        match self.tail.is_empty() {
+            true => None,
+            false => Some(ODFormat::Standard(std::mem::take(&mut self.tail)))
+        }
+    }
+
+    fn reset_behavior(&mut self) {
+        self.tail.clear();
     }
 }
 
-pub struct FrequencyConvolution {
-    transfer_function: TransferFunction,
+pub struct FrequencyConvolution<T: ValidFloat = f32> {
+    transfer_function: TransferFunction<T>,
 }
-impl FrequencyConvolution {
-    pub fn new(transfer_function: TransferFunction) -> Self {
+impl<T: ValidFloat> FrequencyConvolution<T> {
+    pub fn new(transfer_function: TransferFunction<T>) -> Self {
         Self { transfer_function }
     }
-    fn convolve(&self, mut data: Vec<Complex<f32>>) -> Vec<Complex<f32>> {
+    fn convolve(&self, mut data: Vec<Complex<T>>) -> Vec<Complex<T>> {
         for (sample, transfer_sample) in data.iter_mut().zip(self.transfer_function.transfer_function.iter()) {
             *sample = *sample * *transfer_sample;
         }
@@ -107,8 +152,76 @@ impl FrequencyConvolution {
         data
     }
 }
-impl PipelineStep<Vec<Complex<f32>>, Vec<Complex<f32>>> for  FrequencyConvolution {
-    fn run_SISO(&mut self, input: Vec<Complex<f32>>) -> Result<ODFormat<Vec<Complex<f32>>>, String> {
+impl<T: ValidFloat> PipelineStep<Vec<Complex<T>>, Vec<Complex<T>>> for  FrequencyConvolution<T> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: Vec<Complex<T>>) -> Result<ODFormat<Vec<Complex<T>>>, StepError> {
         Ok(ODFormat::Standard(self.convolve(input)))
     }
-}
\ No newline at end of file
+}
+
+
+#[derive(Debug, PartialEq)]
+pub enum OverlapAddParameterError {
+    ChunkSizeError(String),
+    ImpulseResponseError(String),
+    FftSizeError(String)
+}
+
+impl fmt::Display for OverlapAddParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ChunkSizeError(e) => write!(f, "Chunk Size Error: {}", e),
+            Self::ImpulseResponseError(e) => write!(f, "Impulse Response Error: {}", e),
+            Self::FftSizeError(e) => write!(f, "FFT Size Error: {}", e),
+        }
+    }
+}
+
+
+pub struct OverlapAddFilter<T: ValidFloat = f32> {
+    input_size: usize,
+    chunk_size: usize,
+    impulse_response_size: usize,
+    transfer_function: TransferFunction<T>,
+}
+impl<T: ValidFloat> OverlapAddFilter<T> {
+    pub fn new(input_size: usize, chunk_size: usize, impulse_response: Vec<T>) -> Result<Self, OverlapAddParameterError> {
+        // chunk_size + impulse response length - 1 is the fft size, so it must be a power of 2
+        let impulse_response_size = impulse_response.len();
+        if chunk_size == 0 || chunk_size > input_size || input_size % chunk_size != 0 {
+            return Err(OverlapAddParameterError::ChunkSizeError(format!("an input of {} samples cannot be split into whole chunks of {}", input_size, chunk_size)));
+        }
+        if impulse_response_size == 0 || impulse_response_size > chunk_size {
+            return Err(OverlapAddParameterError::ImpulseResponseError(format!("an impulse response of {} samples must be between 1 and the chunk size of {}", impulse_response_size, chunk_size)));
+        }
+        let fft_size = chunk_size + impulse_response_size - 1;
+        if !fft_size.is_power_of_two() {
+            return Err(OverlapAddParameterError::FftSizeError(format!("chunk size + impulse response size - 1 = {} is not a power of 2", fft_size)));
+        }
+
+        let mut transfer_function: Vec<Complex<T>> = impulse_response.iter().map(|x| Complex::new(*x, T::zero())).collect();
+        transfer_function.resize(fft_size, Complex::new(T::zero(), T::zero()));
+        FftPlanner::new().plan_fft_forward(fft_size).process(&mut transfer_function);
+
+        Ok(Self { input_size, chunk_size, impulse_response_size, transfer_function: TransferFunction::new_configured(transfer_function) })
+    }
+}
+impl<T: ValidFloat> PipelineRecipe<Vec<T>, Vec<T>> for OverlapAddFilter<T> {
+    type Entry = Vec<Vec<T>>;
+
+    fn construct<F: Sharable>(self, builder: NodeBuilder<Vec<T>, Vec<Vec<T>>>) -> NodeBuilder<Vec<T>, F> {
+        let fft_size = self.chunk_size + self.impulse_response_size - 1;
+
+        builder
+            .attach("chunker", OverlapAddChunker::new(self.input_size, self.impulse_response_size, self.chunk_size))
+            .attach("chunk_series", ChunkSeries::new())
+            .attach("complex_cast", ComplexCaster {})
+            .attach("fft", FFTBitReversal::<T>::new(fft_size, false).expect("the fft size is checked when the filter is configured"))
+            .attach("convolution", FrequencyConvolution::new(self.transfer_function))
+            .attach("ifft", FFTBitReversal::<T>::new(fft_size, true).expect("the fft size is checked when the filter is configured"))
+            .attach("real_cast", RealCaster {})
+            .add_reassembler(self.input_size / self.chunk_size)
+            .attach("combiner", OverlapAddCombiner::new(self.input_size, self.impulse_response_size, self.chunk_size))
+    }
+}
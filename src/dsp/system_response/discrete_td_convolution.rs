@@ -4,47 +4,50 @@ use crate::pipeline::api::*;
 use super::system_functions::ImpulseResponse;
 
 
-pub struct DiscreteConvolution {
-    impulse_response: ImpulseResponse,
+pub struct DiscreteConvolution<T: ValidFloat = f32> {
+    impulse_response: ImpulseResponse<T>,
     external_impulse_response: bool,
     input_size: usize,
-    full_input: Vec<f32>,
+    full_input: Vec<T>,
     previous_input_size: usize,
 }
 
-impl DiscreteConvolution {
-    pub fn new(input_size: usize, impulse_response_length: usize, impulse_response: Option<ImpulseResponse>) -> Self {
-        match impulse_response {
+impl<T: ValidFloat> DiscreteConvolution<T> {
+    pub fn new(input_size: usize, impulse_response_length: usize, impulse_response: Option<ImpulseResponse<T>>) -> Result<Self, String> {
+        if impulse_response_length == 0 {
+            return Err(String::from("the impulse response must hold at least one value"));
+        }
+        Ok(match impulse_response {
             Some(impulse_response) => {
                 DiscreteConvolution {
                     impulse_response,
                     external_impulse_response: false,
                     input_size,
-                    full_input: vec![0.0; input_size + impulse_response_length - 1], // optimize this crap later, no cloning in the finished product!
+                    full_input: vec![T::zero(); input_size + impulse_response_length - 1], // optimize this crap later, no cloning in the finished product!
                     previous_input_size: impulse_response_length - 1,
                 }
             }
             None => {
                 DiscreteConvolution {
-                    impulse_response: ImpulseResponse::new_configured(vec![0.0; impulse_response_length]),
+                    impulse_response: ImpulseResponse::new_configured(vec![T::zero(); impulse_response_length]),
                     external_impulse_response: true,
                     input_size,
-                    full_input: vec![0.0; input_size + impulse_response_length - 1], // optimize this crap later, no cloning in the finished product!
+                    full_input: vec![T::zero(); input_size + impulse_response_length - 1], // optimize this crap later, no cloning in the finished product!
                     previous_input_size: impulse_response_length - 1,
                 }
             }
-        }
+        })
     }
     
-    fn convolve_input(&mut self, mut input: Vec<f32>) -> Vec<f32> {
-        let mut output = vec![0.0; self.input_size];
+    fn convolve_input(&mut self, mut input: Vec<T>) -> Vec<T> {
+        let mut output = vec![T::zero(); self.input_size];
         let full_input_len = self.full_input.len();
 
         (&mut self.full_input[self.previous_input_size..]).swap_with_slice(&mut input.as_mut_slice()); // put the input into the full input efficiently
 
         for index in 0..self.input_size {
             let window = &mut self.full_input[index..index + self.impulse_response.len()];
-            output[index] = window.iter().zip(self.impulse_response.reversed_impulse_response.iter()).map(|(a, b)| a * b).sum();
+            output[index] = window.iter().zip(self.impulse_response.reversed_impulse_response.iter()).map(|(a, b)| *a * *b).sum();
         }
         
         self.full_input.reverse();
@@ -55,11 +58,13 @@ impl DiscreteConvolution {
 }
 
 
-impl PipelineStep<Vec<f32>, Vec<f32>> for DiscreteConvolution {
-    fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<Vec<f32>>, String> {
+impl<T: ValidFloat> PipelineStep<Vec<T>, Vec<T>> for DiscreteConvolution<T> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO, StepHandler::MISO] }
+
+    fn run_SISO(&mut self, input: Vec<T>) -> Result<ODFormat<Vec<T>>, StepError> {
         Ok(ODFormat::Standard(self.convolve_input(input)))
     }
-    fn run_MISO(&mut self, mut input: Vec<Vec<f32>>) -> Result<ODFormat<Vec<f32>>, String> {
+    fn run_MISO(&mut self, mut input: Vec<Vec<T>>) -> Result<ODFormat<Vec<T>>, StepError> {
         let mut impulse_response = input.pop().unwrap();
         impulse_response.reverse();
         self.impulse_response.reversed_impulse_response = impulse_response;
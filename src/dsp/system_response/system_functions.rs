@@ -1,12 +1,13 @@
 use rustfft::FftPlanner;
 use crate::dsp::fft::fftshift::fft_shift;
+use crate::pipeline::api::ValidFloat;
 use num::Complex;
 
-pub struct ImpulseResponse {
-    pub reversed_impulse_response: Vec<f32>,
+pub struct ImpulseResponse<T: ValidFloat = f32> {
+    pub reversed_impulse_response: Vec<T>,
 }
-impl ImpulseResponse {
-    pub fn new_configured(mut impulse_response: Vec<f32>) -> Self {
+impl<T: ValidFloat> ImpulseResponse<T> {
+    pub fn new_configured(mut impulse_response: Vec<T>) -> Self {
         impulse_response.reverse();
         let ir_len = impulse_response.len();
 
@@ -15,7 +16,7 @@ impl ImpulseResponse {
         }
     }
     pub fn new_from_function<F>(func: F, sample_start_n: i64, window_size: usize) -> Self
-    where F: Fn(i64) -> f32 {
+    where F: Fn(i64) -> T {
         let mut impulse_response = Vec::with_capacity(window_size);
         for index in sample_start_n..sample_start_n + window_size as i64 {
             impulse_response.push(func(index));
@@ -26,19 +27,19 @@ impl ImpulseResponse {
         }
     }
 
-    pub fn transfer_function(mut self, padding: usize) -> TransferFunction {
+    pub fn transfer_function(mut self, padding: usize) -> TransferFunction<T> {
         self.reversed_impulse_response.reverse();
         let mut impulse_response = self.reversed_impulse_response;
 
         if padding > 0 {
-            impulse_response.extend(vec![0.0; padding]);
+            impulse_response.extend(vec![T::zero(); padding]);
         }
 
         let mut planner = FftPlanner::new();
         let fft = planner.plan_fft_forward(impulse_response.len());
-        let mut complex_impulse_response: Vec<Complex<f32>> = impulse_response
+        let mut complex_impulse_response: Vec<Complex<T>> = impulse_response
             .iter_mut()
-            .map(|x| Complex::new(*x, 0.0))
+            .map(|x| Complex::new(*x, T::zero()))
             .collect();
 
         fft.process(&mut complex_impulse_response);
@@ -48,26 +49,26 @@ impl ImpulseResponse {
         )
     }
 
-    pub fn normalize_at_frequency(ir: Self, frequency: f32, sample_rate: f32) -> Self {
+    pub fn normalize_at_frequency(ir: Self, frequency: T, sample_rate: T) -> Self {
         let tf = ir.transfer_function(0);
-        let index = (frequency * tf.len() as f32 / sample_rate) as usize;
+        let index = (frequency * T::constant(tf.len() as f64) / sample_rate).to_usize().unwrap();
         let select_frequency_magnitude = tf.transfer_function[index].norm();
         
         let mut ir = tf.impulse_response(0);
 
         ir.reversed_impulse_response = ir.reversed_impulse_response.iter()
-            .map(|x| x / select_frequency_magnitude)
+            .map(|x| *x / select_frequency_magnitude)
             .collect();
 
         ir
     }
     pub fn normalize_to_max(ir: Self) -> Self {
-        Self::normalize_at_frequency(ir, 0.0, 1.0)
+        Self::normalize_at_frequency(ir, T::zero(), T::one())
     }
     pub fn normalize_to_sum(mut ir: Self) -> Self {
-        let sum: f32 = ir.reversed_impulse_response.iter().sum();
+        let sum: T = ir.reversed_impulse_response.iter().copied().sum();
         ir.reversed_impulse_response = ir.reversed_impulse_response.iter()
-            .map(|x| x / sum)
+            .map(|x| *x / sum)
             .collect();
         ir
     }
@@ -77,17 +78,17 @@ impl ImpulseResponse {
     }
 }
 
-pub struct TransferFunction {
-    pub transfer_function: Vec<Complex<f32>>
+pub struct TransferFunction<T: ValidFloat = f32> {
+    pub transfer_function: Vec<Complex<T>>
 }
-impl TransferFunction {
-    pub fn new_configured(transfer_function: Vec<Complex<f32>>) -> Self {
+impl<T: ValidFloat> TransferFunction<T> {
+    pub fn new_configured(transfer_function: Vec<Complex<T>>) -> Self {
         TransferFunction {
             transfer_function,
         }
     }
     pub fn new_from_function<F>(func: F, sample_start_n: i64, window_size: usize) -> Self
-    where F: Fn(i64) -> Complex<f32> {
+    where F: Fn(i64) -> Complex<T> {
         let mut transfer_function = Vec::with_capacity(window_size);
         for index in sample_start_n..sample_start_n + window_size as i64 {
             transfer_function.push(func(index));
@@ -98,7 +99,7 @@ impl TransferFunction {
         }
     }
 
-    pub fn impulse_response(mut self, dropped: usize) -> ImpulseResponse {
+    pub fn impulse_response(mut self, dropped: usize) -> ImpulseResponse<T> {
         let mut planner = FftPlanner::new();
         let fft = planner.plan_fft_inverse(self.transfer_function.len());
         
@@ -106,7 +107,7 @@ impl TransferFunction {
         
         self.transfer_function.truncate(self.transfer_function.len() - dropped);
 
-        let impulse_response: Vec<f32> = self.transfer_function
+        let impulse_response: Vec<T> = self.transfer_function
             .iter_mut()
             .map(|x| x.re)
             .collect();
@@ -117,4 +118,4 @@ impl TransferFunction {
     pub fn len(&self) -> usize {
         self.transfer_function.len()
     }
-}
\ No newline at end of file
+}
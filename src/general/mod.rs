@@ -1,4 +1,4 @@
 pub mod validation_functions;
 pub mod parallel_computation;
 pub mod tests;
-mod tap;
+pub mod tap;
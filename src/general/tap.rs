@@ -3,15 +3,24 @@ use std::sync::mpsc::Sender;
 use crate::pipeline::api::*;
 
 pub struct TapStep<T> {
-    tap_sender: Sender<T>
+    // passes its input through unchanged and copies it to a channel. For a bounded, lossy view of a node's output see NodeBuilder::with_tap
+    tap_sender: Sender<T>,
+    connected: bool
 }
 impl<T> TapStep<T> {
-    fn new(tap_sender: Sender<T>) -> Self {
-        TapStep { tap_sender }
+    pub fn new(tap_sender: Sender<T>) -> Self {
+        TapStep { tap_sender, connected: true }
     }
 }
 impl<T: Sharable> PipelineStep<T, T> for TapStep<T> {
-    fn run_SISO(&mut self, input: T) -> Result<ODFormat<T>, String> {
-        self.tap_sender.send(input.clone()).unwrap(); Ok(ODFormat::Standard(input))
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: T) -> Result<ODFormat<T>, StepError> {
+        if self.connected && self.tap_sender.send(input.clone()).is_err() {
+            // whoever listened went away, which is no reason to stop the pipeline
+            log_message("TapStep: tap receiver dropped, no longer copying input".to_string(), Level::Warn);
+            self.connected = false;
+        }
+        Ok(ODFormat::Standard(input))
     }
-}
\ No newline at end of file
+}
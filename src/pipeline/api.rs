@@ -1,8 +1,29 @@
 pub use super::pipeline_comms::{ReceiveType, ODFormat};
-pub use super::pipeline_step::{PipelineStep, PipelineStepResult, PipelineNode, PipelineRecipe, JointBuilder, SplitBuilder, MultiplexerBuilder, DemultiplexerBuilder, NodeBuilder, joint_begin, joint_feedback_begin, demultiplexer_begin};
+pub use super::pipeline_channels::{ChannelBackend, BackpressurePolicy};
+pub use super::pipeline_thread_orchestrator::{ExecutionModel, DeterministicExecutor};
+pub use super::pipeline_step::{PipelineStep, PipelineStepResult, StepHandler, StepWrapper, PipelineNode, PipelineRecipe, JointBuilder, SplitBuilder, MultiplexerBuilder, DemultiplexerBuilder, NodeBuilder, joint_begin, joint_feedback_begin, demultiplexer_begin};
 pub use super::pipeline_traits::*;
+pub use super::pipeline_errors::{StepError, StepErrorKind, PipelineBuildError, TopologyViolation, PipelineConfigError, ControlError, TapError, RecordingError, SchedulingError, RemoteError, SwapError, CheckpointError};
 pub use super::valid_types::{ValidBytes, ValidComplex, ValidDSPNumerical, ValidFloat};
 pub use super::logging::{log_message, Level, debug, error, trace, info, warn};
 pub use super::pipeline_thread::PipelineThread;
 pub use super::pipeline::{ConstructingPipeline, ActivePipeline, PipelineParameters, ThreadDiagnostic};
-pub use super::thread_state_space::*;
\ No newline at end of file
+pub use super::pipeline_metrics::NodeMetricsSnapshot;
+pub use super::pipeline_graph::{PipelineGraph, GraphNode, GraphEdge, GraphNodeKind, GraphEdgeKind};
+pub use super::thread_state_space::*;
+pub use super::pipeline_registry::{StepRegistry, StepParameters, StepFactory};
+pub use super::pipeline_config::{PipelineDefinition, NodeDefinition, ConnectionDefinition, ParameterDefinition, load_pipeline, pipeline_from_toml, pipeline_from_json, pipeline_from_definition};
+pub use super::stream_tags::{StreamTag, TagValue, tag_keys, find_tag};
+pub use super::pipeline_control::{ControlMessage, ControlValue, ControlTicket};
+pub use super::pipeline_supervisor::{SupervisionPolicy, Supervised};
+pub use super::pipeline_taps::{TapOptions, TapHandle, CapturedInput};
+pub use super::pipeline_thread_options::{ThreadOptions, SchedulingPolicy};
+pub use super::pipeline_recording::{Recordable, Recording, RecordedInput, RecordingSummary, InputRecorder, ReplaySource, replay_step};
+pub use super::time_sync::{SampleClock, Paced, DriftMonitor};
+pub use super::pipeline_swap::SwapTicket;
+pub use super::pipeline_checkpoint::{Checkpointable, CheckpointSummary};
+pub use super::byte_codec::{ByteWriter, ByteReader};
+pub use super::pipeline_remote::{RemoteServer, RemoteServerHandle, RemoteClient, RemoteAddress, RemoteRequest, RemoteStatus, RemoteDiagnostic, RemoteTap, RemoteTapBatch};
+pub(crate) use super::pipeline_dsl::{pipeline, end_branch};
+pub(crate) use super::pipeline_step::wrapped_step;
+pub use super::pipeline_tracing::{ChromeTraceLayer, ChromeTraceGuard, install_chrome_trace};
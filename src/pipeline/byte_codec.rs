@@ -0,0 +1,90 @@
+// The little endian writer and reader behind the binary containers of the pipeline, the recordings and the checkpoints, and behind
+// the state a Checkpointable step saves. Strings and byte blocks are a u32 length followed by the bytes, lists a u32 count followed
+// by the values. The reader reports a cut short or invalid input as a message, which every container wraps in an error of its own.
+
+
+#[derive(Debug, Default)]
+pub struct ByteWriter {
+    bytes: Vec<u8>
+}
+impl ByteWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn put_u8(&mut self, value: u8) { self.bytes.push(value) }
+    pub fn put_bool(&mut self, value: bool) { self.bytes.push(value as u8) }
+    pub fn put_u32(&mut self, value: u32) { self.bytes.extend_from_slice(&value.to_le_bytes()) }
+    pub fn put_u64(&mut self, value: u64) { self.bytes.extend_from_slice(&value.to_le_bytes()) }
+    pub fn put_i64(&mut self, value: i64) { self.bytes.extend_from_slice(&value.to_le_bytes()) }
+    pub fn put_f32(&mut self, value: f32) { self.bytes.extend_from_slice(&value.to_le_bytes()) }
+    pub fn put_f64(&mut self, value: f64) { self.bytes.extend_from_slice(&value.to_le_bytes()) }
+    pub fn put_bytes(&mut self, value: &[u8]) {
+        self.put_u32(value.len() as u32);
+        self.bytes.extend_from_slice(value);
+    }
+    pub fn put_str(&mut self, value: &str) { self.put_bytes(value.as_bytes()) }
+    pub fn put_f32s(&mut self, values: &[f32]) {
+        self.put_u32(values.len() as u32);
+        values.iter().for_each(|value| self.put_f32(*value));
+    }
+    pub fn put_f64s(&mut self, values: &[f64]) {
+        self.put_u32(values.len() as u32);
+        values.iter().for_each(|value| self.put_f64(*value));
+    }
+    pub fn put_u32s(&mut self, values: &[u32]) {
+        self.put_u32(values.len() as u32);
+        values.iter().for_each(|value| self.put_u32(*value));
+    }
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+
+pub struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize
+}
+impl<'a> ByteReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+    pub fn take(&mut self, count: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() - self.position < count {
+            return Err(format!("truncated at byte {}", self.position));
+        }
+        let taken = &self.bytes[self.position..self.position + count];
+        self.position += count;
+        Ok(taken)
+    }
+    pub fn u8(&mut self) -> Result<u8, String> { Ok(self.take(1)?[0]) }
+    pub fn bool(&mut self) -> Result<bool, String> { Ok(self.u8()? != 0) }
+    pub fn u16(&mut self) -> Result<u16, String> { Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap())) }
+    pub fn u32(&mut self) -> Result<u32, String> { Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap())) }
+    pub fn u64(&mut self) -> Result<u64, String> { Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap())) }
+    pub fn i64(&mut self) -> Result<i64, String> { Ok(i64::from_le_bytes(self.take(8)?.try_into().unwrap())) }
+    pub fn f32(&mut self) -> Result<f32, String> { Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap())) }
+    pub fn f64(&mut self) -> Result<f64, String> { Ok(f64::from_le_bytes(self.take(8)?.try_into().unwrap())) }
+    pub fn bytes(&mut self) -> Result<&'a [u8], String> {
+        let length = self.u32()? as usize;
+        self.take(length)
+    }
+    pub fn string(&mut self) -> Result<String, String> {
+        String::from_utf8(self.bytes()?.to_vec()).map_err(|_| format!("invalid utf8 before byte {}", self.position))
+    }
+    pub fn f32s(&mut self) -> Result<Vec<f32>, String> {
+        (0..self.u32()?).map(|_| self.f32()).collect()
+    }
+    pub fn f64s(&mut self) -> Result<Vec<f64>, String> {
+        (0..self.u32()?).map(|_| self.f64()).collect()
+    }
+    pub fn u32s(&mut self) -> Result<Vec<u32>, String> {
+        (0..self.u32()?).map(|_| self.u32()).collect()
+    }
+    pub fn position(&self) -> usize {
+        self.position
+    }
+    pub fn at_end(&self) -> bool {
+        self.position == self.bytes.len()
+    }
+}
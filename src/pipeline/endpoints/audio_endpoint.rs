@@ -33,7 +33,9 @@ impl AudioSink {
     }
 }
 impl PipelineStep<Vec<Sample>, ()> for AudioSink {
-    fn run_SIDO(&mut self, input: Vec<Sample>) -> Result<ODFormat<()>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+    fn run_SIDO(&mut self, input: Vec<Sample>) -> Result<ODFormat<()>, StepError> {
         let new_source = SourceObject::new(input, self.channels, self.sample_rate);
         
         match &mut self.sink {
@@ -41,7 +43,7 @@ impl PipelineStep<Vec<Sample>, ()> for AudioSink {
                 sink.append(new_source);
                 Ok(ODFormat::Standard(()))
             },
-            None => Err("Sink does not exist".to_string())
+            None => Err(StepError::fatal("Sink does not exist"))
         }
     }
 
@@ -57,6 +59,12 @@ impl PipelineStep<Vec<Sample>, ()> for AudioSink {
             None => panic!("Cannot start nonexistant")
         }
     }
+    fn flush(&mut self) -> Option<ODFormat<()>> { // the stream has ended, play out everything already appended
+        if let Some(sink) = &self.sink {
+            sink.sleep_until_end();
+        }
+        None
+    }
     fn kill_behavior(&mut self) {
         let flag = self.detach_on_kill.clone();
         let sink = self.sink.take();
@@ -80,7 +88,9 @@ impl Sink for AudioSink {}
 
 pub struct ToSamples {}
 impl PipelineStep<Vec<f32>, Vec<Sample>> for ToSamples {
-    fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<Vec<Sample>>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<Vec<Sample>>, StepError> {
         let output = input.iter()
             .map(|sample| Sample::from(*sample))
             .collect();
@@ -10,6 +10,25 @@ pub mod sources;
 mod valid_types;
 mod pipeline_traits;
 mod pipeline_comms;
+mod pipeline_channels;
+mod pipeline_errors;
+mod pipeline_graph;
+mod pipeline_metrics;
+mod pipeline_registry;
+mod pipeline_config;
+mod stream_tags;
+mod pipeline_control;
+mod pipeline_supervisor;
+mod pipeline_taps;
+mod pipeline_recording;
+mod pipeline_thread_options;
+mod time_sync;
+mod pipeline_remote;
+mod pipeline_swap;
+mod pipeline_checkpoint;
+mod byte_codec;
+mod pipeline_tracing;
+mod pipeline_dsl;
 pub mod api;
 pub mod logging;
 mod thread_state_space;
@@ -8,16 +8,24 @@ use super::pipeline_thread::{PipelineThread};
 use std::thread::{self, JoinHandle, Thread};
 use std::sync::mpsc;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use std::sync::mpmc::RecvTimeoutError;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use std::path::Path;
 use crossbeam_queue::SegQueue;
 use super::api::*;
+use super::pipeline_graph::{GraphRecorder, PipelineGraph};
+use super::pipeline_thread_orchestrator::{DeterministicExecutor, PoolScheduler};
+use super::pipeline_supervisor::Supervisor;
+use super::pipeline_checkpoint::{quiesce_nodes, restore_nodes, snapshot_nodes, Checkpoint};
 //use crate::frontend::curses::app::{App, AppBuilder};
 
 
 pub type ConstructionQueue = Arc<SegQueue<PipelineThread>>;
 
+const END_OF_STREAM_POLL_TIME: Duration = Duration::from_millis(10); // how often wait_for_end_of_stream checks on the sinks
+const SUPERVISION_POLL_TIME: Duration = Duration::from_millis(10); // longest the manager thread goes without checking on the nodes
+
 
 #[derive(Clone)]
 pub struct PipelineParameters {
@@ -27,6 +35,11 @@ pub struct PipelineParameters {
     pub max_compute_errors: usize,
     pub unchanged_state_time: u64,
     pub backpressure_val: usize,
+    pub stall_timeout: u64, // milliseconds a node may spend in one call (receive included) before the supervisor treats it as stalled. 0 disables
+    pub channel_backend: ChannelBackend,
+    pub execution_model: ExecutionModel,
+    pub thread_options: ThreadOptions, // for every node thread without options of its own
+    pub node_thread_options: HashMap<String, ThreadOptions>, // by node id, for nodes no builder call can reach first (eg sources and joints)
 }
 impl PipelineParameters {
     pub fn new(retries: usize, timeout: u64, backpressure_val: usize, max_infrastructure_errors: usize, max_compute_errors: usize, unchanged_state_time: u64) -> PipelineParameters {
@@ -37,25 +50,41 @@ impl PipelineParameters {
             max_compute_errors,
             max_infrastructure_errors,
             unchanged_state_time,
+            stall_timeout: 0,
+            channel_backend: ChannelBackend::default(),
+            execution_model: ExecutionModel::default(),
+            thread_options: ThreadOptions::default(),
+            node_thread_options: HashMap::new(),
         }
     }
+    pub fn thread_options_for(&self, node_id: &str) -> ThreadOptions {
+        self.node_thread_options.get(node_id).unwrap_or(&self.thread_options).clone()
+    }
 }
 
 
 pub struct CommandStatePasser {
     state: Arc<AtomicU8>,
+    drain: Arc<AtomicBool>,
     aggregate_receiver: Option<mpsc::Receiver<ThreadStateSpace>>,
     copyable_sender: mpsc::Sender<ThreadStateSpace>,
     manager_thread_handle: Option<JoinHandle<()>>,
+    supervisor: Supervisor,
     timeout: u64
 }
 impl CommandStatePasser {
     pub fn new(timeout: u64) -> Self {
         let (copyable_sender, aggregate_receiver) = mpsc::channel();
-        Self { state: Arc::new(AtomicU8::new(1)), copyable_sender, aggregate_receiver: Some(aggregate_receiver), manager_thread_handle: None, timeout }
+        Self { state: Arc::new(AtomicU8::new(1)), drain: Arc::new(AtomicBool::new(false)), copyable_sender, aggregate_receiver: Some(aggregate_receiver), manager_thread_handle: None, supervisor: Supervisor::default(), timeout }
+    }
+    pub fn supervise(&mut self, supervisor: Supervisor) { // the nodes the manager thread watches over, once the pipeline is finished
+        self.supervisor = supervisor;
     }
-    pub fn extract_for_node(&self) -> (Arc<AtomicU8>, mpsc::Sender<ThreadStateSpace>) {
-        (self.state.clone(), self.copyable_sender.clone())
+    pub fn extract_for_node(&self) -> NodeStateCommunicator {
+        NodeStateCommunicator::new(self.state.clone(), self.copyable_sender.clone(), self.drain.clone())
+    }
+    pub fn request_drain(&mut self) { // sources end their stream on their next call instead of producing more
+        self.drain.store(true, Ordering::Release);
     }
     pub fn broadcast_requested_state(&mut self, requested_state: ThreadStateSpace) {
         self.state.store(requested_state as u8, Ordering::Release);
@@ -67,11 +96,12 @@ impl CommandStatePasser {
         let receiver = self.aggregate_receiver.take();
         let state = self.state.clone();
         let timeout = self.timeout.clone();
+        let supervisor = self.supervisor.clone();
         
         match receiver {
             Some(receiver) => self.manager_thread_handle = Some(thread::spawn(
                 move || {
-                    Self::internal_state_management_thread(receiver, state, timeout)
+                    Self::internal_state_management_thread(receiver, state, timeout, supervisor)
                 }
             )),
             None => panic!("Cannot start pipeline manager thread without aggregate receiver instantiated")
@@ -87,14 +117,16 @@ impl CommandStatePasser {
         
         log_message(format!("Pipeline management thread successfully joined"), Level::Info);
     }
-    fn internal_state_management_thread(receiver: mpsc::Receiver<ThreadStateSpace>, mut state_broadcaster: Arc<AtomicU8>, timeout: u64) {
+    fn internal_state_management_thread(receiver: mpsc::Receiver<ThreadStateSpace>, mut state_broadcaster: Arc<AtomicU8>, timeout: u64, supervisor: Supervisor) {
         log_message(format!("Pipeline management thread starting"), Level::Info);
+        let poll_time = Duration::from_millis(timeout).min(SUPERVISION_POLL_TIME);
         while state_broadcaster.load(Ordering::Acquire) != ThreadStateSpace::KILLED as u8 {
-            let received_state = receiver.recv_timeout(std::time::Duration::from_millis(timeout));
+            let received_state = receiver.recv_timeout(poll_time);
             match received_state {
                 Err(err) => Self::internal_receive_error_handler(err),
                 Ok(state) => Self::state_supercession(&mut state_broadcaster, state)
             }
+            supervisor.check(&state_broadcaster);
         }
         log_message(format!("Pipeline management thread exiting"), Level::Info);
     }
@@ -118,6 +150,17 @@ impl CommandStatePasser {
     pub fn is_started(&self) -> bool {
         self.manager_thread_handle.is_some()
     }
+    pub fn get_requested_state(&self) -> ThreadStateSpace {
+        ThreadStateSpace::try_from(self.state.load(Ordering::Acquire)).unwrap()
+    }
+    pub fn apply_node_requests(&mut self) { // does the manager thread's job for the deterministic executor, which has no manager thread
+        if let Some(receiver) = &self.aggregate_receiver {
+            while let Ok(state) = receiver.try_recv() {
+                Self::state_supercession(&mut self.state, state);
+            }
+        }
+        self.supervisor.check(&self.state);
+    }
 }
 
 
@@ -125,6 +168,9 @@ pub struct ConstructingPipeline {
     nodes: ConstructionQueue,
     parameters: PipelineParameters,
     state_passer: CommandStatePasser,
+    graph: GraphRecorder,
+    channel_backend_chosen: bool, // with_channel_backend was called, so the deterministic model may not pick the backend itself
+    parameter_conflict: Option<String>, // reported by finish_pipeline, the builders have already made their links by then
 }
 impl ConstructingPipeline {
     pub fn new(retries: usize, timeout: u64, backpressure_val: usize, max_infrastructure_errors: usize, max_compute_errors: usize, unchanged_state_time: u64) -> Self {
@@ -133,25 +179,123 @@ impl ConstructingPipeline {
             nodes: Arc::new(SegQueue::new()),
             parameters,
             state_passer: CommandStatePasser::new(timeout),
+            graph: PipelineGraph::new_recorder(),
+            channel_backend_chosen: false,
+            parameter_conflict: None,
         }
     }
+    pub fn with_channel_backend(mut self, channel_backend: ChannelBackend) -> Self {
+        // must be chosen before any nodes are built, the builders create their links from the parameters
+        self.parameters.channel_backend = channel_backend;
+        self.channel_backend_chosen = true;
+        self.check_parameter_conflict();
+        self
+    }
+    pub fn with_stall_timeout(mut self, stall_timeout: u64) -> Self {
+        // milliseconds, must be longer than the longest call a step should ever make, plus the receive timeout and retries
+        self.parameters.stall_timeout = stall_timeout;
+        self
+    }
+    pub fn with_execution_model(mut self, execution_model: ExecutionModel) -> Self {
+        // same as the channel backend, the nodes decide on spawning a thread or waiting for the scheduler when they are built
+        if execution_model == ExecutionModel::Deterministic && !self.channel_backend_chosen {
+            self.parameters.channel_backend = ChannelBackend::Unbounded; // one thread cannot wait for a full link to drain
+        }
+        self.parameters.execution_model = execution_model;
+        self.check_parameter_conflict();
+        self
+    }
+    fn check_parameter_conflict(&mut self) {
+        if self.parameters.execution_model == ExecutionModel::Deterministic && self.parameters.channel_backend != ChannelBackend::Unbounded {
+            self.parameter_conflict = Some(format!("the deterministic execution model needs unbounded links, not the {} channel backend", self.parameters.channel_backend));
+        }
+        else {
+            self.parameter_conflict = None;
+        }
+    }
+    pub fn with_thread_options(mut self, thread_options: ThreadOptions) -> Self {
+        // the default for every node thread, applied when the builders spawn them so it must come before any nodes are built
+        self.parameters.thread_options = thread_options;
+        self
+    }
+    pub fn with_node_thread_options(mut self, node_id: &str, thread_options: ThreadOptions) -> Self {
+        // for a single node by its full (namespaced) id, overrides the default
+        self.parameters.node_thread_options.insert(node_id.to_string(), thread_options);
+        self
+    }
     pub fn get_cloned_parameters(&self) -> PipelineParameters {
         self.parameters.clone()
     }
-    pub fn get_state_communicators(&self) -> (Arc<AtomicU8>, mpsc::Sender<ThreadStateSpace>) {
+    pub fn get_state_communicators(&self) -> NodeStateCommunicator {
         self.state_passer.extract_for_node()
     }
     pub fn get_nodes(&self) -> ConstructionQueue {
         self.nodes.clone()
     }
-    pub fn finish_pipeline(mut self) -> ActivePipeline {
+    pub fn get_graph(&self) -> GraphRecorder {
+        self.graph.clone()
+    }
+    pub fn finish_pipeline(mut self) -> Result<ActivePipeline, PipelineBuildError> {
         let mut static_nodes = Vec::with_capacity(self.nodes.len());
         
         while self.nodes.len() > 0 {
             static_nodes.push(self.nodes.pop().unwrap());
         }
         
-        ActivePipeline { nodes: static_nodes, parameters: self.parameters, state_passer: self.state_passer, start_time: Instant::now() }
+        if let Some(conflict) = self.parameter_conflict.take() {
+            let error = PipelineBuildError::ConflictingParameters(conflict);
+            log_message(format!("Pipeline construction failed: {}", error), Level::Error);
+            Self::discard_nodes(self.state_passer, static_nodes);
+            return Err(error);
+        }
+
+        let violations: Vec<TopologyViolation> = static_nodes.iter()
+            .filter_map(|thread| thread.topology_violation.clone())
+            .collect();
+        
+        if !violations.is_empty() {
+            let error = PipelineBuildError::InvalidTopology(violations);
+            log_message(format!("Pipeline construction failed: {}", error), Level::Error);
+            Self::discard_nodes(self.state_passer, static_nodes);
+            return Err(error);
+        }
+
+        let scheduling_errors: Vec<SchedulingError> = static_nodes.iter()
+            .filter(|thread| !thread.thread_options.best_effort)
+            .filter_map(|thread| thread.scheduling_error.clone())
+            .collect();
+
+        if !scheduling_errors.is_empty() {
+            let error = PipelineBuildError::Scheduling(scheduling_errors);
+            log_message(format!("Pipeline construction failed: {}", error), Level::Error);
+            Self::discard_nodes(self.state_passer, static_nodes);
+            return Err(error);
+        }
+        
+        let stall_timeout = match self.parameters.stall_timeout {
+            0 => None,
+            stall_timeout => Some(Duration::from_millis(stall_timeout))
+        };
+        self.state_passer.supervise(Supervisor::new(static_nodes.iter().map(|thread| thread.health.clone()).collect(), stall_timeout));
+
+        let graph = self.graph.lock().unwrap().clone();
+        let scheduler = match self.parameters.execution_model {
+            ExecutionModel::ThreadPerNode | ExecutionModel::Deterministic => None,
+            ExecutionModel::ThreadPool(workers) => {
+                let scheduled = static_nodes.iter_mut().filter_map(|thread| thread.take_scheduled()).collect();
+                Some(PoolScheduler::spawn(scheduled, workers, self.state_passer.extract_for_node().global_state, self.parameters.unchanged_state_time))
+            }
+        };
+        
+        Ok(ActivePipeline { nodes: static_nodes, parameters: self.parameters, state_passer: self.state_passer, scheduler, graph, start_time: Instant::now() })
+    }
+    fn discard_nodes(mut state_passer: CommandStatePasser, nodes: Vec<PipelineThread>) {
+        // the node threads are already spawned (paused) by the builders, so they must be killed and joined before the error is returned
+        state_passer.broadcast_requested_state(ThreadStateSpace::KILLED);
+        
+        for thread in nodes {
+            thread.join()
+        }
     }
     // pub fn finish_with_tui(mut self) -> App {
     //     
@@ -163,23 +307,44 @@ pub struct ThreadDiagnostic {
     pub thread_state: ThreadStateSpace,
     pub return_code: PipelineStepResult,
     pub execution_time: u64,
+    pub last_error: Option<StepError>,
+    pub metrics: NodeMetricsSnapshot,
+    pub end_of_stream: bool,
+    pub panic_message: Option<String>, // the last panic caught around the step, kept after it was restarted
+    pub stalled: bool,
+    pub restarts: usize,
+    pub dropped: usize, // values the node's output links discarded instead of waiting for room
+    pub scheduling_error: Option<SchedulingError>, // thread options which could not be applied to a best effort node
     pub id: String
 }
 impl ThreadDiagnostic {
     pub fn new(thread: &PipelineThread) -> Self {
         Self {
-            thread_state: ThreadStateSpace::try_from(thread.requested_state.load(Ordering::Acquire)).unwrap(),
+            thread_state: thread.requested_state(),
             return_code: thread.return_code.read().unwrap().clone(),
             execution_time: thread.execution_time.load(Ordering::Acquire),
+            last_error: thread.last_error.read().unwrap().clone(),
+            metrics: thread.metrics.snapshot(),
+            end_of_stream: thread.end_of_stream.load(Ordering::Acquire),
+            panic_message: thread.health.panic_message(),
+            stalled: thread.health.is_stalled(),
+            restarts: thread.health.restarts(),
+            dropped: thread.dropped.iter().map(|counter| counter.load(Ordering::Acquire)).sum(),
+            scheduling_error: thread.scheduling_error.clone(),
             id: thread.id.clone()
         }
     }
+    pub fn last_error_kind(&self) -> Option<StepErrorKind> { // for grouping failures by category
+        self.last_error.as_ref().map(|error| error.kind())
+    }
 }
 
 pub struct ActivePipeline {
     nodes: Vec<PipelineThread>,
     parameters: PipelineParameters,
     state_passer: CommandStatePasser,
+    scheduler: Option<PoolScheduler>, // only for the thread pool execution model
+    graph: PipelineGraph,
     start_time: Instant
 }
 impl ActivePipeline {
@@ -203,9 +368,114 @@ impl ActivePipeline {
         for thread in self.nodes {
             thread.join()
         }
+        if let Some(scheduler) = self.scheduler {
+            scheduler.join()
+        }
         
         self.state_passer.join();
     }
+
+    pub fn wait_for_end_of_stream(&self, timeout: Duration) -> bool {
+        // blocks until every sink has consumed the end of stream, ignoring sinks in multiplexed branches which are not selected.
+        // False if the timeout ran out first, or the pipeline was killed (eg by a fatal error) while waiting
+        let start = Instant::now();
+
+        loop {
+            let finished = self.nodes.iter()
+                .filter(|thread| thread.is_sink)
+                .all(|thread| thread.end_of_stream.load(Ordering::Acquire) || thread.is_held_by_branch());
+
+            if finished { return true }
+            if start.elapsed() >= timeout || self.state_passer.get_requested_state() == ThreadStateSpace::KILLED { return false }
+            thread::sleep(END_OF_STREAM_POLL_TIME);
+        }
+    }
+
+    pub fn drain_and_stop(mut self, timeout: Duration) -> bool {
+        // ends the stream at every source and lets everything already in flight be flushed through to the sinks before killing the pipeline.
+        // A paused pipeline is resumed so it can drain. Returns whether the sinks consumed everything before the timeout, the pipeline is killed either way
+        log_message(format!("Draining active pipeline length: {}", self.nodes.len()), Level::Debug);
+        self.state_passer.request_drain();
+        self.start();
+
+        let drained = self.wait_for_end_of_stream(timeout);
+        if !drained {
+            log_message(format!("Pipeline did not drain within {:?}, killing with data still in flight", timeout), Level::Warn);
+        }
+
+        self.kill();
+        drained
+    }
+    pub fn send_control(&self, node_id: &str, message: ControlMessage) -> Result<ControlTicket, ControlError> {
+        // queues the message for the node, which handles it between two work calls. The ticket tells whether the step accepted it
+        match self.nodes.iter().find(|thread| thread.id == node_id) {
+            Some(thread) => thread.send_control(message),
+            None => Err(ControlError::UnknownNode { node_id: node_id.to_string() })
+        }
+    }
+    pub fn swap_step<I: Sharable, O: Sharable>(&self, node_id: &str, step: impl PipelineStep<I, O>) -> Result<SwapTicket, SwapError> {
+        // replaces the step of one node while the rest of the pipeline runs on. The node takes it between two work calls, pausing and
+        // killing the old step and starting the new one if it is running. Its links and supervision policy stay as they were built
+        match self.nodes.iter().find(|thread| thread.id == node_id) {
+            Some(thread) => thread.swap_step(Box::new(step)),
+            None => Err(SwapError::UnknownNode { node_id: node_id.to_string() })
+        }
+    }
+    pub fn checkpoint(&mut self, path: impl AsRef<Path>) -> Result<CheckpointSummary, CheckpointError> {
+        // pauses the pipeline and saves the state of every checkpointable step with the values waiting on every link to path.
+        // A running pipeline is started again afterwards, the values stay on their links so nothing is lost by checkpointing
+        let was_running = self.is_running();
+        self.stop();
+        let result = quiesce_nodes(&self.nodes)
+            .and_then(|_| snapshot_nodes(&self.nodes))
+            .and_then(|checkpoint| checkpoint.save(path));
+        if was_running {
+            self.start();
+        }
+        result
+    }
+    pub fn restore(&mut self, path: impl AsRef<Path>) -> Result<CheckpointSummary, CheckpointError> {
+        // loads a checkpoint into a pipeline built the same way as the one it was taken from, usually before it is started.
+        // Every node of the checkpoint must be in the pipeline and the other way round, values waiting on the links are replaced.
+        // A checkpoint which any node rejects changes none of them
+        let checkpoint = Checkpoint::load(path)?;
+        let was_running = self.is_running();
+        self.stop();
+        let result = quiesce_nodes(&self.nodes).and_then(|_| restore_nodes(&self.nodes, checkpoint));
+        if was_running {
+            self.start();
+        }
+        result
+    }
+    pub fn select_branch(&self, multiplexer_id: &str, branch: usize) -> Result<(), ControlError> {
+        // moves the channel selector of the multiplexer, the branches follow on their next call like with any other change of the selector
+        let (selector, branches) = self.graph.selector(multiplexer_id).ok_or_else(|| ControlError::UnknownNode { node_id: multiplexer_id.to_string() })?;
+        if branch >= branches {
+            return Err(ControlError::Rejected { node_id: multiplexer_id.to_string(), key: String::from("branch"), message: format!("there are only {} branches", branches) });
+        }
+        selector.store(branch, Ordering::Release);
+        Ok(())
+    }
+    pub fn selected_branch(&self, multiplexer_id: &str) -> Result<usize, ControlError> {
+        match self.graph.selector(multiplexer_id) {
+            Some((selector, _)) => Ok(selector.load(Ordering::Acquire)),
+            None => Err(ControlError::UnknownNode { node_id: multiplexer_id.to_string() })
+        }
+    }
+    pub fn tap<T: Sharable>(&self, node_id: &str, options: TapOptions) -> Result<TapHandle<ODFormat<T>>, TapError> {
+        // subscribes to the output of a node, T must be the type it outputs. Can be done at any time, the handle detaches when dropped
+        match self.nodes.iter().find(|thread| thread.id == node_id) {
+            Some(thread) => thread.tap(options),
+            None => Err(TapError::UnknownNode { node_id: node_id.to_string() })
+        }
+    }
+    pub fn record_inputs<T: Recordable>(&self, node_id: &str, path: impl AsRef<Path>) -> Result<InputRecorder, RecordingError> {
+        // writes every input the node receives from now on to path, until the recorder is stopped
+        match self.nodes.iter().find(|thread| thread.id == node_id) {
+            Some(thread) => thread.record_inputs::<T>(path.as_ref()),
+            None => Err(RecordingError::Tap(TapError::UnknownNode { node_id: node_id.to_string() }))
+        }
+    }
     pub fn get_thread_diagnostics(&self) -> Vec<ThreadDiagnostic> {
         let mut diagnostics = Vec::with_capacity(self.nodes.len());
         
@@ -215,6 +485,16 @@ impl ActivePipeline {
         
         diagnostics
     }
+    pub fn get_graph(&self) -> &PipelineGraph {
+        &self.graph
+    }
+    pub fn into_executor(mut self) -> DeterministicExecutor {
+        if self.parameters.execution_model != ExecutionModel::Deterministic {
+            panic!("Only pipelines built with the deterministic execution model can be driven by an executor");
+        }
+        let nodes = self.nodes.iter_mut().filter_map(|thread| thread.take_scheduled()).collect();
+        DeterministicExecutor::new(nodes, self.nodes, self.state_passer, self.graph)
+    }
     pub fn is_running(&self) -> bool {
         self.state_passer.state.load(Ordering::Acquire) == ThreadStateSpace::RUNNING as u8
     }
@@ -0,0 +1,293 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpmc::{self, RecvTimeoutError, SendError, SendTimeoutError, TrySendError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crossbeam::channel;
+use crossbeam::utils::Backoff;
+use crossbeam_queue::ArrayQueue;
+use serde::Deserialize;
+use strum::Display;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Display, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelBackend {
+    /*
+    The channel type used for every link between nodes, selected in PipelineParameters
+    Std: bounded std channel, parks the thread when blocked. The default
+    Crossbeam: crossbeam bounded channel, lower overhead under contention
+    SpscRing: lock free ring buffer with exactly one producer and one consumer. Spins then sleeps briefly when blocked, so it trades idle cpu for latency on hot paths
+    Unbounded: std channel without backpressure. Used by the deterministic executor, which runs every node on one thread and so must never block on a full link
+     */
+    #[default]
+    Std,
+    Crossbeam,
+    SpscRing,
+    Unbounded
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackpressurePolicy {
+    /*
+    What a link does when its sender finds it full, selected per link in the builders
+    Block: wait until the receiver makes room. The default, nothing is ever lost
+    DropNewest: discard the value being sent
+    DropOldest: discard the oldest value on the link to make room, so the receiver always gets the most recent data
+    BlockThenDrop: wait up to the given number of milliseconds, then discard the value being sent
+    DropNewest and DropOldest need a backpressure of at least 1, a rendezvous link is always full unless the receiver is already waiting.
+    A link into a reassembler must Block, it counts whole series off the link. check_link rejects both when the link is set up.
+    The end of stream is never dropped, it always waits for room
+     */
+    #[default]
+    Block,
+    DropNewest,
+    DropOldest,
+    BlockThenDrop(u64)
+}
+impl BackpressurePolicy {
+    pub fn check_link(&self, capacity: Option<usize>, reassembled: bool) -> Result<(), String> {
+        // whether the policy can work on a link of this capacity (None if unbounded), and into a reassembler if reassembled
+        match self {
+            BackpressurePolicy::Block => Ok(()),
+            BackpressurePolicy::DropNewest | BackpressurePolicy::DropOldest if capacity == Some(0) => {
+                Err(format!("{:?} needs a backpressure of at least 1, a rendezvous link has no queue to drop from", self))
+            },
+            _ if reassembled => Err(format!("{:?} would drop single values out of the series a reassembler receives whole", self)),
+            _ => Ok(())
+        }
+    }
+}
+
+
+pub fn link_channel<T>(backend: ChannelBackend, capacity: usize) -> (LinkSender<T>, LinkReceiver<T>) {
+    match backend {
+        ChannelBackend::Std => {
+            let (sender, receiver) = mpmc::sync_channel(capacity);
+            (LinkSender::Std(sender), LinkReceiver::Std(receiver))
+        },
+        ChannelBackend::Crossbeam => {
+            let (sender, receiver) = channel::bounded(capacity);
+            (LinkSender::Crossbeam(sender), LinkReceiver::Crossbeam(receiver))
+        },
+        ChannelBackend::SpscRing => {
+            let (sender, receiver) = ring_channel(capacity);
+            (LinkSender::SpscRing(sender), LinkReceiver::SpscRing(receiver))
+        },
+        ChannelBackend::Unbounded => {
+            let (sender, receiver) = mpmc::channel();
+            (LinkSender::Std(sender), LinkReceiver::Std(receiver))
+        }
+    }
+}
+
+
+#[derive(Debug)]
+pub enum LinkSender<T> {
+    Std(mpmc::Sender<T>),
+    Crossbeam(channel::Sender<T>),
+    SpscRing(RingSender<T>)
+}
+impl<T> LinkSender<T> {
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> { // blocks while the link is full
+        match self {
+            LinkSender::Std(sender) => sender.send(value),
+            LinkSender::Crossbeam(sender) => sender.send(value).map_err(|err| SendError(err.into_inner())),
+            LinkSender::SpscRing(sender) => sender.send(value)
+        }
+    }
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        match self {
+            LinkSender::Std(sender) => sender.try_send(value),
+            LinkSender::Crossbeam(sender) => sender.try_send(value).map_err(|err| match err {
+                channel::TrySendError::Full(value) => TrySendError::Full(value),
+                channel::TrySendError::Disconnected(value) => TrySendError::Disconnected(value)
+            }),
+            LinkSender::SpscRing(sender) => sender.try_send(value)
+        }
+    }
+    pub fn send_timeout(&self, value: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
+        match self {
+            LinkSender::Std(sender) => sender.send_timeout(value, timeout),
+            LinkSender::Crossbeam(sender) => sender.send_timeout(value, timeout).map_err(|err| match err {
+                channel::SendTimeoutError::Timeout(value) => SendTimeoutError::Timeout(value),
+                channel::SendTimeoutError::Disconnected(value) => SendTimeoutError::Disconnected(value)
+            }),
+            LinkSender::SpscRing(sender) => sender.send_timeout(value, timeout)
+        }
+    }
+    pub fn has_room(&self) -> bool { // a rendezvous link never has room in advance, so it counts as always having room
+        match self {
+            LinkSender::Std(sender) => sender.capacity() == Some(0) || !sender.is_full(),
+            LinkSender::Crossbeam(sender) => sender.capacity() == Some(0) || !sender.is_full(),
+            LinkSender::SpscRing(sender) => !sender.shared.queue.is_full()
+        }
+    }
+}
+
+
+#[derive(Debug)]
+pub enum LinkReceiver<T> {
+    Std(mpmc::Receiver<T>),
+    Crossbeam(channel::Receiver<T>),
+    SpscRing(RingReceiver<T>)
+}
+impl<T> LinkReceiver<T> {
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        match self {
+            LinkReceiver::Std(receiver) => receiver.recv_timeout(timeout),
+            LinkReceiver::Crossbeam(receiver) => receiver.recv_timeout(timeout).map_err(|err| match err {
+                channel::RecvTimeoutError::Timeout => RecvTimeoutError::Timeout,
+                channel::RecvTimeoutError::Disconnected => RecvTimeoutError::Disconnected
+            }),
+            LinkReceiver::SpscRing(receiver) => receiver.recv_timeout(timeout)
+        }
+    }
+    pub fn len(&self) -> usize {
+        match self {
+            LinkReceiver::Std(receiver) => receiver.len(),
+            LinkReceiver::Crossbeam(receiver) => receiver.len(),
+            LinkReceiver::SpscRing(receiver) => receiver.shared.queue.len()
+        }
+    }
+    pub fn capacity(&self) -> Option<usize> { // None for an unbounded link
+        match self {
+            LinkReceiver::Std(receiver) => receiver.capacity(),
+            LinkReceiver::Crossbeam(receiver) => receiver.capacity(),
+            LinkReceiver::SpscRing(receiver) => Some(receiver.shared.queue.capacity())
+        }
+    }
+    pub fn evictor(&self) -> LinkEvictor<T> {
+        // a second handle on the receiving end, for a sender which drops the oldest value. The sender no longer sees the receiver disconnect
+        match self {
+            LinkReceiver::Std(receiver) => LinkEvictor::Std(receiver.clone()),
+            LinkReceiver::Crossbeam(receiver) => LinkEvictor::Crossbeam(receiver.clone()),
+            LinkReceiver::SpscRing(receiver) => LinkEvictor::SpscRing(receiver.shared.clone())
+        }
+    }
+}
+
+
+#[derive(Debug)]
+pub enum LinkEvictor<T> {
+    Std(mpmc::Receiver<T>),
+    Crossbeam(channel::Receiver<T>),
+    SpscRing(Arc<RingShared<T>>) // not a RingReceiver, dropping it would disconnect the ring
+}
+impl<T> LinkEvictor<T> {
+    pub fn evict(&self) -> Option<T> { // takes the oldest value off the link, None if the receiver got to it first
+        match self {
+            LinkEvictor::Std(receiver) => receiver.try_recv().ok(),
+            LinkEvictor::Crossbeam(receiver) => receiver.try_recv().ok(),
+            LinkEvictor::SpscRing(shared) => shared.queue.pop()
+        }
+    }
+}
+
+
+const RING_PARK_TIME: Duration = Duration::from_micros(50); // sleep between polls once spinning has not produced anything
+
+#[derive(Debug)]
+pub struct RingShared<T> {
+    queue: ArrayQueue<T>,
+    sender_connected: AtomicBool,
+    receiver_connected: AtomicBool,
+}
+
+fn ring_channel<T>(capacity: usize) -> (RingSender<T>, RingReceiver<T>) {
+    // a ring cannot be a rendezvous channel, so a backpressure of 0 still holds a single value
+    let shared = Arc::new(RingShared {
+        queue: ArrayQueue::new(capacity.max(1)),
+        sender_connected: AtomicBool::new(true),
+        receiver_connected: AtomicBool::new(true)
+    });
+    (RingSender { shared: shared.clone() }, RingReceiver { shared })
+}
+
+fn ring_wait(backoff: &Backoff) {
+    if backoff.is_completed() { thread::sleep(RING_PARK_TIME) } else { backoff.snooze() }
+}
+
+
+#[derive(Debug)]
+pub struct RingSender<T> { // deliberately not Clone, the ring only supports a single producer
+    shared: Arc<RingShared<T>>
+}
+impl<T> RingSender<T> {
+    pub fn send(&self, mut value: T) -> Result<(), SendError<T>> {
+        let backoff = Backoff::new();
+
+        loop {
+            if !self.shared.receiver_connected.load(Ordering::Acquire) {
+                return Err(SendError(value));
+            }
+            match self.shared.queue.push(value) {
+                Ok(()) => return Ok(()),
+                Err(returned) => {
+                    value = returned;
+                    ring_wait(&backoff);
+                }
+            }
+        }
+    }
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        if !self.shared.receiver_connected.load(Ordering::Acquire) {
+            return Err(TrySendError::Disconnected(value));
+        }
+        self.shared.queue.push(value).map_err(TrySendError::Full)
+    }
+    pub fn send_timeout(&self, mut value: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
+        let deadline = Instant::now() + timeout;
+        let backoff = Backoff::new();
+
+        loop {
+            match self.try_send(value) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Disconnected(returned)) => return Err(SendTimeoutError::Disconnected(returned)),
+                Err(TrySendError::Full(returned)) => value = returned
+            }
+            if Instant::now() >= deadline {
+                return Err(SendTimeoutError::Timeout(value));
+            }
+            ring_wait(&backoff);
+        }
+    }
+}
+impl<T> Drop for RingSender<T> {
+    fn drop(&mut self) {
+        self.shared.sender_connected.store(false, Ordering::Release);
+    }
+}
+
+
+#[derive(Debug)]
+pub struct RingReceiver<T> {
+    shared: Arc<RingShared<T>>
+}
+impl<T> RingReceiver<T> {
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let backoff = Backoff::new();
+
+        loop {
+            if let Some(value) = self.shared.queue.pop() {
+                return Ok(value);
+            }
+            if !self.shared.sender_connected.load(Ordering::Acquire) {
+                // the sender may have pushed a final value between the pop and the check
+                return self.shared.queue.pop().ok_or(RecvTimeoutError::Disconnected);
+            }
+            if Instant::now() >= deadline {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            ring_wait(&backoff);
+        }
+    }
+}
+impl<T> Drop for RingReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.receiver_connected.store(false, Ordering::Release);
+    }
+}
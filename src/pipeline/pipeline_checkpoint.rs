@@ -0,0 +1,406 @@
+// Checkpoints save what a running pipeline has built up, so a long processing job can be stopped and resumed where it was instead of
+// starting over: the state of every step which implements Checkpointable (delay lines, phases, read positions, path metrics) and the
+// values waiting on every link. Steps without Checkpointable take part with their links only, they are assumed to keep no state.
+//
+// The requests are commands on each node's control queue, handled between two work calls like control messages. A checkpoint first
+// pauses the pipeline and waits until every node has paused, taking whatever waits on the inputs of a paused node off the channel, so
+// an upstream node blocked on a full link can finish its call and pause too. Once no node runs, nothing moves and every node is saved.
+// The values taken off the channels are held by the receiving end of their link and received before the channel, nothing is lost.
+// A restore goes the same way into a pipeline built like the one that was saved, and replaces whatever waits on its links. Every node
+// checks its part first and only once all of them would take it is any node restored.
+// The nodes are only run between work calls by the thread per node and thread pool execution models, not the deterministic executor.
+//
+// The container is little endian throughout, strings and byte blocks are a u32 length followed by the bytes:
+//     header:   b"ZCKP", version u8, node count u32
+//     nodes:    node id, whether the step state follows u8, step state, input link count u32
+//     links:    feedback default still to come u8, message count u32
+//     messages: kind u8 (0 data, 1 end of stream), then for data the value as a byte block encoded by its HasEncoding and tag count
+//               u32 followed by every tag as key, offset u64, value kind u8 (0 flag, 1 integer, 2 float, 3 text) and value
+
+use std::any::type_name;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use super::byte_codec::{ByteReader, ByteWriter};
+use super::pipeline_comms::{LinkMessage, NodeReceiver};
+use super::pipeline_errors::CheckpointError;
+use super::pipeline_step::PipelineStep;
+use super::pipeline_thread::PipelineThread;
+use super::pipeline_traits::Sharable;
+use super::stream_tags::{StreamTag, TagValue};
+use super::logging::{log_message, Level};
+
+
+const MAGIC: &[u8; 4] = b"ZCKP";
+const VERSION: u8 = 1;
+pub const CHECKPOINT_TIMEOUT: Duration = Duration::from_secs(10); // for every node to pause, and then to answer
+const QUIESCE_POLL_TIME: Duration = Duration::from_millis(5);
+
+
+pub trait Checkpointable {
+    // state a step builds up while it runs, which a freshly built step would not have. The configuration the step is built with is not saved,
+    // a checkpoint is restored into steps built the same way
+    fn save_state(&self, state: &mut ByteWriter);
+    // called between work calls, on a paused node. Should only change the step if the whole state is valid
+    fn restore_state(&mut self, state: &mut ByteReader) -> Result<(), String>;
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SavedMessage {
+    Data { value: Vec<u8>, tags: Vec<StreamTag> },
+    EndOfStream
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkSnapshot {
+    pub startup_pending: bool, // a feedback link which had not given its first default value yet
+    pub messages: Vec<SavedMessage>
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeSnapshot {
+    pub node_id: String,
+    pub step_state: Option<Vec<u8>>, // None for a step which does not implement Checkpointable
+    pub links: Vec<LinkSnapshot> // every input link, in the order they were added to the node
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CheckpointSummary {
+    pub nodes: usize,
+    pub stateful_steps: usize, // the steps whose state was saved or restored
+    pub in_flight: usize // values waiting on the links, end of stream markers included
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    pub nodes: Vec<NodeSnapshot>
+}
+impl Checkpoint {
+    pub fn summary(&self) -> CheckpointSummary {
+        CheckpointSummary {
+            nodes: self.nodes.len(),
+            stateful_steps: self.nodes.iter().filter(|node| node.step_state.is_some()).count(),
+            in_flight: self.nodes.iter().flat_map(|node| node.links.iter()).map(|link| link.messages.len()).sum()
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<CheckpointSummary, CheckpointError> {
+        // written next to path and moved over it once complete, so a crash while saving leaves the previous checkpoint intact
+        let path = path.as_ref();
+        let partial = path.with_extension("partial");
+        let file = File::create(&partial).map_err(|error| CheckpointError::Io(error.to_string()))?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(&self.encode())
+            .and_then(|_| writer.flush())
+            .and_then(|_| std::fs::rename(&partial, path))
+            .map_err(|error| CheckpointError::Io(error.to_string()))?;
+        Ok(self.summary())
+    }
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CheckpointError> {
+        let file = File::open(path).map_err(|error| CheckpointError::Io(error.to_string()))?;
+        let mut bytes = Vec::new();
+        BufReader::new(file).read_to_end(&mut bytes).map_err(|error| CheckpointError::Io(error.to_string()))?;
+        Self::decode(&bytes).map_err(CheckpointError::Format)
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = ByteWriter::new();
+        MAGIC.iter().for_each(|byte| out.put_u8(*byte));
+        out.put_u8(VERSION);
+        out.put_u32(self.nodes.len() as u32);
+
+        for node in self.nodes.iter() {
+            out.put_str(&node.node_id);
+            out.put_bool(node.step_state.is_some());
+            if let Some(state) = &node.step_state {
+                out.put_bytes(state);
+            }
+            out.put_u32(node.links.len() as u32);
+            for link in node.links.iter() {
+                out.put_bool(link.startup_pending);
+                out.put_u32(link.messages.len() as u32);
+                link.messages.iter().for_each(|message| encode_message(message, &mut out));
+            }
+        }
+        out.into_bytes()
+    }
+    fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = ByteReader::new(bytes);
+        if reader.take(4).ok() != Some(MAGIC.as_slice()) {
+            return Err(String::from("not a checkpoint file"));
+        }
+        let version = reader.u8()?;
+        if version != VERSION {
+            return Err(format!("unsupported checkpoint version {}", version));
+        }
+
+        let mut nodes = Vec::new();
+        for _ in 0..reader.u32()? {
+            let node_id = reader.string()?;
+            let step_state = match reader.bool()? {
+                true => Some(reader.bytes()?.to_vec()),
+                false => None
+            };
+            let mut links = Vec::new();
+            for _ in 0..reader.u32()? {
+                let startup_pending = reader.bool()?;
+                let messages = (0..reader.u32()?).map(|_| decode_message(&mut reader)).collect::<Result<_, _>>()?;
+                links.push(LinkSnapshot { startup_pending, messages });
+            }
+            nodes.push(NodeSnapshot { node_id, step_state, links });
+        }
+        match reader.at_end() {
+            true => Ok(Checkpoint { nodes }),
+            false => Err(String::from("unexpected data after the last node"))
+        }
+    }
+}
+
+fn encode_message(message: &SavedMessage, out: &mut ByteWriter) {
+    match message {
+        SavedMessage::EndOfStream => out.put_u8(1),
+        SavedMessage::Data { value, tags } => {
+            out.put_u8(0);
+            out.put_bytes(value);
+            out.put_u32(tags.len() as u32);
+            for tag in tags.iter() {
+                out.put_str(&tag.key);
+                out.put_u64(tag.offset as u64);
+                match &tag.value {
+                    TagValue::Flag => out.put_u8(0),
+                    TagValue::Integer(value) => { out.put_u8(1); out.put_i64(*value) },
+                    TagValue::Float(value) => { out.put_u8(2); out.put_f64(*value) },
+                    TagValue::Text(value) => { out.put_u8(3); out.put_str(value) }
+                }
+            }
+        }
+    }
+}
+
+fn decode_message(reader: &mut ByteReader) -> Result<SavedMessage, String> {
+    match reader.u8()? {
+        1 => Ok(SavedMessage::EndOfStream),
+        0 => {
+            let value = reader.bytes()?.to_vec();
+            let mut tags = Vec::new();
+            for _ in 0..reader.u32()? {
+                let key = reader.string()?;
+                let offset = reader.u64()? as usize;
+                let value = match reader.u8()? {
+                    0 => TagValue::Flag,
+                    1 => TagValue::Integer(reader.i64()?),
+                    2 => TagValue::Float(reader.f64()?),
+                    3 => TagValue::Text(reader.string()?),
+                    kind => return Err(format!("unknown tag value kind {}", kind))
+                };
+                tags.push(StreamTag { key, offset, value });
+            }
+            Ok(SavedMessage::Data { value, tags })
+        },
+        kind => Err(format!("unknown message kind {}", kind))
+    }
+}
+
+
+pub enum CheckpointRequest {
+    /*
+    Quiesce: take the values waiting on the inputs off their channels and tell whether the node has paused
+    Snapshot: save the step state and the held values of a paused node
+    Validate: check that a paused node would take the snapshot, without changing it
+    Restore: replace the step state and the values waiting on the links of a paused node
+     */
+    Quiesce,
+    Snapshot,
+    Validate(NodeSnapshot),
+    Restore(NodeSnapshot)
+}
+
+
+pub enum CheckpointReply {
+    Paused(bool),
+    Snapshot(NodeSnapshot),
+    Valid,
+    Restored
+}
+
+
+pub struct CheckpointTicket {
+    node_id: String,
+    reply: mpsc::Receiver<Result<CheckpointReply, CheckpointError>>
+}
+impl CheckpointTicket {
+    pub fn new(node_id: &str, reply: mpsc::Receiver<Result<CheckpointReply, CheckpointError>>) -> Self {
+        Self { node_id: node_id.to_string(), reply }
+    }
+    pub fn wait(&self, timeout: Duration) -> Result<CheckpointReply, CheckpointError> {
+        match self.reply.recv_timeout(timeout) {
+            Ok(reply) => reply,
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(CheckpointError::Timeout { node_id: self.node_id.clone() }),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(CheckpointError::NodeStopped { node_id: self.node_id.clone() })
+        }
+    }
+}
+
+
+pub fn handle_request<I: Sharable, O: Sharable>(request: CheckpointRequest, step: &mut impl PipelineStep<I, O>, input: &mut NodeReceiver<I>, paused: bool, node_id: &str) -> Result<CheckpointReply, CheckpointError> {
+    // run by the node when the request comes off its control queue
+    match request {
+        CheckpointRequest::Quiesce => {
+            input.links_mut().into_iter().for_each(|link| { link.hold_waiting(); });
+            Ok(CheckpointReply::Paused(paused))
+        },
+        _ if !paused => Err(CheckpointError::Rejected { node_id: node_id.to_string(), message: String::from("the node is not paused") }),
+        CheckpointRequest::Snapshot => snapshot_node(step, input, node_id).map(CheckpointReply::Snapshot),
+        CheckpointRequest::Validate(snapshot) => validate_node(step, input, &snapshot, node_id).map(|_| CheckpointReply::Valid),
+        CheckpointRequest::Restore(snapshot) => restore_node(step, input, snapshot, node_id).map(|_| CheckpointReply::Restored)
+    }
+}
+
+
+fn snapshot_node<I: Sharable, O: Sharable>(step: &mut impl PipelineStep<I, O>, input: &mut NodeReceiver<I>, node_id: &str) -> Result<NodeSnapshot, CheckpointError> {
+    let step_state = step.checkpointable().map(|step| {
+        let mut state = ByteWriter::new();
+        step.save_state(&mut state);
+        state.into_bytes()
+    });
+
+    let mut links = Vec::new();
+    for link in input.links_mut() {
+        let startup_pending = link.startup_pending();
+        let messages = link.hold_waiting().iter().map(|message| match message {
+            LinkMessage::EndOfStream => Ok(SavedMessage::EndOfStream),
+            LinkMessage::Data(value, tags) => value.to_bytes()
+                .map(|value| SavedMessage::Data { value, tags: tags.clone() })
+                .map_err(|message| CheckpointError::NotEncodable { node_id: node_id.to_string(), message })
+        }).collect::<Result<_, _>>()?;
+        links.push(LinkSnapshot { startup_pending, messages });
+    }
+
+    Ok(NodeSnapshot { node_id: node_id.to_string(), step_state, links })
+}
+
+fn decode_links<I: Sharable>(input: &mut NodeReceiver<I>, links: &[LinkSnapshot], node_id: &str) -> Result<Vec<(VecDeque<LinkMessage<I>>, bool)>, CheckpointError> {
+    let rejected = |message: String| CheckpointError::Rejected { node_id: node_id.to_string(), message };
+    let link_count = input.links_mut().len();
+    if link_count != links.len() {
+        return Err(rejected(format!("the checkpoint holds {} input links, the node has {}", links.len(), link_count)));
+    }
+
+    links.iter().map(|link| {
+        let messages = link.messages.iter().map(|message| match message {
+            SavedMessage::EndOfStream => Ok(LinkMessage::EndOfStream),
+            SavedMessage::Data { value, tags } => I::from_bytes(value)
+                .map(|value| LinkMessage::Data(value, tags.clone()))
+                .map_err(|message| rejected(format!("a saved value is not a valid {}: {}", type_name::<I>(), message)))
+        }).collect::<Result<VecDeque<_>, _>>()?;
+        Ok((messages, link.startup_pending))
+    }).collect()
+}
+
+fn validate_node<I: Sharable, O: Sharable>(step: &mut impl PipelineStep<I, O>, input: &mut NodeReceiver<I>, snapshot: &NodeSnapshot, node_id: &str) -> Result<(), CheckpointError> {
+    // tries everything restore_node does without keeping any of it. The step state goes through the step's own restore and the step
+    // is always given back the state it had, which it saved itself and so takes again, even when it rejected the saved one halfway through
+    let rejected = |message: String| CheckpointError::Rejected { node_id: node_id.to_string(), message };
+    decode_links(input, &snapshot.links, node_id)?;
+
+    match (&snapshot.step_state, step.checkpointable()) {
+        (Some(state), Some(step)) => {
+            let mut current = ByteWriter::new();
+            step.save_state(&mut current);
+            let candidate = step.restore_state(&mut ByteReader::new(state));
+            let reverted = step.restore_state(&mut ByteReader::new(&current.into_bytes()));
+            candidate.and(reverted).map_err(rejected)
+        },
+        (Some(_), None) => Err(CheckpointError::NotCheckpointable { node_id: node_id.to_string() }),
+        (None, _) => Ok(())
+    }
+}
+
+fn restore_node<I: Sharable, O: Sharable>(step: &mut impl PipelineStep<I, O>, input: &mut NodeReceiver<I>, snapshot: NodeSnapshot, node_id: &str) -> Result<(), CheckpointError> {
+    // everything is decoded before anything is changed, so a checkpoint which does not fit leaves the node as it was
+    let restored_links = decode_links(input, &snapshot.links, node_id)?;
+
+    match (snapshot.step_state, step.checkpointable()) {
+        (Some(state), Some(step)) => step.restore_state(&mut ByteReader::new(&state))
+            .map_err(|message| CheckpointError::Rejected { node_id: node_id.to_string(), message })?,
+        (Some(_), None) => return Err(CheckpointError::NotCheckpointable { node_id: node_id.to_string() }),
+        (None, _) => ()
+    }
+
+    for (link, (messages, startup_pending)) in input.links_mut().iter_mut().zip(restored_links) {
+        let discarded = link.restore_held(messages, startup_pending);
+        if discarded > 0 {
+            log_message(format!("NodeID: {} discarded {} values waiting on an input link for the restored ones", node_id, discarded), Level::Warn);
+        }
+    }
+    log_message(format!("NodeID: {} restored from a checkpoint", node_id), Level::Info);
+    Ok(())
+}
+
+
+fn wait_all(tickets: Vec<CheckpointTicket>, deadline: Instant) -> Result<Vec<CheckpointReply>, CheckpointError> {
+    tickets.iter().map(|ticket| ticket.wait(deadline.saturating_duration_since(Instant::now()))).collect()
+}
+
+fn request_all(nodes: &[PipelineThread], mut request: impl FnMut(&PipelineThread) -> CheckpointRequest) -> Result<Vec<CheckpointTicket>, CheckpointError> {
+    nodes.iter().map(|thread| thread.checkpoint_request(request(thread))).collect()
+}
+
+pub fn quiesce_nodes(nodes: &[PipelineThread]) -> Result<(), CheckpointError> {
+    // for a pipeline which was asked to pause, waits until every node has. A node blocked on a full link pauses once the node it sends to
+    // has taken the waiting values off the channel
+    let deadline = Instant::now() + CHECKPOINT_TIMEOUT;
+    loop {
+        let replies = wait_all(request_all(nodes, |_| CheckpointRequest::Quiesce)?, deadline)?;
+        let running = nodes.iter().zip(replies).find(|(_, reply)| !matches!(reply, CheckpointReply::Paused(true)));
+
+        match running {
+            None => return Ok(()),
+            Some((thread, _)) if Instant::now() >= deadline => return Err(CheckpointError::Timeout { node_id: thread.id.clone() }),
+            Some(_) => sleep(QUIESCE_POLL_TIME)
+        }
+    }
+}
+
+pub fn snapshot_nodes(nodes: &[PipelineThread]) -> Result<Checkpoint, CheckpointError> { // every node must have paused
+    let deadline = Instant::now() + CHECKPOINT_TIMEOUT;
+    let replies = wait_all(request_all(nodes, |_| CheckpointRequest::Snapshot)?, deadline)?;
+    let nodes = replies.into_iter().filter_map(|reply| match reply {
+        CheckpointReply::Snapshot(snapshot) => Some(snapshot),
+        _ => None
+    }).collect();
+    Ok(Checkpoint { nodes })
+}
+
+pub fn restore_nodes(nodes: &[PipelineThread], checkpoint: Checkpoint) -> Result<CheckpointSummary, CheckpointError> { // every node must have paused
+    let summary = checkpoint.summary();
+    if let Some(unknown) = checkpoint.nodes.iter().find(|snapshot| !nodes.iter().any(|thread| thread.id == snapshot.node_id)) {
+        return Err(CheckpointError::UnknownNode { node_id: unknown.node_id.clone() });
+    }
+    if let Some(missing) = nodes.iter().find(|thread| !checkpoint.nodes.iter().any(|snapshot| snapshot.node_id == thread.id)) {
+        return Err(CheckpointError::MissingNode { node_id: missing.id.clone() });
+    }
+
+    // every node checks its part before any node is restored, so a checkpoint which one of them rejects leaves the whole pipeline as it was
+    let mut snapshots = checkpoint.nodes;
+    let snapshot_of = |thread: &PipelineThread| snapshots.iter().find(|snapshot| snapshot.node_id == thread.id).unwrap().clone();
+    let deadline = Instant::now() + CHECKPOINT_TIMEOUT;
+    wait_all(request_all(nodes, |thread| CheckpointRequest::Validate(snapshot_of(thread)))?, deadline)?;
+
+    let deadline = Instant::now() + CHECKPOINT_TIMEOUT;
+    let tickets = request_all(nodes, |thread| {
+        let index = snapshots.iter().position(|snapshot| snapshot.node_id == thread.id).unwrap();
+        CheckpointRequest::Restore(snapshots.swap_remove(index))
+    });
+    wait_all(tickets?, deadline)?;
+    Ok(summary)
+}
@@ -1,9 +1,13 @@
-use std::sync::mpsc::{Receiver, SyncSender, SendError, RecvTimeoutError};
+use std::collections::VecDeque;
+use std::sync::mpmc::{SendError, RecvTimeoutError, SendTimeoutError, TrySendError};
+use super::pipeline_channels::{link_channel, BackpressurePolicy, ChannelBackend, LinkEvictor, LinkReceiver, LinkSender};
 use std::time::Duration;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use strum::Display;
-use super::pipeline_traits::{Sharable, HasDefault};
+use super::pipeline_traits::{Sharable, HasDefault, HasSampleCount};
+use super::pipeline_thread_orchestrator::ExecutionModel;
+use super::stream_tags::{StreamTag, join_series_tags, merge_parallel_tags, split_series_tags};
 
 
 #[derive(Debug, Clone, Display)]
@@ -14,13 +18,15 @@ pub enum ReceiveType<T: Sharable> {
     Reassembled: Multiple values were received and they were reassembled from a series sender somewhere previously
     Multichannel: This data is the aggregation of data received from multiple channels simultaneously
     Dummy: this channel is not configured
+    EndOfStream: every input of the node has ended, nothing more will arrive. Handled by the node itself, never passed to the step
     
     The pipeline step must handle how the internal behavior responds to each of these types!
      */
     Single(T),
     Reassembled(Vec<T>),
     Multichannel(Vec<T>),
-    Dummy
+    Dummy,
+    EndOfStream
 }
 impl<T: Sharable> ReceiveType<T> {
     pub fn to_string(&self) -> &str {
@@ -28,12 +34,60 @@ impl<T: Sharable> ReceiveType<T> {
             Self::Single(_) => "Single",
             Self::Reassembled(_) => "Reassembled",
             Self::Multichannel(_) => "Multichannel",
-            Self::Dummy => "Dummy"
+            Self::Dummy => "Dummy",
+            Self::EndOfStream => "EndOfStream"
+        }
+    }
+    pub fn counts(&self) -> (usize, usize) { // (items, samples) received, for the node metrics
+        match self {
+            Self::Single(value) => (1, value.sample_count()),
+            Self::Reassembled(values) | Self::Multichannel(values) => (values.len(), values.iter().map(|value| value.sample_count()).sum()),
+            Self::Dummy | Self::EndOfStream => (0, 0)
         }
     }
 }
 
 
+#[derive(Debug, Clone)]
+pub enum LinkMessage<T> {
+    /*
+    What actually travels over a link between two nodes
+    Data: one value sent by the upstream step, with the stream tags that belong to its samples
+    EndOfStream: the upstream node has flushed its step and will never send again. Always the last message on a link
+     */
+    Data(T, Vec<StreamTag>),
+    EndOfStream
+}
+
+
+#[derive(Debug)]
+struct LinkState<T> {
+    /*
+    Shared by both ends of a link
+    end_sent: set once the end of stream is on the link, so a receiver can tell it is ready without taking the marker off the link
+    policy: what the sender does when the link is full. Set through the receiving end while the pipeline is built, the sender is already owned by its node
+    evictor: a handle on the receiving end for the drop oldest policy
+    dropped: how many values the sender discarded under its policy
+     */
+    end_sent: AtomicBool,
+    policy: RwLock<BackpressurePolicy>,
+    evictor: Mutex<Option<LinkEvictor<LinkMessage<T>>>>,
+    dropped: Arc<AtomicUsize>,
+}
+
+
+pub fn wrapped_link<T: Sharable>(backend: ChannelBackend, capacity: usize) -> (WrappedSender<T>, WrappedReceiver<T>) {
+    let (sender, receiver) = link_channel::<LinkMessage<T>>(backend, capacity);
+    let link = Arc::new(LinkState {
+        end_sent: AtomicBool::new(false),
+        policy: RwLock::new(BackpressurePolicy::Block),
+        evictor: Mutex::new(None),
+        dropped: Arc::new(AtomicUsize::new(0))
+    });
+    (WrappedSender { sender, link: link.clone() }, WrappedReceiver::new(receiver, link))
+}
+
+
 #[derive(Debug, Clone)]
 pub enum ODFormat<T: Sharable> { // Output Data Format
     /*
@@ -62,6 +116,13 @@ pub enum ODFormat<T: Sharable> { // Output Data Format
     Standard(T)
 }
 impl<T: Sharable> ODFormat<T> {
+    pub fn counts(&self) -> (usize, usize) { // (items, samples) as they will be sent, so Repeat counts every repeat
+        match self {
+            ODFormat::Decompose(values) | ODFormat::Series(values) => (values.len(), values.iter().map(|value| value.sample_count()).sum()),
+            ODFormat::Repeat(value, repeats) => (*repeats, value.sample_count() * repeats),
+            ODFormat::Standard(value) => (1, value.sample_count())
+        }
+    }
     pub fn unwrap_standard(self) -> T {
         match self {
             ODFormat::Standard(x) => x,
@@ -71,80 +132,230 @@ impl<T: Sharable> ODFormat<T> {
 }
 
 
+#[derive(Debug)]
+pub struct WrappedSender<T: Sharable> {
+    sender: LinkSender<LinkMessage<T>>,
+    link: Arc<LinkState<T>>
+}
+impl<T: Sharable> WrappedSender<T> {
+    pub fn send(&self, value: T, tags: Vec<StreamTag>) -> Result<(), SendError<T>> {
+        let message = LinkMessage::Data(value, tags);
+        let policy = *self.link.policy.read().unwrap();
+
+        let result = match policy {
+            BackpressurePolicy::Block => self.sender.send(message),
+            BackpressurePolicy::DropNewest => self.send_or_drop(message),
+            BackpressurePolicy::DropOldest => self.send_evicting(message),
+            BackpressurePolicy::BlockThenDrop(timeout) => match self.sender.send_timeout(message, Duration::from_millis(timeout)) {
+                Ok(()) => Ok(()),
+                Err(SendTimeoutError::Timeout(_)) => { self.count_dropped(); Ok(()) },
+                Err(SendTimeoutError::Disconnected(message)) => Err(SendError(message))
+            }
+        };
+        result.map_err(|SendError(message)| match message {
+            LinkMessage::Data(value, _) => SendError(value),
+            LinkMessage::EndOfStream => unreachable!("only data was sent")
+        })
+    }
+    fn send_or_drop(&self, message: LinkMessage<T>) -> Result<(), SendError<LinkMessage<T>>> {
+        match self.sender.try_send(message) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => { self.count_dropped(); Ok(()) },
+            Err(TrySendError::Disconnected(message)) => Err(SendError(message))
+        }
+    }
+    fn send_evicting(&self, mut message: LinkMessage<T>) -> Result<(), SendError<LinkMessage<T>>> {
+        let evictor = self.link.evictor.lock().unwrap();
+        let evictor = match evictor.as_ref() {
+            Some(evictor) => evictor,
+            None => return self.send_or_drop(message)
+        };
+        loop {
+            match self.sender.try_send(message) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Disconnected(returned)) => return Err(SendError(returned)),
+                Err(TrySendError::Full(returned)) => {
+                    message = returned;
+                    if evictor.evict().is_some() { self.count_dropped() } // otherwise the receiver took it first and there is room now
+                }
+            }
+        }
+    }
+    fn count_dropped(&self) {
+        self.link.dropped.fetch_add(1, Ordering::AcqRel);
+    }
+    pub fn send_end_of_stream(&self) -> Result<(), SendError<()>> {
+        let result = self.sender.send(LinkMessage::EndOfStream).map_err(|_| SendError(()));
+        self.link.end_sent.store(true, Ordering::Release);
+        result
+    }
+    pub fn has_room(&self) -> bool { // only a blocking link can hold its sender up
+        match *self.link.policy.read().unwrap() {
+            BackpressurePolicy::Block => self.sender.has_room(),
+            _ => true
+        }
+    }
+    pub fn dropped_counter(&self) -> Arc<AtomicUsize> {
+        self.link.dropped.clone()
+    }
+}
+
+
 #[derive(Debug)]
 pub struct WrappedReceiver<T: Sharable> {
-    receiver: Receiver<T>,
-    feedback_startup_flag: bool
+    receiver: LinkReceiver<LinkMessage<T>>,
+    link: Arc<LinkState<T>>,
+    ended: bool, // the end of stream was received, every later receive returns it straight away
+    feedback: bool,
+    feedback_startup_flag: bool,
+    startup_immediate: bool, // the deterministic executor gives the first feedback default without reading the link, one thread cannot wait on it
+    held: VecDeque<LinkMessage<T>> // taken off the channel for a checkpoint, or put back by a restore. Received before the channel
 }
 impl<T: Sharable + HasDefault> WrappedReceiver<T> {
-    pub fn new(receiver: Receiver<T>) -> Self {
-        WrappedReceiver { receiver, feedback_startup_flag: false }
+    fn new(receiver: LinkReceiver<LinkMessage<T>>, link: Arc<LinkState<T>>) -> Self {
+        WrappedReceiver { receiver, link, ended: false, feedback: false, feedback_startup_flag: false, startup_immediate: false, held: VecDeque::new() }
     }
-    pub fn set_startup_flag(mut self) -> Self {
+    pub fn set_backpressure(&self, policy: BackpressurePolicy) -> Result<(), String> {
+        policy.check_link(self.receiver.capacity(), false)?;
+        if policy == BackpressurePolicy::DropOldest {
+            *self.link.evictor.lock().unwrap() = Some(self.receiver.evictor());
+        }
+        *self.link.policy.write().unwrap() = policy;
+        Ok(())
+    }
+    pub fn backpressure(&self) -> BackpressurePolicy {
+        *self.link.policy.read().unwrap()
+    }
+    pub fn queue_depth(&self) -> (usize, usize) { // (values waiting in the channel, channel capacity)
+        (self.receiver.len() + self.held.len(), self.receiver.capacity().unwrap_or(0))
+    }
+    pub fn has_input(&self) -> bool { // whether a receive would return without waiting
+        self.has_inputs(1)
+    }
+    pub fn has_inputs(&self, count: usize) -> bool {
+        // a bounded link never holds more than its capacity, so a full link is as ready as it will get. A rendezvous link is never ready in advance
+        // once the end of stream is on the link whatever is left of a series in front of it is all that will ever arrive
+        let waiting = self.receiver.len() + self.held.len();
+        self.feedback_startup_flag || self.ended || self.link.end_sent.load(Ordering::Acquire) || match self.receiver.capacity() {
+            Some(0) => true,
+            Some(capacity) => waiting >= count.min(capacity),
+            None => waiting >= count
+        }
+    }
+    pub fn set_startup_flag(mut self, execution_model: ExecutionModel) -> Self {
+        self.feedback = true;
         self.feedback_startup_flag = true;
+        self.startup_immediate = execution_model == ExecutionModel::Deterministic;
         self
     }
-    fn result_handler(&self, result: &mut Result<T, RecvTimeoutError>, retry_num: &mut usize, retries: usize) -> bool {
+    pub fn is_feedback(&self) -> bool {
+        self.feedback
+    }
+    pub fn has_ended(&self) -> bool {
+        self.ended
+    }
+    pub fn hold_waiting(&mut self) -> &VecDeque<LinkMessage<T>> {
+        // takes everything waiting on the channel into the held values, so a paused node's input can be saved without losing it
+        while let Ok(message) = self.receiver.recv_timeout(Duration::ZERO) {
+            self.held.push_back(message);
+        }
+        &self.held
+    }
+    pub fn startup_pending(&self) -> bool { // a feedback link which has not given its first default value yet
+        self.feedback_startup_flag
+    }
+    pub fn restore_held(&mut self, held: VecDeque<LinkMessage<T>>, startup_pending: bool) -> usize {
+        // replaces whatever is waiting on the link, returns how many of those values were discarded
+        let discarded = self.hold_waiting().len();
+        self.held = held;
+        self.feedback_startup_flag = self.feedback && startup_pending;
+        discarded
+    }
+    fn result_handler(&mut self, result: &mut Result<LinkMessage<T>, RecvTimeoutError>, retry_num: &mut usize, retries: usize) -> bool {
         match result {
             Err(err) => {
                 match err { RecvTimeoutError::Timeout => *retry_num += 1, _ => *retry_num = retries};
                 false
             },
-            Ok(_) => true
+            Ok(LinkMessage::EndOfStream) => { self.ended = true; true },
+            Ok(LinkMessage::Data(..)) => true
         }
     }
-    pub fn recv(&mut self, timeout: u64, retries: usize) -> Result<T, RecvTimeoutError> {
-        let mut retry_num = 0;
-        let mut success_flag = false;
-        
-        let mut result = Err(RecvTimeoutError::Timeout);
-        let mut success_flag = false;
+    pub fn recv(&mut self, timeout: u64, retries: usize) -> Result<LinkMessage<T>, RecvTimeoutError> {
+        if self.ended {
+            return Ok(LinkMessage::EndOfStream);
+        }
+        if self.feedback_startup_flag && self.startup_immediate { // nothing can have been fed back before the first call, so dont wait for it
+            self.feedback_startup_flag = false;
+            return Ok(LinkMessage::Data(T::default(), Vec::new()));
+        }
 
-        while retry_num < retries && !success_flag {
-            result = self.receiver.recv_timeout(Duration::from_millis(timeout));
+        let result = match self.held.pop_front() {
+            Some(message) => {
+                self.ended = matches!(message, LinkMessage::EndOfStream);
+                Ok(message)
+            },
+            None => {
+                let mut retry_num = 0;
+                let mut result = Err(RecvTimeoutError::Timeout);
+                let mut success_flag = false;
 
-            success_flag = self.result_handler(&mut result, &mut retry_num, retries);
+                while retry_num < retries && !success_flag {
+                    result = self.receiver.recv_timeout(Duration::from_millis(timeout));
+
+                    success_flag = self.result_handler(&mut result, &mut retry_num, retries);
+                };
+                result
+            }
         };
-        if self.feedback_startup_flag {
+        if self.feedback_startup_flag { // the first receive of a feedback link gives the default in place of whatever arrived
             self.feedback_startup_flag = false;
-            Ok(T::default())
+            return Ok(LinkMessage::Data(T::default(), Vec::new()));
         }
-        else { result }
+        result
     }
 }
 
 #[derive(Debug)]
 pub struct SingleSender<T: Sharable> {
-    sender: SyncSender<T>,
+    sender: WrappedSender<T>,
 }
 impl<T: Sharable> SingleSender<T> {
-    pub fn new(sender: SyncSender<T>) -> Self {
+    pub fn new(sender: WrappedSender<T>) -> Self {
         SingleSender { sender }
     }
-    fn repeat_send(&mut self, value: T, repeats: usize, result: &mut Result<(), SendError<T>>) {
+    fn repeat_send(&mut self, value: T, repeats: usize, tags: Vec<StreamTag>, result: &mut Result<(), SendError<T>>) {
+        let mut tags = Some(tags); // only the first repeat is tagged, the others are copies of samples that were already tagged
         for _ in 0..repeats {
-            *result = self.sender.send(value.clone());
+            *result = self.sender.send(value.clone(), tags.take().unwrap_or_default());
             match &result { Err(_) => { break }, _ => () }
         }
     }
-    fn series_send(&mut self, value: Vec<T>, result: &mut Result<(), SendError<T>>) {
-        for point in value {
-            *result = self.sender.send(point);
+    fn series_send(&mut self, value: Vec<T>, tags: Vec<StreamTag>, result: &mut Result<(), SendError<T>>) {
+        let point_tags = split_series_tags(&value, tags);
+        for (point, tags) in value.into_iter().zip(point_tags) {
+            *result = self.sender.send(point, tags);
             match &result { Err(_) => { break }, _ => () }
         }
     }
-    pub fn send(&mut self, value: ODFormat<T>) -> Result<(), SendError<T>> {
+    pub fn send(&mut self, value: ODFormat<T>, tags: Vec<StreamTag>) -> Result<(), SendError<T>> {
         let mut result = Ok(());
         
         match value {
             ODFormat::Decompose(mut data_vec) => result = Err(SendError(data_vec.pop().unwrap())),
-            ODFormat::Standard(value) => result = self.sender.send(value),
-            ODFormat::Repeat(value, repeats) => self.repeat_send(value, repeats, &mut result),
-            ODFormat::Series(value) => self.series_send(value, &mut result),
+            ODFormat::Standard(value) => result = self.sender.send(value, tags),
+            ODFormat::Repeat(value, repeats) => self.repeat_send(value, repeats, tags, &mut result),
+            ODFormat::Series(value) => self.series_send(value, tags, &mut result),
         }
         
         result
     }
+    pub fn send_end_of_stream(&mut self) -> Result<(), SendError<()>> {
+        self.sender.send_end_of_stream()
+    }
+    pub fn has_room(&self) -> bool {
+        self.sender.has_room()
+    }
 }
 
 #[derive(Debug)]
@@ -157,19 +368,26 @@ impl<T: Sharable> SingleReceiver<T> {
     pub fn new(receiver: WrappedReceiver<T>, timeout: u64, retries: usize) -> SingleReceiver<T> {
         SingleReceiver { receiver, timeout,  retries }
     }
-    pub fn receive(&mut self) -> Result<ReceiveType<T>, RecvTimeoutError> {
+    pub fn receive(&mut self) -> Result<(ReceiveType<T>, Vec<StreamTag>), RecvTimeoutError> {
         match self.receiver.recv(self.timeout, self.retries) {
-            Ok(result) => Ok(ReceiveType::Single(result)),
+            Ok(LinkMessage::Data(result, tags)) => Ok((ReceiveType::Single(result), tags)),
+            Ok(LinkMessage::EndOfStream) => Ok((ReceiveType::EndOfStream, Vec::new())),
             Err(err) => Err(err)
         }
     }
     pub fn extract_receiver(self) -> WrappedReceiver<T> {
         self.receiver
     }
+    pub fn queue_depth(&self) -> (usize, usize) {
+        self.receiver.queue_depth()
+    }
+    pub fn has_input(&self) -> bool {
+        self.receiver.has_input()
+    }
 }
 
 pub struct MultichannelSender<T: Sharable> {
-    senders: Vec<SyncSender<T>>,
+    senders: Vec<WrappedSender<T>>,
 }
 impl<T: Sharable> MultichannelSender<T> {
     pub fn new() -> MultichannelSender<T> {
@@ -177,49 +395,56 @@ impl<T: Sharable> MultichannelSender<T> {
             senders: Vec::new(),
         }
     }
-    fn decompose_send(&mut self, value: Vec<T>, result: &mut Result<(), SendError<T>>) {
+    fn decompose_send(&mut self, value: Vec<T>, tags: Vec<StreamTag>, result: &mut Result<(), SendError<T>>) { // every part is tagged the same
         for (sender, point) in self.senders.iter_mut().zip(value) {
-            *result = sender.send(point);
+            *result = sender.send(point, tags.clone());
             match &result { Err(_) => { break }, _ => () }
         }
     }
-    fn standard_send(&mut self, value: T, result: &mut Result<(), SendError<T>>) {
+    fn standard_send(&mut self, value: T, tags: Vec<StreamTag>, result: &mut Result<(), SendError<T>>) {
         for index in 0..self.senders.len() {
-            *result = self.senders[index].send(value.clone());
+            *result = self.senders[index].send(value.clone(), tags.clone());
             match &result { Err(_) => { break }, _ => () }
         }
     }
-    fn series_send(&mut self, value: Vec<T>, result: &mut Result<(), SendError<T>>) {
-        for point in value {
+    fn series_send(&mut self, value: Vec<T>, tags: Vec<StreamTag>, result: &mut Result<(), SendError<T>>) {
+        let point_tags = split_series_tags(&value, tags);
+        for (point, tags) in value.into_iter().zip(point_tags) {
             for sender in self.senders.iter_mut() {
-                *result = sender.send(point.clone());
+                *result = sender.send(point.clone(), tags.clone());
                 match &result { Err(_) => { break }, _ => () }
             }
         }
     }
-    fn repeat_send(&mut self, value: T, repeats: usize, result: &mut Result<(), SendError<T>>) {
-        for _ in 0..repeats {
+    fn repeat_send(&mut self, value: T, repeats: usize, tags: Vec<StreamTag>, result: &mut Result<(), SendError<T>>) {
+        for repeat in 0..repeats {
             for sender in self.senders.iter_mut() {
-                *result = sender.send(value.clone());
+                *result = sender.send(value.clone(), if repeat == 0 { tags.clone() } else { Vec::new() });
                 match &result { Err(_) => { break }, _ => () }
             }
         }
     }
-    pub fn send_all(&mut self, data: ODFormat<T>) -> Result<(), SendError<T>> { // all branches must be ready to receive
+    pub fn send_all(&mut self, data: ODFormat<T>, tags: Vec<StreamTag>) -> Result<(), SendError<T>> { // all branches must be ready to receive
         let mut result = Ok(());
         
         match data {
-            ODFormat::Standard(value) => self.standard_send(value, &mut result),
-            ODFormat::Decompose(value) => self.decompose_send(value, &mut result),
-            ODFormat::Series(value) => self.series_send(value, &mut result),
-            ODFormat::Repeat(value, repeats) => self.repeat_send(value, repeats, &mut result),
+            ODFormat::Standard(value) => self.standard_send(value, tags, &mut result),
+            ODFormat::Decompose(value) => self.decompose_send(value, tags, &mut result),
+            ODFormat::Series(value) => self.series_send(value, tags, &mut result),
+            ODFormat::Repeat(value, repeats) => self.repeat_send(value, repeats, tags, &mut result),
         }
         
         result
     }
-    pub fn add_sender(&mut self, sender: SyncSender<T>) { 
+    pub fn add_sender(&mut self, sender: WrappedSender<T>) { 
         self.senders.push(sender);
     }
+    pub fn send_end_of_stream(&mut self) -> Result<(), SendError<()>> { // every branch is ended, even if an earlier one failed
+        self.senders.iter().map(|sender| sender.send_end_of_stream()).fold(Ok(()), |result, sent| result.and(sent))
+    }
+    pub fn has_room(&self) -> bool {
+        self.senders.iter().all(|sender| sender.has_room())
+    }
 }
 
 
@@ -234,24 +459,36 @@ impl<T: Sharable> MultichannelReceiver<T> {
     pub fn new(timeout: u64, retries: usize) ->  Self {
         MultichannelReceiver { receivers: Vec::new(), timeout, retries }
     }    
-    fn receive_handler(result: Result<T, RecvTimeoutError>, proceed_flag: &mut bool, output: &mut Vec<T>, return_value: &mut Result<ReceiveType<T>, RecvTimeoutError>) {
+    fn receive_handler(result: Result<LinkMessage<T>, RecvTimeoutError>, proceed_flag: &mut bool, output: &mut Vec<T>, tags: &mut Vec<Vec<StreamTag>>, return_value: &mut Result<(ReceiveType<T>, Vec<StreamTag>), RecvTimeoutError>) {
         match result {
-            Ok(received) => { if *proceed_flag { output.push(received) }; }
+            Ok(LinkMessage::Data(received, received_tags)) => { if *proceed_flag { output.push(received); tags.push(received_tags) }; }
+            Ok(LinkMessage::EndOfStream) => { *proceed_flag = false; *return_value = Ok((ReceiveType::EndOfStream, Vec::new())); } // one ended input ends the joint
             Err(error) => { *proceed_flag = false; *return_value = Err(error); }
         }
     }
-    pub fn receive(&mut self) -> Result<ReceiveType<T>, RecvTimeoutError> {
+    fn has_ended(&self) -> bool {
+        // feedback inputs only end after the joint itself has, so the joint ends as soon as any of its other inputs does
+        self.receivers.iter().filter(|receiver| !receiver.is_feedback()).any(|receiver| receiver.has_ended())
+    }
+    pub fn receive(&mut self) -> Result<(ReceiveType<T>, Vec<StreamTag>), RecvTimeoutError> {
+        if self.has_ended() {
+            return Ok((ReceiveType::EndOfStream, Vec::new()));
+        }
         let mut output = Vec::with_capacity(self.receivers.len());
+        let mut tags = Vec::with_capacity(self.receivers.len());
         let mut proceed_flag = true;
-        let mut return_value = Ok(ReceiveType::Multichannel(Vec::new()));
+        let mut return_value = Ok((ReceiveType::Multichannel(Vec::new()), Vec::new()));
         
         for receiver in self.receivers.iter_mut() {
             let received = receiver.recv(self.timeout, self.retries);
-            Self::receive_handler(received, &mut proceed_flag, &mut output, &mut return_value);
+            Self::receive_handler(received, &mut proceed_flag, &mut output, &mut tags, &mut return_value);
         }
         
-        if output.len() == self.receivers.len() {
-            return_value = Ok(ReceiveType::Multichannel(output));
+        if self.has_ended() {
+            return_value = Ok((ReceiveType::EndOfStream, Vec::new()));
+        }
+        else if output.len() == self.receivers.len() {
+            return_value = Ok((ReceiveType::Multichannel(output), merge_parallel_tags(tags)));
         }
         
         return_value
@@ -259,44 +496,54 @@ impl<T: Sharable> MultichannelReceiver<T> {
     pub fn add_receiver(&mut self, receiver: WrappedReceiver<T>) {
         self.receivers.push(receiver);
     }
+    pub fn queue_depth(&self) -> (usize, usize) {
+        self.receivers.iter()
+            .map(|receiver| receiver.queue_depth())
+            .fold((0, 0), |total, depth| (total.0 + depth.0, total.1 + depth.1))
+    }
+    pub fn has_input(&self) -> bool { // every channel is received from in turn, so all of them must hold a value
+        self.receivers.iter().all(|receiver| receiver.has_input())
+    }
 }
 
 
 #[derive(Debug)]
 pub struct Multiplexer<T: Sharable> {
-    senders: Vec<SyncSender<T>>,
+    senders: Vec<WrappedSender<T>>,
     channel: Arc<AtomicUsize> // external control for the channel selection
 }
 impl<T: Sharable> Multiplexer<T> {
     pub fn new(channel: Arc<AtomicUsize>) -> Multiplexer<T> {
         Multiplexer { senders: Vec::new(), channel }
     }
-    pub fn send(&mut self, input: ODFormat<T>) -> Result<(), SendError<T>> {
+    pub fn send(&mut self, input: ODFormat<T>, tags: Vec<StreamTag>) -> Result<(), SendError<T>> {
         match self.senders.get_mut(self.channel.load(Ordering::Acquire)) {
-            Some(sender) => Self::send_logic(sender, input),
+            Some(sender) => Self::send_logic(sender, input, tags),
             None => Err(SendError(self.index_error_unwrap(input)))
         }
     }
-    fn repeat_send(selected_sender: &mut SyncSender<T>, value: T, repeats: usize, result: &mut Result<(), SendError<T>>) {
+    fn repeat_send(selected_sender: &mut WrappedSender<T>, value: T, repeats: usize, tags: Vec<StreamTag>, result: &mut Result<(), SendError<T>>) {
+        let mut tags = Some(tags);
         for _ in 0..repeats {
-            *result = selected_sender.send(value.clone());
+            *result = selected_sender.send(value.clone(), tags.take().unwrap_or_default());
             match &result { Err(_) => { break }, _ => () }
         }
     }
-    fn series_send(selected_sender: &mut SyncSender<T>, value: Vec<T>, result: &mut Result<(), SendError<T>>) {
-        for unit in value  {
-            *result = selected_sender.send(unit);
+    fn series_send(selected_sender: &mut WrappedSender<T>, value: Vec<T>, tags: Vec<StreamTag>, result: &mut Result<(), SendError<T>>) {
+        let unit_tags = split_series_tags(&value, tags);
+        for (unit, tags) in value.into_iter().zip(unit_tags) {
+            *result = selected_sender.send(unit, tags);
             match &result { Err(_) => { break }, _ => () }
         }
     }
-    fn send_logic(selected_sender: &mut SyncSender<T>, input: ODFormat<T>) -> Result<(), SendError<T>> {
+    fn send_logic(selected_sender: &mut WrappedSender<T>, input: ODFormat<T>, tags: Vec<StreamTag>) -> Result<(), SendError<T>> {
         let mut result = Ok(());
         
         match input {
-            ODFormat::Standard(value) => result = selected_sender.send(value),
-            ODFormat::Repeat(value, repeats) => Self::repeat_send(selected_sender, value, repeats, &mut result),
+            ODFormat::Standard(value) => result = selected_sender.send(value, tags),
+            ODFormat::Repeat(value, repeats) => Self::repeat_send(selected_sender, value, repeats, tags, &mut result),
             ODFormat::Decompose(_) => panic!("ODFormat Decompose not compatible with multiplexer"),
-            ODFormat::Series(value) => Self::series_send(selected_sender, value, &mut result),
+            ODFormat::Series(value) => Self::series_send(selected_sender, value, tags, &mut result),
         }
         
         result
@@ -308,9 +555,25 @@ impl<T: Sharable> Multiplexer<T> {
         }
     }
 
-    pub fn add_sender(&mut self, sender: SyncSender<T>) {
+    pub fn add_sender(&mut self, sender: WrappedSender<T>) {
         self.senders.push(sender);
     }
+    pub fn send_end_of_stream(&mut self) -> Result<(), SendError<()>> {
+        // only the selected branch is running, the others are paused and may be holding a full link the end of stream would block on
+        match self.senders.get(self.channel.load(Ordering::Acquire)) {
+            Some(sender) => sender.send_end_of_stream(),
+            None => Err(SendError(()))
+        }
+    }
+    pub fn channel_selector(&self) -> Arc<AtomicUsize> {
+        self.channel.clone()
+    }
+    pub fn branches(&self) -> usize {
+        self.senders.len()
+    }
+    pub fn has_room(&self) -> bool { // an out of range channel is reported by send, so it counts as having room
+        self.senders.get(self.channel.load(Ordering::Acquire)).map_or(true, |sender| sender.has_room())
+    }
 }
 
 #[derive(Debug)]
@@ -324,10 +587,11 @@ impl<T: Sharable> Demultiplexer<T> {
     pub fn new(channel: Arc<AtomicUsize>, timeout: u64, retries: usize) -> Demultiplexer<T> {
         Demultiplexer { receivers: Vec::new(), channel, timeout, retries }
     }
-    pub fn receive(&mut self) -> Result<ReceiveType<T>, RecvTimeoutError> {
+    pub fn receive(&mut self) -> Result<(ReceiveType<T>, Vec<StreamTag>), RecvTimeoutError> {
         match self.receivers.get_mut(self.channel.load(Ordering::Acquire)) {
             Some(receiver) => { match receiver.recv(self.timeout, self.retries) {
-                Ok(received) => Ok(ReceiveType::Single(received)),
+                Ok(LinkMessage::Data(received, tags)) => Ok((ReceiveType::Single(received), tags)),
+                Ok(LinkMessage::EndOfStream) => Ok((ReceiveType::EndOfStream, Vec::new())),
                 Err(err) => Err(err)
             }},
             None => Err(RecvTimeoutError::Disconnected)
@@ -336,6 +600,14 @@ impl<T: Sharable> Demultiplexer<T> {
     pub fn add_receiver(&mut self, receiver: WrappedReceiver<T>) {
         self.receivers.push(receiver);
     }
+    pub fn queue_depth(&self) -> (usize, usize) {
+        self.receivers.iter()
+            .map(|receiver| receiver.queue_depth())
+            .fold((0, 0), |total, depth| (total.0 + depth.0, total.1 + depth.1))
+    }
+    pub fn has_input(&self) -> bool {
+        self.receivers.get(self.channel.load(Ordering::Acquire)).map_or(true, |receiver| receiver.has_input())
+    }
 }
 
 
@@ -351,17 +623,32 @@ impl<T: Sharable> Reassembler<T> {
         Self { receiver, num_receives, timeout, retries }
     }
     
-    pub fn receive(&mut self) -> Result<ReceiveType<T>, RecvTimeoutError> {
+    pub fn receive(&mut self) -> Result<(ReceiveType<T>, Vec<StreamTag>), RecvTimeoutError> {
         let mut receive_vec = Vec::with_capacity(self.num_receives);
+        let mut tags_vec = Vec::with_capacity(self.num_receives);
         
         for _ in 0..self.num_receives {
             match self.receiver.recv(self.timeout, self.retries) {
-                Ok(received) => receive_vec.push(received),
+                Ok(LinkMessage::Data(received, tags)) => { receive_vec.push(received); tags_vec.push(tags) },
+                Ok(LinkMessage::EndOfStream) => break,
                 Err(error) => return Err(error)
             }
         }
         
-        Ok(ReceiveType::Reassembled(receive_vec))
+        // a series cut short by the end of stream is still passed on, the end of stream itself comes with the next receive
+        match receive_vec.is_empty() && self.receiver.has_ended() {
+            true => Ok((ReceiveType::EndOfStream, Vec::new())),
+            false => {
+                let tags = join_series_tags(&receive_vec, tags_vec);
+                Ok((ReceiveType::Reassembled(receive_vec), tags))
+            }
+        }
+    }
+    pub fn queue_depth(&self) -> (usize, usize) {
+        self.receiver.queue_depth()
+    }
+    pub fn has_input(&self) -> bool { // the whole series, or as much of it as the link can hold
+        self.receiver.has_inputs(self.num_receives)
     }
 }
 
@@ -375,13 +662,59 @@ pub enum NodeReceiver<I: Sharable> {
     Dummy
 }
 impl<I: Sharable> NodeReceiver<I> {
-    pub fn receive(&mut self) -> Result<ReceiveType<I>, RecvTimeoutError> {
+    pub fn receive(&mut self) -> Result<(ReceiveType<I>, Vec<StreamTag>), RecvTimeoutError> {
         match self {
             NodeReceiver::SI(receiver) => receiver.receive(),
             NodeReceiver::MI(receiver) => receiver.receive(),
             NodeReceiver::REA(receiver) => receiver.receive(),
             NodeReceiver::DMI(receiver) => receiver.receive(),
-            NodeReceiver::Dummy => Ok(ReceiveType::Dummy)
+            NodeReceiver::Dummy => Ok((ReceiveType::Dummy, Vec::new()))
+        }
+    }
+    pub fn queue_depth(&self) -> (usize, usize) { // summed over every input channel of the node
+        match self {
+            NodeReceiver::SI(receiver) => receiver.queue_depth(),
+            NodeReceiver::MI(receiver) => receiver.queue_depth(),
+            NodeReceiver::REA(receiver) => receiver.queue_depth(),
+            NodeReceiver::DMI(receiver) => receiver.queue_depth(),
+            NodeReceiver::Dummy => (0, 0)
+        }
+    }
+    pub fn has_input(&self) -> bool { // used by the pool scheduler to only run nodes which will not block on their inputs
+        match self {
+            NodeReceiver::SI(receiver) => receiver.has_input(),
+            NodeReceiver::MI(receiver) => receiver.has_input(),
+            NodeReceiver::REA(receiver) => receiver.has_input(),
+            NodeReceiver::DMI(receiver) => receiver.has_input(),
+            NodeReceiver::Dummy => true
+        }
+    }
+    pub fn links_mut(&mut self) -> Vec<&mut WrappedReceiver<I>> { // every input link of the node, in the order they were added
+        match self {
+            NodeReceiver::SI(receiver) => vec![&mut receiver.receiver],
+            NodeReceiver::MI(receiver) => receiver.receivers.iter_mut().collect(),
+            NodeReceiver::REA(receiver) => vec![&mut receiver.receiver],
+            NodeReceiver::DMI(receiver) => receiver.receivers.iter_mut().collect(),
+            NodeReceiver::Dummy => Vec::new()
+        }
+    }
+    pub fn set_backpressure(&self, policy: BackpressurePolicy) -> Result<(), String> { // sets the policy of the link into the node, which must have a single input link
+        match self {
+            NodeReceiver::SI(receiver) => receiver.receiver.set_backpressure(policy),
+            NodeReceiver::REA(receiver) => {
+                policy.check_link(receiver.receiver.receiver.capacity(), true)?;
+                receiver.receiver.set_backpressure(policy)
+            },
+            _ => Err(String::from("must be a single receiver or reassembler node"))
+        }
+    }
+    pub fn shape_name(&self) -> &str {
+        match self {
+            NodeReceiver::SI(_) => "SI",
+            NodeReceiver::MI(_) => "MI",
+            NodeReceiver::REA(_) => "REA",
+            NodeReceiver::DMI(_) => "DMI",
+            NodeReceiver::Dummy => "Dummy"
         }
     }
 }
@@ -393,12 +726,44 @@ pub enum NodeSender<O: Sharable> {
     Dummy
 }
 impl <O: Sharable> NodeSender<O> {
-    pub fn send(&mut self, data: ODFormat<O>) -> Result<(), SendError<O>> {
+    pub fn send(&mut self, data: ODFormat<O>, tags: Vec<StreamTag>) -> Result<(), SendError<O>> {
         match self {
-            NodeSender::SO(sender) => sender.send(data),
-            NodeSender::MO(sender) => sender.send_all(data),
-            NodeSender::MUO(sender) => sender.send(data),
+            NodeSender::SO(sender) => sender.send(data, tags),
+            NodeSender::MO(sender) => sender.send_all(data, tags),
+            NodeSender::MUO(sender) => sender.send(data, tags),
             NodeSender::Dummy => Ok(())
         }
     }
+    pub fn send_end_of_stream(&mut self) -> Result<(), SendError<()>> {
+        match self {
+            NodeSender::SO(sender) => sender.send_end_of_stream(),
+            NodeSender::MO(sender) => sender.send_end_of_stream(),
+            NodeSender::MUO(sender) => sender.send_end_of_stream(),
+            NodeSender::Dummy => Ok(())
+        }
+    }
+    pub fn has_room(&self) -> bool {
+        match self {
+            NodeSender::SO(sender) => sender.has_room(),
+            NodeSender::MO(sender) => sender.has_room(),
+            NodeSender::MUO(sender) => sender.has_room(),
+            NodeSender::Dummy => true
+        }
+    }
+    pub fn dropped_counters(&self) -> Vec<Arc<AtomicUsize>> { // one per output link, counting the values its backpressure policy discarded
+        match self {
+            NodeSender::SO(sender) => vec![sender.sender.dropped_counter()],
+            NodeSender::MO(sender) => sender.senders.iter().map(|sender| sender.dropped_counter()).collect(),
+            NodeSender::MUO(sender) => sender.senders.iter().map(|sender| sender.dropped_counter()).collect(),
+            NodeSender::Dummy => Vec::new()
+        }
+    }
+    pub fn shape_name(&self) -> &str {
+        match self {
+            NodeSender::SO(_) => "SO",
+            NodeSender::MO(_) => "MO",
+            NodeSender::MUO(_) => "MUO",
+            NodeSender::Dummy => "Dummy"
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,233 @@
+// Declarative pipeline definitions. A definition file lists the pipeline parameters, the nodes (each naming a step from a StepRegistry
+// with its constructor parameters) and the connections between them. Fan out from a node becomes a split, fan in becomes a joint,
+// and a connection marked feedback is primed with a default value at startup like a lazy joint input.
+//
+// [parameters]
+// retries = 3
+// timeout = 1000
+// backpressure_val = 2
+// max_infrastructure_errors = 2
+// max_compute_errors = 3
+// unchanged_state_time = 100
+// channel_backend = "crossbeam" # optional, std by default and unbounded for the deterministic model
+// execution_model = { thread_pool = 4 } # optional, thread_per_node by default
+//
+// [[nodes]]
+// id = "tone"
+// step = "sinusoid"
+// params = { frequency = 440.0, sampling_frequency = 44100.0, phase = 0.0, buffer_size = 1024 }
+//
+// [[connections]]
+// from = "tone"
+// to = "decimate"
+// backpressure = "drop_oldest" # optional, block by default. { block_then_drop = 50 } waits 50ms before dropping
+
+use std::collections::HashMap;
+use std::path::Path;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use super::api::*;
+use super::pipeline_errors::PipelineConfigError;
+use super::pipeline_graph::GraphEdgeKind;
+use super::pipeline_registry::{NodeWiring, StepParameters, StepRegistry};
+
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParameterDefinition {
+    pub retries: usize,
+    pub timeout: u64,
+    pub backpressure_val: usize,
+    pub max_infrastructure_errors: usize,
+    pub max_compute_errors: usize,
+    pub unchanged_state_time: u64,
+    #[serde(default)]
+    pub channel_backend: Option<ChannelBackend>, // std, crossbeam, spsc_ring or unbounded. Unbounded for the deterministic model, std otherwise
+    #[serde(default)]
+    pub execution_model: ExecutionModel,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeDefinition {
+    pub id: String,
+    pub step: String,
+    #[serde(default)]
+    pub params: Map<String, Value>,
+    pub reassemble: Option<usize>, // receive this many values from a series sender and hand them to the step together
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConnectionDefinition {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub feedback: bool,
+    #[serde(default)]
+    pub backpressure: BackpressurePolicy,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PipelineDefinition {
+    pub parameters: ParameterDefinition,
+    #[serde(default)]
+    pub nodes: Vec<NodeDefinition>,
+    #[serde(default)]
+    pub connections: Vec<ConnectionDefinition>,
+}
+impl PipelineDefinition {
+    pub fn from_toml(source: &str) -> Result<Self, PipelineConfigError> {
+        toml::from_str(source).map_err(|err| PipelineConfigError::Parse {
+            line: err.span().map(|span| line_at_offset(source, span.start)),
+            message: err.message().to_string()
+        })
+    }
+    pub fn from_json(source: &str) -> Result<Self, PipelineConfigError> {
+        serde_json::from_str(source).map_err(|err| PipelineConfigError::Parse {
+            line: if err.line() > 0 { Some(err.line()) } else { None },
+            message: err.to_string()
+        })
+    }
+}
+
+
+fn line_at_offset(source: &str, offset: usize) -> usize {
+    source[..offset.min(source.len())].matches('\n').count() + 1
+}
+
+struct SourceLocator<'a> {
+    // finds lines for semantic errors, which serde no longer has spans for once the definition is parsed
+    source: Option<&'a str>
+}
+impl<'a> SourceLocator<'a> {
+    fn first_line_of(&self, value: &str) -> Option<usize> {
+        let quoted = format!("\"{}\"", value);
+        self.source?.lines().position(|line| line.contains(&quoted)).map(|index| index + 1)
+    }
+    fn last_line_of(&self, value: &str) -> Option<usize> { // references in connections come after the node definitions
+        let quoted = format!("\"{}\"", value);
+        let lines: Vec<&str> = self.source?.lines().collect();
+        lines.iter().rposition(|line| line.contains(&quoted)).map(|index| index + 1)
+    }
+}
+
+
+pub fn load_pipeline(path: impl AsRef<Path>, registry: &StepRegistry) -> Result<ActivePipeline, PipelineConfigError> {
+    let path = path.as_ref();
+    let source = std::fs::read_to_string(path).map_err(|err| PipelineConfigError::Io(format!("{}: {}", path.display(), err)))?;
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => pipeline_from_json(&source, registry),
+        _ => pipeline_from_toml(&source, registry)
+    }
+}
+
+pub fn pipeline_from_toml(source: &str, registry: &StepRegistry) -> Result<ActivePipeline, PipelineConfigError> {
+    let definition = PipelineDefinition::from_toml(source)?;
+    build_definition(definition, Some(source), registry)
+}
+
+pub fn pipeline_from_json(source: &str, registry: &StepRegistry) -> Result<ActivePipeline, PipelineConfigError> {
+    let definition = PipelineDefinition::from_json(source)?;
+    build_definition(definition, Some(source), registry)
+}
+
+pub fn pipeline_from_definition(definition: PipelineDefinition, registry: &StepRegistry) -> Result<ActivePipeline, PipelineConfigError> {
+    build_definition(definition, None, registry)
+}
+
+
+fn build_definition(definition: PipelineDefinition, source: Option<&str>, registry: &StepRegistry) -> Result<ActivePipeline, PipelineConfigError> {
+    let locator = SourceLocator { source };
+    let mut node_indices: HashMap<&str, usize> = HashMap::new();
+
+    for (index, node) in definition.nodes.iter().enumerate() {
+        if node_indices.insert(node.id.as_str(), index).is_some() {
+            return Err(PipelineConfigError::DuplicateNode { line: locator.last_line_of(&node.id), node_id: node.id.clone() });
+        }
+        if !registry.contains(&node.step) {
+            return Err(PipelineConfigError::UnknownStep { line: locator.first_line_of(&node.step), node_id: node.id.clone(), step: node.step.clone() });
+        }
+    }
+
+    let mut links = Vec::with_capacity(definition.connections.len());
+    for connection in definition.connections.iter() {
+        let from = *node_indices.get(connection.from.as_str())
+            .ok_or(PipelineConfigError::UnknownNode { line: locator.last_line_of(&connection.from), node_id: connection.from.clone() })?;
+        let to = *node_indices.get(connection.to.as_str())
+            .ok_or(PipelineConfigError::UnknownNode { line: locator.last_line_of(&connection.to), node_id: connection.to.clone() })?;
+
+        let from_factory = registry.get(&definition.nodes[from].step).unwrap();
+        let to_factory = registry.get(&definition.nodes[to].step).unwrap();
+        if from_factory.output_type() != to_factory.input_type() {
+            return Err(PipelineConfigError::TypeMismatch {
+                line: locator.last_line_of(&connection.to),
+                from: connection.from.clone(),
+                to: connection.to.clone(),
+                output_type: from_factory.output_type_name(),
+                input_type: to_factory.input_type_name()
+            });
+        }
+        connection.backpressure.check_link(None, definition.nodes[to].reassemble.is_some())
+            .map_err(|message| PipelineConfigError::InvalidParameters { line: locator.last_line_of(&connection.to), node_id: connection.to.clone(), message })?;
+        links.push((from, to, connection.feedback, connection.backpressure));
+    }
+    for (index, node) in definition.nodes.iter().enumerate() {
+        let fan_in = links.iter().filter(|(_, to, _, _)| *to == index).count();
+        if node.reassemble.is_some() && fan_in > 1 {
+            let message = format!("only a node with one input can reassemble, '{}' has {}", node.id, fan_in);
+            return Err(PipelineConfigError::InvalidParameters { line: locator.first_line_of(&node.id), node_id: node.id.clone(), message });
+        }
+    }
+
+    // every step is constructed before any thread is spawned, so invalid parameters cannot leave a half built pipeline running
+    let mut prepared_steps = Vec::with_capacity(definition.nodes.len());
+    for node in definition.nodes.iter() {
+        let parameters = StepParameters::new(&node.id, node.params.clone());
+        let prepared = registry.get(&node.step).unwrap().prepare(&parameters)
+            .map_err(|message| PipelineConfigError::InvalidParameters { line: locator.first_line_of(&node.id), node_id: node.id.clone(), message })?;
+        prepared_steps.push(prepared);
+    }
+
+    let parameters = definition.parameters;
+    let mut pipeline = ConstructingPipeline::new(parameters.retries, parameters.timeout, parameters.backpressure_val, parameters.max_infrastructure_errors, parameters.max_compute_errors, parameters.unchanged_state_time)
+        .with_execution_model(parameters.execution_model);
+    if let Some(channel_backend) = parameters.channel_backend {
+        pipeline = pipeline.with_channel_backend(channel_backend);
+    }
+    let channel_backend = pipeline.get_cloned_parameters().channel_backend;
+    let graph = pipeline.get_graph();
+    let construction_queue = pipeline.get_nodes();
+
+    let mut wirings: Vec<NodeWiring> = definition.nodes.iter()
+        .map(|node| NodeWiring {
+            id: node.id.clone(),
+            graph_index: graph.lock().unwrap().add_node(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            reassemble: node.reassemble
+        })
+        .collect();
+
+    let mut fan_out = vec![0; wirings.len()];
+    links.iter().for_each(|(from, _, _, _)| fan_out[*from] += 1);
+
+    for (from, to, feedback, backpressure) in links {
+        let (sender, receiver) = registry.get(&definition.nodes[from].step).unwrap().create_output_channel(channel_backend, parameters.backpressure_val, backpressure)
+            .map_err(|message| PipelineConfigError::InvalidParameters { line: locator.last_line_of(&definition.nodes[to].id), node_id: definition.nodes[to].id.clone(), message })?;
+        let kind = match (feedback, fan_out[from]) {
+            (true, _) => GraphEdgeKind::Feedback,
+            (false, 1) => GraphEdgeKind::Standard,
+            (false, _) => GraphEdgeKind::Split
+        };
+        graph.lock().unwrap().add_edge(wirings[from].graph_index, wirings[to].graph_index, parameters.backpressure_val, kind);
+
+        wirings[from].outputs.push(sender);
+        wirings[to].inputs.push((receiver, feedback));
+    }
+
+    for (prepared, wiring) in prepared_steps.into_iter().zip(wirings) {
+        log_message(format!("Spawning node {} from pipeline definition", wiring.id), Level::Debug);
+        construction_queue.push(prepared.spawn(wiring, pipeline.get_cloned_parameters(), pipeline.get_state_communicators(), &graph));
+    }
+
+    pipeline.finish_pipeline().map_err(PipelineConfigError::Build)
+}
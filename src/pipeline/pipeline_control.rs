@@ -0,0 +1,184 @@
+// Control messages change the configuration of a running step without rebuilding the pipeline, eg the frequency of a source.
+// Every node has its own out of band queue, which is emptied into PipelineStep::handle_control between work calls, so a step never
+// sees a change in the middle of a call. Each message is answered once it was handled, so whoever sent it knows whether it took effect.
+// The same queue carries the other commands which must reach a node between two work calls, a hot swap of its step and checkpoint requests.
+
+use std::any::Any;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+use super::pipeline_checkpoint::{handle_request, CheckpointReply, CheckpointRequest};
+use super::pipeline_comms::NodeReceiver;
+use super::pipeline_errors::{CheckpointError, ControlError};
+use super::pipeline_step::PipelineStep;
+use super::pipeline_swap::{take_swap, SwappableStep};
+use super::pipeline_traits::Sharable;
+use super::thread_state_space::ThreadStateSpace;
+use super::logging::{log_message, Level};
+
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ControlValue {
+    Flag, // the message itself is the command, eg a reset
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    FloatList(Vec<f32>)
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlMessage {
+    pub key: String,
+    pub value: ControlValue
+}
+impl ControlMessage {
+    pub fn new(key: &str, value: ControlValue) -> Self {
+        Self { key: key.to_string(), value }
+    }
+    fn type_error(&self, expected: &str) -> String {
+        format!("control '{}' must be {}", self.key, expected)
+    }
+    pub fn get_f32(&self) -> Result<f32, String> {
+        match self.value {
+            ControlValue::Float(value) => Ok(value as f32),
+            ControlValue::Integer(value) => Ok(value as f32),
+            _ => Err(self.type_error("a number"))
+        }
+    }
+    pub fn get_usize(&self) -> Result<usize, String> {
+        match self.value {
+            ControlValue::Integer(value) if value >= 0 => Ok(value as usize),
+            _ => Err(self.type_error("a positive integer"))
+        }
+    }
+    pub fn get_bool(&self) -> Result<bool, String> {
+        match self.value {
+            ControlValue::Bool(value) => Ok(value),
+            _ => Err(self.type_error("a boolean"))
+        }
+    }
+    pub fn get_string(&self) -> Result<String, String> {
+        match &self.value {
+            ControlValue::Text(value) => Ok(value.clone()),
+            _ => Err(self.type_error("a string"))
+        }
+    }
+    pub fn get_f32_vec(&self) -> Result<Vec<f32>, String> {
+        match &self.value {
+            ControlValue::FloatList(value) => Ok(value.clone()),
+            _ => Err(self.type_error("a list of numbers"))
+        }
+    }
+}
+
+
+pub enum NodeCommand {
+    // everything a node takes from its control queue, in the order it was sent, each with the channel its answer goes back on
+    Control(ControlMessage, mpsc::Sender<Result<(), String>>),
+    Swap(Box<dyn Any + Send>, mpsc::Sender<()>), // a Box<dyn PipelineStep<I, O>> of the node's own types, checked by the sender
+    Checkpoint(CheckpointRequest, mpsc::Sender<Result<CheckpointReply, CheckpointError>>)
+}
+
+
+pub fn control_port(node_id: &str) -> (ControlSender, ControlPort) {
+    let (sender, receiver) = mpsc::channel();
+    let pending = Arc::new(AtomicUsize::new(0));
+
+    (ControlSender { sender, pending: pending.clone(), node_id: node_id.to_string() }, ControlPort { receiver, pending })
+}
+
+
+#[derive(Clone)]
+pub struct ControlSender { // the pipeline side of a node's control queue
+    sender: mpsc::Sender<NodeCommand>,
+    pending: Arc<AtomicUsize>,
+    node_id: String
+}
+impl ControlSender {
+    pub fn send(&self, message: ControlMessage) -> Result<ControlTicket, ControlError> {
+        let key = message.key.clone();
+        match self.send_command(|reply| NodeCommand::Control(message, reply)) {
+            Some(reply) => Ok(ControlTicket { node_id: self.node_id.clone(), key, reply }),
+            None => Err(ControlError::NodeStopped { node_id: self.node_id.clone() })
+        }
+    }
+    pub fn send_command<R>(&self, command: impl FnOnce(mpsc::Sender<R>) -> NodeCommand) -> Option<mpsc::Receiver<R>> {
+        // queues the command with a fresh reply channel. None if the node is gone
+        let (reply, reply_receiver) = mpsc::channel();
+        self.pending.fetch_add(1, Ordering::AcqRel);
+        match self.sender.send(command(reply)) {
+            Ok(_) => Some(reply_receiver),
+            Err(_) => {
+                self.pending.fetch_sub(1, Ordering::AcqRel);
+                None
+            }
+        }
+    }
+}
+
+
+pub struct ControlPort { // the node side of its control queue, owned by whatever runs the node
+    receiver: mpsc::Receiver<NodeCommand>,
+    pending: Arc<AtomicUsize>
+}
+impl ControlPort {
+    pub fn has_pending(&self) -> bool {
+        self.pending.load(Ordering::Acquire) > 0
+    }
+    pub fn deliver<I: Sharable, O: Sharable, S: PipelineStep<I, O>>(&mut self, step: &mut SwappableStep<I, O, S>, input: &mut NodeReceiver<I>, state: &ThreadStateSpace, node_id: &str) {
+        // the replies are sent whether or not anyone still waits on them, the sender may have dropped its ticket
+        while let Ok(command) = self.receiver.try_recv() {
+            match command {
+                NodeCommand::Control(message, reply) => {
+                    let result = step.handle_control(&message);
+                    match &result {
+                        Ok(_) => log_message(format!("NodeID: {} applied control '{}'", node_id, message.key), Level::Info),
+                        Err(error) => log_message(format!("NodeID: {} rejected control '{}': {}", node_id, message.key, error), Level::Warn)
+                    }
+                    let _ = reply.send(result);
+                },
+                NodeCommand::Swap(replacement, reply) => {
+                    if take_swap(step, replacement, *state == ThreadStateSpace::RUNNING, node_id) {
+                        let _ = reply.send(());
+                    }
+                },
+                NodeCommand::Checkpoint(request, reply) => {
+                    let _ = reply.send(handle_request(request, step, input, *state == ThreadStateSpace::PAUSED, node_id));
+                }
+            }
+            self.pending.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+}
+
+
+pub struct ControlTicket {
+    /*
+    The acknowledgement of one control message. The reply arrives once the node handled the message between two work calls,
+    which can take as long as a call does. The deterministic executor only delivers messages while it runs
+     */
+    node_id: String,
+    key: String,
+    reply: mpsc::Receiver<Result<(), String>>
+}
+impl ControlTicket {
+    fn reply_to_result(&self, reply: Result<(), String>) -> Result<(), ControlError> {
+        reply.map_err(|message| ControlError::Rejected { node_id: self.node_id.clone(), key: self.key.clone(), message })
+    }
+    pub fn try_reply(&self) -> Option<Result<(), ControlError>> { // None while the node has not handled the message yet
+        match self.reply.try_recv() {
+            Ok(reply) => Some(self.reply_to_result(reply)),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some(Err(ControlError::NodeStopped { node_id: self.node_id.clone() }))
+        }
+    }
+    pub fn wait(&self, timeout: Duration) -> Result<(), ControlError> {
+        match self.reply.recv_timeout(timeout) {
+            Ok(reply) => self.reply_to_result(reply),
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(ControlError::Timeout { node_id: self.node_id.clone(), key: self.key.clone() }),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(ControlError::NodeStopped { node_id: self.node_id.clone() })
+        }
+    }
+}
@@ -0,0 +1,98 @@
+// pipeline! declares a flowgraph as its edges instead of as a sequence of builder calls, and expands to the very same calls
+// (start_pipeline, attach, split_begin/split_add/split_lock, joint_begin/branch_end/joint_lock and cap_pipeline)
+//
+//     pipeline!(&pipeline;
+//         "source" (Source::new()) -> "fir" (Fir::new(taps)) -> [
+//             "a" (BranchA::new()),
+//             "b" (BranchB::new()) -> "b gain" (Gain::new(2.0))
+//         ] -> "add" (Adder::new()) -> "sink" (Sink::new())
+//     );
+//
+// Every node is its id followed by its step in parentheses, nodes are joined by ->. A chain starts with a source and ends with a sink.
+// The node in front of a [ ] is the split, the branches in it are separated by commas and the node after the ] is the joint they are
+// merged into. A branch of _ links the split straight to the joint. Without a joint after the ], every branch ends with a sink of its
+// own, or in a branch of an enclosing split, is merged into that split's joint. Several chains are separated by semicolons.
+//
+// The types are checked by the builder calls the macro expands to, a step which does not take what the edge into it carries is
+// reported on that step. Reassemblers, backpressure policies, thread options, multiplexers and feedback still need the builders.
+
+use super::pipeline_step::{JointBuilder, NodeBuilder, PipelineStep};
+use super::pipeline_traits::Sharable;
+
+
+pub fn end_branch<I: Sharable, O: Sharable, F: Sharable>(branch: NodeBuilder<I, O>, joint: &mut JointBuilder<O, F>, id: &'static str, step: impl PipelineStep<I, O>) {
+    // attach and branch_end in one, so the joint fixes what the last step of the branch must give and a mismatch is reported on the step
+    branch.attach(id, step).branch_end(joint);
+}
+
+
+macro_rules! pipeline {
+    (@chains $pipeline:ident;) => {};
+    (@chains $pipeline:ident; $id:literal ($step:expr) $($rest:tt)*) => {
+        let builder = $crate::pipeline::api::NodeBuilder::start_pipeline($id, $step, $pipeline);
+        $crate::pipeline::api::pipeline!(@after $pipeline; builder; [cap]; $($rest)*);
+    };
+
+    (@end $pipeline:ident;) => {};
+    (@end $pipeline:ident; ; $($rest:tt)*) => {
+        $crate::pipeline::api::pipeline!(@chains $pipeline; $($rest)*);
+    };
+
+    // a split followed by its joint
+    (@after $pipeline:ident; $builder:ident; $tail:tt; -> $id:literal ($step:expr) -> [$($branches:tt)*] -> $joint_id:literal ($joint_step:expr) $($rest:tt)*) => {
+        let mut joint = $builder.joint_begin($joint_id);
+        let mut split = $builder.split_begin($id);
+        $crate::pipeline::api::pipeline!(@branches $pipeline; split; [joint joint]; [] $($branches)*);
+        split.split_lock($step);
+        let builder = joint.joint_lock($joint_step);
+        $crate::pipeline::api::pipeline!(@after $pipeline; builder; $tail; $($rest)*);
+    };
+    // a split whose branches end the way the chain around it ends
+    (@after $pipeline:ident; $builder:ident; $tail:tt; -> $id:literal ($step:expr) -> [$($branches:tt)*] $($rest:tt)*) => {
+        let mut split = $builder.split_begin($id);
+        $crate::pipeline::api::pipeline!(@branches $pipeline; split; $tail; [] $($branches)*);
+        split.split_lock($step);
+        $crate::pipeline::api::pipeline!(@end $pipeline; $($rest)*);
+    };
+    (@after $pipeline:ident; $builder:ident; $tail:tt; -> $id:literal ($step:expr) -> $($rest:tt)+) => {
+        let builder = $builder.attach($id, $step);
+        $crate::pipeline::api::pipeline!(@after $pipeline; builder; $tail; -> $($rest)+);
+    };
+    (@after $pipeline:ident; $builder:ident; [cap]; -> $id:literal ($step:expr) $($rest:tt)*) => {
+        $builder.cap_pipeline($id, $step);
+        $crate::pipeline::api::pipeline!(@end $pipeline; $($rest)*);
+    };
+    (@after $pipeline:ident; $builder:ident; [joint $joint:ident]; -> $id:literal ($step:expr)) => {
+        $crate::pipeline::api::end_branch($builder, &mut $joint, $id, $step);
+    };
+
+    // collects the tokens of each branch up to the next comma at the top level of the brackets
+    (@branches $pipeline:ident; $split:ident; $tail:tt; []) => {};
+    (@branches $pipeline:ident; $split:ident; $tail:tt; [$($branch:tt)+]) => {
+        $crate::pipeline::api::pipeline!(@branch $pipeline; $split; $tail; $($branch)+);
+    };
+    (@branches $pipeline:ident; $split:ident; $tail:tt; [$($branch:tt)+] , $($rest:tt)*) => {
+        $crate::pipeline::api::pipeline!(@branch $pipeline; $split; $tail; $($branch)+);
+        $crate::pipeline::api::pipeline!(@branches $pipeline; $split; $tail; [] $($rest)*);
+    };
+    (@branches $pipeline:ident; $split:ident; $tail:tt; [$($branch:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::pipeline::api::pipeline!(@branches $pipeline; $split; $tail; [$($branch)* $next] $($rest)*);
+    };
+
+    (@branch $pipeline:ident; $split:ident; [joint $joint:ident]; _) => {
+        $split.split_add().branch_end(&mut $joint);
+    };
+    (@branch $pipeline:ident; $split:ident; [cap]; _) => {
+        compile_error!("a branch of _ needs a joint after the branches to be merged into");
+    };
+    (@branch $pipeline:ident; $split:ident; $tail:tt; $($branch:tt)+) => {
+        let builder = $split.split_add();
+        $crate::pipeline::api::pipeline!(@after $pipeline; builder; $tail; -> $($branch)+);
+    };
+
+    ($pipeline:expr; $($graph:tt)+) => {{
+        let pipeline: &$crate::pipeline::api::ConstructingPipeline = &$pipeline;
+        $crate::pipeline::api::pipeline!(@chains pipeline; $($graph)+);
+    }};
+}
+pub(crate) use pipeline;
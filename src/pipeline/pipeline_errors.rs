@@ -0,0 +1,328 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use strum::Display;
+use super::pipeline_step::StepHandler;
+
+
+#[derive(Debug, PartialEq, Clone, Copy, Display)]
+pub enum StepErrorKind {
+    /*
+    Recoverable: the step failed on this input but will likely succeed on the next. The thread retries, and pauses the pipeline once max_compute_errors is exceeded
+    NeedsReset: the internal state of the step is no longer valid. The thread calls reset_behavior on the step and pauses the pipeline so it can be restarted cleanly
+    Fatal: the step can never produce valid output again. The thread kills the pipeline
+    EndOfStream: not a failure, the step has no more data to give (eg a source reached the end of its file). The node flushes its step and
+        passes the end of stream downstream, then idles until the pipeline is killed
+     */
+    Recoverable,
+    NeedsReset,
+    Fatal,
+    EndOfStream
+}
+
+
+#[derive(Debug, Clone)]
+pub struct StepError {
+    kind: StepErrorKind,
+    message: String,
+    source: Option<Arc<dyn Error + Send + Sync + 'static>> // arc so the error can be cloned into diagnostics
+}
+impl StepError {
+    pub fn new(kind: StepErrorKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into(), source: None }
+    }
+    pub fn recoverable(message: impl Into<String>) -> Self {
+        Self::new(StepErrorKind::Recoverable, message)
+    }
+    pub fn needs_reset(message: impl Into<String>) -> Self {
+        Self::new(StepErrorKind::NeedsReset, message)
+    }
+    pub fn fatal(message: impl Into<String>) -> Self {
+        Self::new(StepErrorKind::Fatal, message)
+    }
+    pub fn end_of_stream() -> Self {
+        Self::new(StepErrorKind::EndOfStream, "end of stream")
+    }
+    pub fn with_source(mut self, source: impl Error + Send + Sync + 'static) -> Self {
+        self.source = Some(Arc::new(source));
+        self
+    }
+    pub fn kind(&self) -> StepErrorKind {
+        self.kind
+    }
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+impl fmt::Display for StepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} step error: {}", self.kind, self.message)?;
+        if let Some(source) = &self.source {
+            write!(f, " (caused by: {})", source)?;
+        }
+        Ok(())
+    }
+}
+impl Error for StepError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_ref().map(|source| source.as_ref() as &(dyn Error + 'static))
+    }
+}
+impl PartialEq for StepError { // sources are not comparable, the kind and message identify the error
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.message == other.message
+    }
+}
+
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TopologyViolation {
+    pub node_id: String,
+    pub required_handler: Option<StepHandler>, // None when the receiver/sender combination cannot be dispatched to any handler
+    pub supported_handlers: Vec<StepHandler>
+}
+impl fmt::Display for TopologyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.required_handler {
+            Some(handler) => {
+                let supported: Vec<String> = self.supported_handlers.iter().map(|handler| handler.to_string()).collect();
+                write!(f, "node '{}' is wired as {} but its step only supports [{}]", self.node_id, handler, supported.join(", "))
+            },
+            None => write!(f, "node '{}' has a receiver/sender combination no step handler can process", self.node_id)
+        }
+    }
+}
+
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum SchedulingError {
+    Priority { node_id: String, message: String }, // the priority or policy was refused, usually for lack of privileges for the real time policies
+    Affinity { node_id: String, message: String },
+    Unsupported { node_id: String, message: String } // the node has no thread of its own to apply the options to
+}
+impl SchedulingError {
+    pub fn node_id(&self) -> &str {
+        match self {
+            SchedulingError::Priority { node_id, .. } |
+            SchedulingError::Affinity { node_id, .. } |
+            SchedulingError::Unsupported { node_id, .. } => node_id
+        }
+    }
+}
+impl fmt::Display for SchedulingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchedulingError::Priority { node_id, message } => write!(f, "could not set the priority of node '{}': {}", node_id, message),
+            SchedulingError::Affinity { node_id, message } => write!(f, "could not set the cpu affinity of node '{}': {}", node_id, message),
+            SchedulingError::Unsupported { node_id, message } => write!(f, "cannot apply thread options to node '{}': {}", node_id, message)
+        }
+    }
+}
+impl Error for SchedulingError {}
+
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PipelineBuildError {
+    InvalidTopology(Vec<TopologyViolation>),
+    Scheduling(Vec<SchedulingError>), // thread options which could not be applied and were not marked best effort
+    ConflictingParameters(String) // parameters which cannot work together, eg the deterministic execution model with bounded links
+}
+impl fmt::Display for PipelineBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipelineBuildError::InvalidTopology(violations) => {
+                let node_ids: Vec<&str> = violations.iter().map(|violation| violation.node_id.as_str()).collect();
+                writeln!(f, "invalid pipeline topology at nodes: {}", node_ids.join(", "))?;
+                for violation in violations {
+                    writeln!(f, "  {}", violation)?;
+                }
+                Ok(())
+            },
+            PipelineBuildError::Scheduling(errors) => {
+                let node_ids: Vec<&str> = errors.iter().map(|error| error.node_id()).collect();
+                writeln!(f, "could not apply thread options at nodes: {}", node_ids.join(", "))?;
+                for error in errors {
+                    writeln!(f, "  {}", error)?;
+                }
+                Ok(())
+            },
+            PipelineBuildError::ConflictingParameters(message) => write!(f, "conflicting pipeline parameters: {}", message)
+        }
+    }
+}
+impl Error for PipelineBuildError {}
+
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PipelineConfigError {
+    /*
+    Errors from loading a pipeline definition file. The line is the best guess at where in the file the problem is, None if it could not be located
+     */
+    Io(String),
+    Parse { line: Option<usize>, message: String },
+    DuplicateNode { line: Option<usize>, node_id: String },
+    UnknownStep { line: Option<usize>, node_id: String, step: String },
+    UnknownNode { line: Option<usize>, node_id: String }, // a connection refers to a node that is not defined
+    TypeMismatch { line: Option<usize>, from: String, to: String, output_type: &'static str, input_type: &'static str },
+    InvalidParameters { line: Option<usize>, node_id: String, message: String },
+    Build(PipelineBuildError)
+}
+impl PipelineConfigError {
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            PipelineConfigError::Parse { line, .. } |
+            PipelineConfigError::DuplicateNode { line, .. } |
+            PipelineConfigError::UnknownStep { line, .. } |
+            PipelineConfigError::UnknownNode { line, .. } |
+            PipelineConfigError::TypeMismatch { line, .. } |
+            PipelineConfigError::InvalidParameters { line, .. } => *line,
+            _ => None
+        }
+    }
+}
+impl fmt::Display for PipelineConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line() {
+            write!(f, "line {}: ", line)?;
+        }
+        match self {
+            PipelineConfigError::Io(message) => write!(f, "could not read pipeline definition: {}", message),
+            PipelineConfigError::Parse { message, .. } => write!(f, "could not parse pipeline definition: {}", message),
+            PipelineConfigError::DuplicateNode { node_id, .. } => write!(f, "node '{}' is defined more than once", node_id),
+            PipelineConfigError::UnknownStep { node_id, step, .. } => write!(f, "node '{}' uses step '{}' which is not in the registry", node_id, step),
+            PipelineConfigError::UnknownNode { node_id, .. } => write!(f, "connection refers to undefined node '{}'", node_id),
+            PipelineConfigError::TypeMismatch { from, to, output_type, input_type, .. } => write!(f, "cannot connect '{}' ({}) to '{}' ({})", from, output_type, to, input_type),
+            PipelineConfigError::InvalidParameters { message, .. } => write!(f, "{}", message),
+            PipelineConfigError::Build(error) => write!(f, "{}", error)
+        }
+    }
+}
+impl Error for PipelineConfigError {}
+
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ControlError {
+    UnknownNode { node_id: String },
+    NodeStopped { node_id: String }, // the node was killed, so the message will never be handled
+    Rejected { node_id: String, key: String, message: String }, // the step refused the message, its configuration is unchanged
+    Timeout { node_id: String, key: String }
+}
+impl fmt::Display for ControlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControlError::UnknownNode { node_id } => write!(f, "no node '{}' in the pipeline", node_id),
+            ControlError::NodeStopped { node_id } => write!(f, "node '{}' is stopped and cannot handle control messages", node_id),
+            ControlError::Rejected { node_id, key, message } => write!(f, "node '{}' rejected control '{}': {}", node_id, key, message),
+            ControlError::Timeout { node_id, key } => write!(f, "node '{}' did not handle control '{}' in time", node_id, key)
+        }
+    }
+}
+impl Error for ControlError {}
+
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum TapError {
+    UnknownNode { node_id: String },
+    WrongType { node_id: String, requested: &'static str, data_type: &'static str } // the handle was asked for a type the node does not carry
+}
+impl fmt::Display for TapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TapError::UnknownNode { node_id } => write!(f, "no node '{}' in the pipeline", node_id),
+            TapError::WrongType { node_id, requested, data_type } => write!(f, "cannot tap node '{}' as {}, it carries {}", node_id, requested, data_type)
+        }
+    }
+}
+impl Error for TapError {}
+
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum RecordingError {
+    Tap(TapError), // the node could not be subscribed to, eg it does not receive the type the recording was asked for
+    Io(String),
+    Format(String) // the file is not a recording of the expected payload type, or was cut short
+}
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordingError::Tap(error) => write!(f, "{}", error),
+            RecordingError::Io(message) => write!(f, "could not access recording: {}", message),
+            RecordingError::Format(message) => write!(f, "invalid recording: {}", message)
+        }
+    }
+}
+impl Error for RecordingError {}
+
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum RemoteError {
+    Io(String), // the socket could not be bound, connected to, read or written
+    NotLoopback(String), // the control server only listens on loopback addresses, it has no authentication
+    AddressInUse(String), // a unix socket path belongs to a running server, or to a file which is not a socket and is left alone
+    Protocol(String), // a message was not valid json, or not the reply that was expected
+    Rejected { command: String, message: String } // the server could not carry out the command, eg the node does not exist
+}
+impl fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RemoteError::Io(message) => write!(f, "remote connection failed: {}", message),
+            RemoteError::NotLoopback(address) => write!(f, "refusing to listen on {}, only loopback addresses are allowed", address),
+            RemoteError::AddressInUse(address) => write!(f, "{} is in use or is not a socket", address),
+            RemoteError::Protocol(message) => write!(f, "invalid remote message: {}", message),
+            RemoteError::Rejected { command, message } => write!(f, "remote command '{}' failed: {}", command, message)
+        }
+    }
+}
+impl Error for RemoteError {}
+
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum SwapError {
+    UnknownNode { node_id: String },
+    TypeMismatch { node_id: String, expected: String, provided: String }, // the replacement takes or returns other types than the node's links carry
+    UnsupportedHandler(TopologyViolation), // the replacement does not support the handler the node is wired for
+    NodeStopped { node_id: String }, // the node was killed, so the replacement will never be taken in
+    Timeout { node_id: String }
+}
+impl fmt::Display for SwapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwapError::UnknownNode { node_id } => write!(f, "no node '{}' in the pipeline", node_id),
+            SwapError::TypeMismatch { node_id, expected, provided } => write!(f, "cannot swap a {} step into node '{}', it carries {}", provided, node_id, expected),
+            SwapError::UnsupportedHandler(violation) => write!(f, "cannot swap the step: {}", violation),
+            SwapError::NodeStopped { node_id } => write!(f, "node '{}' is stopped and cannot take a new step", node_id),
+            SwapError::Timeout { node_id } => write!(f, "node '{}' did not take the new step in time", node_id)
+        }
+    }
+}
+impl Error for SwapError {}
+
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum CheckpointError {
+    Io(String),
+    Format(String), // the file is not a checkpoint, or was cut short
+    UnknownNode { node_id: String }, // the checkpoint holds a node the pipeline does not have, it was built differently
+    MissingNode { node_id: String }, // the pipeline has a node the checkpoint does not hold
+    NotCheckpointable { node_id: String }, // the checkpoint holds state for a step which cannot take it
+    NotEncodable { node_id: String, message: String }, // a value waiting on an input link of the node has no HasEncoding to save it with
+    Rejected { node_id: String, message: String }, // the step or the links of the node did not accept the saved state
+    NodeStopped { node_id: String }, // the node was killed, so it will never answer
+    Timeout { node_id: String } // the node did not pause, or answer, in time
+}
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::Io(message) => write!(f, "could not access checkpoint: {}", message),
+            CheckpointError::Format(message) => write!(f, "invalid checkpoint: {}", message),
+            CheckpointError::UnknownNode { node_id } => write!(f, "the checkpoint holds node '{}' which is not in the pipeline", node_id),
+            CheckpointError::MissingNode { node_id } => write!(f, "the checkpoint holds nothing for node '{}'", node_id),
+            CheckpointError::NotCheckpointable { node_id } => write!(f, "the step of node '{}' cannot restore the state saved for it", node_id),
+            CheckpointError::NotEncodable { node_id, message } => write!(f, "the input of node '{}' cannot be saved: {}", node_id, message),
+            CheckpointError::Rejected { node_id, message } => write!(f, "node '{}' rejected its saved state: {}", node_id, message),
+            CheckpointError::NodeStopped { node_id } => write!(f, "node '{}' is stopped", node_id),
+            CheckpointError::Timeout { node_id } => write!(f, "node '{}' did not pause in time", node_id)
+        }
+    }
+}
+impl Error for CheckpointError {}
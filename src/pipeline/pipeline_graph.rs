@@ -0,0 +1,177 @@
+use std::fmt::Write;
+use std::sync::atomic::AtomicUsize;
+use std::sync::{Arc, Mutex};
+use serde::Serialize;
+use super::pipeline_step::{PipelineNode, StepHandler};
+use super::pipeline_comms::{NodeReceiver, NodeSender};
+use super::pipeline_traits::{HasID, Sharable};
+
+
+pub type GraphRecorder = Arc<Mutex<PipelineGraph>>;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, strum::Display)]
+pub enum GraphNodeKind {
+    Source,
+    Sink,
+    Step,
+    Split,
+    Joint,
+    Multiplexer,
+    Demultiplexer
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, strum::Display)]
+pub enum GraphEdgeKind {
+    Standard,
+    Split, // one branch of a split node
+    Multiplexed, // one selectable branch of a multiplexer
+    Feedback // lazy joint input, primed with a default value at startup
+}
+
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub index: usize,
+    pub id: String,
+    pub kind: GraphNodeKind,
+    pub input: String,
+    pub output: String,
+    pub handler: Option<StepHandler>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphEdge {
+    pub from: usize,
+    pub to: usize,
+    pub capacity: usize,
+    pub kind: GraphEdgeKind
+}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct PipelineGraph {
+    /*
+    Records the shape of a pipeline while the builders assemble it, since the nodes themselves are moved into their threads.
+    Every node gets an index as soon as it is created. Nodes which only exist as placeholders (eg the open end of a branch before branch_end)
+    are merged into the node that actually takes over their receiver, so only nodes with a running step remain once the pipeline is finished
+     */
+    nodes: Vec<Option<GraphNode>>, // None until the step is attached, or after a placeholder was merged away
+    edges: Vec<GraphEdge>,
+    selectors: Vec<(usize, Arc<AtomicUsize>)> // the channel selector of each multiplexer by its index, so the branch can be changed by node id
+}
+impl PipelineGraph {
+    pub fn new_recorder() -> GraphRecorder {
+        Arc::new(Mutex::new(PipelineGraph::default()))
+    }
+    pub fn add_node(&mut self) -> usize {
+        self.nodes.push(None);
+        self.nodes.len() - 1
+    }
+    pub fn add_edge(&mut self, from: usize, to: usize, capacity: usize, kind: GraphEdgeKind) {
+        self.edges.push(GraphEdge { from, to, capacity, kind });
+    }
+    pub fn add_selector(&mut self, multiplexer: usize, selector: Arc<AtomicUsize>) {
+        self.selectors.push((multiplexer, selector));
+    }
+    pub fn merge_node(&mut self, placeholder: usize, into: usize) {
+        // the placeholder's receiver was handed to another node, so its incoming edges now end there
+        for edge in self.edges.iter_mut().filter(|edge| edge.to == placeholder) {
+            edge.to = into;
+        }
+        self.nodes[placeholder] = None;
+    }
+    pub fn register_node<I: Sharable, O: Sharable>(&mut self, node: &PipelineNode<I, O>) {
+        let kind = match (&node.input, &node.output) {
+            (NodeReceiver::Dummy, _) => GraphNodeKind::Source,
+            (_, NodeSender::Dummy) => GraphNodeKind::Sink,
+            (_, NodeSender::MO(_)) => GraphNodeKind::Split,
+            (_, NodeSender::MUO(_)) => GraphNodeKind::Multiplexer,
+            (NodeReceiver::MI(_), _) => GraphNodeKind::Joint,
+            (NodeReceiver::DMI(_), _) => GraphNodeKind::Demultiplexer,
+            (_, _) => GraphNodeKind::Step
+        };
+
+        self.nodes[node.graph_index] = Some(GraphNode {
+            index: node.graph_index,
+            id: node.get_id(),
+            kind,
+            input: node.input.shape_name().to_string(),
+            output: node.output.shape_name().to_string(),
+            handler: node.required_handler(),
+        });
+    }
+
+    pub fn nodes(&self) -> Vec<&GraphNode> {
+        self.nodes.iter().flatten().collect()
+    }
+    pub fn edges(&self) -> Vec<&GraphEdge> { // edges whose endpoints both have a registered step
+        self.edges.iter()
+            .filter(|edge| self.get_node(edge.from).is_some() && self.get_node(edge.to).is_some())
+            .collect()
+    }
+    pub fn selector(&self, multiplexer_id: &str) -> Option<(Arc<AtomicUsize>, usize)> { // the selector and how many branches it chooses from
+        let (index, selector) = self.selectors.iter().find(|(index, _)| self.get_node(*index).is_some_and(|node| node.id == multiplexer_id))?;
+        let branches = self.edges().iter().filter(|edge| edge.from == *index && edge.kind == GraphEdgeKind::Multiplexed).count();
+        Some((selector.clone(), branches))
+    }
+    pub fn get_node(&self, index: usize) -> Option<&GraphNode> {
+        self.nodes.get(index).and_then(|node| node.as_ref())
+    }
+    pub fn find_node(&self, id: &str) -> Option<&GraphNode> {
+        self.nodes().into_iter().find(|node| node.id == id)
+    }
+    pub fn successors(&self, index: usize) -> Vec<&GraphNode> {
+        self.edges().into_iter()
+            .filter(|edge| edge.from == index)
+            .filter_map(|edge| self.get_node(edge.to))
+            .collect()
+    }
+    pub fn predecessors(&self, index: usize) -> Vec<&GraphNode> {
+        self.edges().into_iter()
+            .filter(|edge| edge.to == index)
+            .filter_map(|edge| self.get_node(edge.from))
+            .collect()
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph pipeline {\n    rankdir=LR;\n");
+
+        for node in self.nodes() {
+            let shape = match node.kind {
+                GraphNodeKind::Source | GraphNodeKind::Sink => "ellipse",
+                GraphNodeKind::Split | GraphNodeKind::Joint => "diamond",
+                GraphNodeKind::Multiplexer | GraphNodeKind::Demultiplexer => "trapezium",
+                GraphNodeKind::Step => "box"
+            };
+            let handler = node.handler.map(|handler| handler.to_string()).unwrap_or(String::from("none"));
+            writeln!(dot, "    n{} [label=\"{}\\n{} ({})\", shape={}];", node.index, Self::escape_dot(&node.id), node.kind, handler, shape).unwrap();
+        }
+        for edge in self.edges() {
+            let style = match edge.kind {
+                GraphEdgeKind::Feedback => "dashed",
+                GraphEdgeKind::Multiplexed => "dotted",
+                _ => "solid"
+            };
+            writeln!(dot, "    n{} -> n{} [label=\"{}\", style={}];", edge.from, edge.to, edge.capacity, style).unwrap();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+    fn escape_dot(label: &str) -> String {
+        label.replace('\\', "\\\\").replace('"', "\\\"")
+    }
+
+    pub fn to_json(&self) -> String {
+        let exported = ExportedGraph { nodes: self.nodes(), edges: self.edges() };
+        serde_json::to_string_pretty(&exported).unwrap()
+    }
+}
+
+
+#[derive(Serialize)]
+struct ExportedGraph<'a> { // only the live nodes and edges, placeholders are internal to construction
+    nodes: Vec<&'a GraphNode>,
+    edges: Vec<&'a GraphEdge>
+}
@@ -0,0 +1,129 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+
+const METRICS_WINDOW: usize = 256; // calls kept for the rolling rates and the compute time percentiles
+
+
+#[derive(Debug, Clone, Copy)]
+pub struct CallRecord {
+    pub items_in: usize,
+    pub items_out: usize,
+    pub samples_in: usize,
+    pub samples_out: usize,
+    pub receive_time: Duration, // blocked waiting on the input channels
+    pub compute_time: Duration,
+    pub send_time: Duration, // blocked waiting for space in the output channels
+}
+
+#[derive(Debug)]
+struct TimedRecord {
+    finished: Instant,
+    record: CallRecord
+}
+
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    window: VecDeque<TimedRecord>,
+    total_items_in: u64,
+    total_items_out: u64,
+    receive_blocked: Duration,
+    send_blocked: Duration,
+    queue_depth: (usize, usize),
+}
+
+
+#[derive(Debug, Default)]
+pub struct NodeMetrics {
+    /*
+    Written by the node thread once per call, read by diagnostics. The lock is only contended while a snapshot is being taken
+     */
+    state: Mutex<MetricsState>
+}
+impl NodeMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn record_call(&self, record: CallRecord) {
+        let mut state = self.state.lock().unwrap();
+
+        state.total_items_in += record.items_in as u64;
+        state.total_items_out += record.items_out as u64;
+        state.receive_blocked += record.receive_time;
+        state.send_blocked += record.send_time;
+
+        if state.window.len() == METRICS_WINDOW {
+            state.window.pop_front();
+        }
+        state.window.push_back(TimedRecord { finished: Instant::now(), record });
+    }
+    pub fn record_receive_failure(&self, receive_time: Duration) { // timeouts count as blocked time but not as a call
+        self.state.lock().unwrap().receive_blocked += receive_time;
+    }
+    pub fn record_queue_depth(&self, queue_depth: (usize, usize)) {
+        self.state.lock().unwrap().queue_depth = queue_depth;
+    }
+
+    pub fn snapshot(&self) -> NodeMetricsSnapshot {
+        let state = self.state.lock().unwrap();
+        let mut snapshot = NodeMetricsSnapshot {
+            total_items_in: state.total_items_in,
+            total_items_out: state.total_items_out,
+            receive_blocked: state.receive_blocked,
+            send_blocked: state.send_blocked,
+            queue_depth: state.queue_depth.0,
+            queue_capacity: state.queue_depth.1,
+            ..NodeMetricsSnapshot::default()
+        };
+
+        if let Some(first) = state.window.front() {
+            // rates are measured up to now rather than the last call, so they fall off when the node stalls
+            let span = first.finished.elapsed().as_secs_f64();
+            if span > 0.0 && state.window.len() > 1 {
+                let counted = state.window.iter().skip(1).map(|timed| timed.record);
+                let (items_in, items_out, samples_in, samples_out) = counted.fold((0, 0, 0, 0), |total, record| {
+                    (total.0 + record.items_in, total.1 + record.items_out, total.2 + record.samples_in, total.3 + record.samples_out)
+                });
+                snapshot.items_in_per_second = items_in as f64 / span;
+                snapshot.items_out_per_second = items_out as f64 / span;
+                snapshot.samples_in_per_second = samples_in as f64 / span;
+                snapshot.samples_out_per_second = samples_out as f64 / span;
+            }
+
+            let mut compute_times: Vec<Duration> = state.window.iter().map(|timed| timed.record.compute_time).collect();
+            compute_times.sort();
+            let p99_index = ((compute_times.len() as f64 * 0.99).ceil() as usize).saturating_sub(1);
+
+            snapshot.compute_time_min = compute_times[0];
+            snapshot.compute_time_mean = compute_times.iter().sum::<Duration>() / compute_times.len() as u32;
+            snapshot.compute_time_p99 = compute_times[p99_index];
+        }
+
+        snapshot
+    }
+}
+
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeMetricsSnapshot {
+    pub items_in_per_second: f64,
+    pub items_out_per_second: f64,
+    pub samples_in_per_second: f64, // counts the elements of Vec payloads, one per item otherwise
+    pub samples_out_per_second: f64,
+    pub compute_time_min: Duration,
+    pub compute_time_mean: Duration,
+    pub compute_time_p99: Duration,
+    pub receive_blocked: Duration, // totals since the node started
+    pub send_blocked: Duration,
+    pub total_items_in: u64,
+    pub total_items_out: u64,
+    pub queue_depth: usize, // values waiting in the input channels, summed over all inputs
+    pub queue_capacity: usize, // backpressure_val for every input channel
+}
+impl NodeMetricsSnapshot {
+    pub fn queue_occupancy(&self) -> f32 {
+        if self.queue_capacity == 0 { 0.0 } else { self.queue_depth as f32 / self.queue_capacity as f32 }
+    }
+}
@@ -0,0 +1,286 @@
+// Record the exact buffers entering a node to disk, and replay them later into the same step in isolation or into a fresh pipeline.
+// The recorder subscribes to the input tap of the node and writes from a thread of its own, so recording never holds up the node.
+// Like any tap it is lossy once it falls behind, the buffers it had to discard are counted in the summary returned by stop.
+//
+// The container is little endian throughout:
+//     header:  b"ZREC", version u8, payload type code u8, start of the recording in microseconds since the unix epoch u64,
+//              node id length u16 followed by the node id in utf8
+//     records: microseconds since the start u64, shape u8 (0 single, 1 reassembled, 2 multichannel, 3 end of stream),
+//              value count u32, then every value as its HasEncoding length u32 followed by the encoding
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, sleep, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use num::Complex;
+use super::byte_codec::{ByteReader, ByteWriter};
+use super::pipeline_comms::{ODFormat, ReceiveType};
+use super::pipeline_errors::{RecordingError, StepError};
+use super::pipeline_step::{PipelineStep, StepHandler};
+use super::pipeline_taps::{CapturedInput, TapHandle};
+use super::pipeline_traits::{HasEncoding, Sharable, Source};
+use super::logging::{log_message, Level};
+
+
+const MAGIC: &[u8; 4] = b"ZREC";
+const VERSION: u8 = 2;
+const RECORDER_POLL_TIME: Duration = Duration::from_millis(5);
+pub const RECORDER_CAPACITY: usize = 4096; // buffers the input tap holds for the recorder thread before it starts losing them
+
+
+pub trait Recordable: Sharable {
+    // a payload the recording container and the remote taps can hold, every value is saved with its HasEncoding.
+    // The type code is checked when a recording is loaded, so a capture of one type is never read as another
+    const TYPE_CODE: u8;
+}
+impl Recordable for Vec<f32> { const TYPE_CODE: u8 = 1; }
+impl Recordable for Vec<Complex<f32>> { const TYPE_CODE: u8 = 2; }
+impl Recordable for Vec<u8> { const TYPE_CODE: u8 = 3; }
+
+
+#[derive(Debug, Clone)]
+pub struct RecordedInput<T: Sharable> {
+    pub timestamp: Duration, // since the recording started
+    pub input: ReceiveType<T>
+}
+
+
+fn encode_record<T: Recordable>(record: &RecordedInput<T>) -> Result<Vec<u8>, String> {
+    let (shape, values): (u8, &[T]) = match &record.input {
+        ReceiveType::Single(value) => (0, std::slice::from_ref(value)),
+        ReceiveType::Reassembled(values) => (1, values),
+        ReceiveType::Multichannel(values) => (2, values),
+        ReceiveType::EndOfStream | ReceiveType::Dummy => (3, &[])
+    };
+
+    let mut out = ByteWriter::new();
+    out.put_u64(record.timestamp.as_micros() as u64);
+    out.put_u8(shape);
+    out.put_u32(values.len() as u32);
+    for value in values {
+        out.put_bytes(&value.to_bytes()?);
+    }
+    Ok(out.into_bytes())
+}
+
+
+#[derive(Debug, Clone)]
+pub struct Recording<T: Sharable> {
+    pub node_id: String,
+    pub started: SystemTime,
+    pub inputs: Vec<RecordedInput<T>>
+}
+impl<T: Recordable> Recording<T> {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        let file = File::open(path).map_err(|error| RecordingError::Io(error.to_string()))?;
+        Self::read_from(BufReader::new(file))
+    }
+    pub fn read_from(mut reader: impl Read) -> Result<Self, RecordingError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).map_err(|error| RecordingError::Io(error.to_string()))?;
+        Self::decode(&bytes).map_err(RecordingError::Format)
+    }
+    fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = ByteReader::new(bytes);
+        if reader.take(4)? != MAGIC {
+            return Err("not a recording, the magic bytes do not match".to_string());
+        }
+        let version = reader.u8()?;
+        if version != VERSION {
+            return Err(format!("recording version {} is not supported", version));
+        }
+        let type_code = reader.u8()?;
+        if type_code != T::TYPE_CODE {
+            return Err(format!("recording holds payload type {}, expected {}", type_code, T::TYPE_CODE));
+        }
+        let started = UNIX_EPOCH + Duration::from_micros(reader.u64()?);
+        let id_length = reader.u16()? as usize;
+        let node_id = String::from_utf8(reader.take(id_length)?.to_vec()).map_err(|_| "node id is not utf8".to_string())?;
+
+        let mut inputs = Vec::new();
+        while !reader.at_end() {
+            inputs.push(Self::read_record(&mut reader)?);
+        }
+
+        Ok(Self { node_id, started, inputs })
+    }
+    fn read_record(reader: &mut ByteReader) -> Result<RecordedInput<T>, String> {
+        let timestamp = Duration::from_micros(reader.u64()?);
+        let shape = reader.u8()?;
+        let count = reader.u32()? as usize;
+
+        let mut values = Vec::with_capacity(count.min(1024)); // the count is not trusted until the values are actually there
+        for _ in 0..count {
+            let value = T::from_bytes(reader.bytes()?).map_err(|message| format!("could not decode a value before byte {}: {}", reader.position(), message))?;
+            values.push(value);
+        }
+
+        let input = match (shape, count) {
+            (0, 1) => ReceiveType::Single(values.pop().unwrap()),
+            (1, _) => ReceiveType::Reassembled(values),
+            (2, _) => ReceiveType::Multichannel(values),
+            (3, 0) => ReceiveType::EndOfStream,
+            _ => return Err(format!("record shape {} with {} values is not valid", shape, count))
+        };
+        Ok(RecordedInput { timestamp, input })
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecordingSummary {
+    pub records: usize,
+    pub dropped: usize // inputs the tap discarded because the recorder thread fell behind
+}
+
+
+pub struct InputRecorder {
+    /*
+    Writes every input of one node to a file until stopped. Inputs the node received before stop was called are still written,
+    the recording is flushed and closed by the time stop returns
+     */
+    node_id: String,
+    stop: Arc<AtomicBool>,
+    writer: Option<JoinHandle<Result<RecordingSummary, RecordingError>>>
+}
+impl InputRecorder {
+    pub fn start<T: Recordable>(node_id: &str, tap: TapHandle<CapturedInput<T>>, path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        // the file is created and the header written here, so a bad path is reported to the caller and not lost in the thread
+        let file = File::create(path.as_ref()).map_err(|error| RecordingError::Io(error.to_string()))?;
+        let mut writer = BufWriter::new(file);
+        let start = Instant::now();
+        let started = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+
+        let mut header = MAGIC.to_vec();
+        header.push(VERSION);
+        header.push(T::TYPE_CODE);
+        header.extend_from_slice(&(started.as_micros() as u64).to_le_bytes());
+        header.extend_from_slice(&(node_id.len() as u16).to_le_bytes());
+        header.extend_from_slice(node_id.as_bytes());
+        writer.write_all(&header).map_err(|error| RecordingError::Io(error.to_string()))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let thread_id = node_id.to_string();
+
+        let handle = thread::spawn(move || {
+            let mut records = 0;
+            loop {
+                let stopping = thread_stop.load(Ordering::Acquire); // read before draining, so the last drain sees everything pushed before stop
+                for captured in tap.drain() {
+                    let record = RecordedInput { timestamp: captured.received.saturating_duration_since(start), input: captured.input };
+                    let encoded = encode_record(&record).map_err(RecordingError::Format)?;
+                    writer.write_all(&encoded).map_err(|error| RecordingError::Io(error.to_string()))?;
+                    records += 1;
+                }
+                if stopping { break }
+                sleep(RECORDER_POLL_TIME);
+            }
+            writer.flush().map_err(|error| RecordingError::Io(error.to_string()))?;
+
+            let summary = RecordingSummary { records, dropped: tap.dropped() };
+            if summary.dropped > 0 {
+                log_message(format!("Recorder: node {} recording lost {} inputs", thread_id, summary.dropped), Level::Warn);
+            }
+            Ok(summary)
+        });
+
+        log_message(format!("Recorder: recording the inputs of node {}", node_id), Level::Info);
+        Ok(Self { node_id: node_id.to_string(), stop, writer: Some(handle) })
+    }
+    pub fn stop(mut self) -> Result<RecordingSummary, RecordingError> {
+        self.stop.store(true, Ordering::Release);
+        match self.writer.take().unwrap().join() {
+            Ok(result) => result,
+            Err(_) => Err(RecordingError::Io(format!("recorder thread of node {} panicked", self.node_id)))
+        }
+    }
+}
+impl Drop for InputRecorder {
+    fn drop(&mut self) { // a recorder dropped without stop still closes its file
+        if let Some(writer) = self.writer.take() {
+            self.stop.store(true, Ordering::Release);
+            let _ = writer.join();
+        }
+    }
+}
+
+
+pub fn replay_step<I: Sharable, O: Sharable>(step: &mut impl PipelineStep<I, O>, recording: &Recording<I>) -> Vec<Result<ODFormat<O>, StepError>> {
+    /*
+    Feed a recording straight into a step, with no pipeline around it. Every input goes to the run_ method the step supports for
+    its shape (eg a single input to run_SISO, or run_SIMO/run_SIDO for steps which only have those), the end of stream flushes the step
+     */
+    let handlers = step.handlers();
+    let pick = |preferred: &[StepHandler]| preferred.iter().copied().find(|handler| handlers.contains(handler));
+    let mut outputs = Vec::with_capacity(recording.inputs.len());
+
+    for recorded in recording.inputs.iter() {
+        let output = match recorded.input.clone() {
+            ReceiveType::Single(value) => match pick(&[StepHandler::SISO, StepHandler::SIMO, StepHandler::SIDO]) {
+                Some(StepHandler::SIMO) => step.run_SIMO(value),
+                Some(StepHandler::SIDO) => step.run_SIDO(value),
+                _ => step.run_SISO(value)
+            },
+            ReceiveType::Reassembled(values) => match pick(&[StepHandler::REASO, StepHandler::REAMO]) {
+                Some(StepHandler::REAMO) => step.run_REAMO(values),
+                _ => step.run_REASO(values)
+            },
+            ReceiveType::Multichannel(values) => match pick(&[StepHandler::MISO, StepHandler::MIMO]) {
+                Some(StepHandler::MIMO) => step.run_MIMO(values),
+                _ => step.run_MISO(values)
+            },
+            ReceiveType::EndOfStream | ReceiveType::Dummy => match step.flush() {
+                Some(residual) => Ok(residual),
+                None => continue
+            }
+        };
+        outputs.push(output);
+    }
+    outputs
+}
+
+
+pub struct ReplaySource<T: Sharable> {
+    /*
+    A source which plays a recording back into a fresh pipeline. Single inputs are sent as they were, reassembled and multichannel
+    inputs are sent as a series, so the node under test needs a reassembler of the same size in front of it. Paced replays keep the
+    time between the inputs as it was recorded, otherwise they are sent as fast as the pipeline takes them
+     */
+    inputs: std::vec::IntoIter<RecordedInput<T>>,
+    paced: bool,
+    first_call: Option<(Instant, Duration)> // when the first input was sent and when it was recorded, the replay is paced from there
+}
+impl<T: Sharable> ReplaySource<T> {
+    pub fn new(recording: Recording<T>) -> Self {
+        Self { inputs: recording.inputs.into_iter(), paced: false, first_call: None }
+    }
+    pub fn with_pacing(mut self) -> Self {
+        self.paced = true;
+        self
+    }
+}
+impl<T: Sharable> PipelineStep<(), T> for ReplaySource<T> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+    fn run_DISO(&mut self) -> Result<ODFormat<T>, StepError> {
+        let recorded = match self.inputs.next() {
+            Some(recorded) => recorded,
+            None => return Err(StepError::end_of_stream())
+        };
+        if self.paced {
+            let (first_call, first_timestamp) = *self.first_call.get_or_insert_with(|| (Instant::now(), recorded.timestamp));
+            sleep(recorded.timestamp.saturating_sub(first_timestamp).saturating_sub(first_call.elapsed()));
+        }
+
+        match recorded.input {
+            ReceiveType::Single(value) => Ok(ODFormat::Standard(value)),
+            ReceiveType::Reassembled(values) | ReceiveType::Multichannel(values) => Ok(ODFormat::Series(values)),
+            ReceiveType::EndOfStream | ReceiveType::Dummy => Err(StepError::end_of_stream())
+        }
+    }
+}
+impl<T: Sharable> Source for ReplaySource<T> {}
@@ -0,0 +1,181 @@
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use serde_json::{Map, Value};
+use super::pipeline::PipelineParameters;
+use super::pipeline_graph::GraphRecorder;
+use super::pipeline_channels::{BackpressurePolicy, ChannelBackend};
+use super::pipeline_comms::{wrapped_link, MultichannelReceiver, MultichannelSender, NodeReceiver, NodeSender, Reassembler, SingleReceiver, SingleSender, WrappedReceiver, WrappedSender};
+use super::pipeline_step::{PipelineNode, PipelineStep};
+use super::pipeline_thread::PipelineThread;
+use super::pipeline_traits::{HasID, Sharable};
+use super::thread_state_space::NodeStateCommunicator;
+
+
+pub struct StepParameters {
+    // the constructor parameters of one node in a pipeline definition file
+    node_id: String,
+    values: Map<String, Value>
+}
+impl StepParameters {
+    pub fn new(node_id: &str, values: Map<String, Value>) -> Self {
+        Self { node_id: node_id.to_string(), values }
+    }
+    fn get_value(&self, key: &str) -> Result<&Value, String> {
+        match self.values.get(key) {
+            Some(value) => Ok(value),
+            None => Err(format!("node '{}' is missing parameter '{}'", self.node_id, key))
+        }
+    }
+    fn type_error(&self, key: &str, expected: &str) -> String {
+        format!("node '{}' parameter '{}' must be {}", self.node_id, key, expected)
+    }
+    pub fn contains(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+    pub fn get_f32(&self, key: &str) -> Result<f32, String> {
+        self.get_value(key)?.as_f64().map(|value| value as f32).ok_or(self.type_error(key, "a number"))
+    }
+    pub fn get_usize(&self, key: &str) -> Result<usize, String> {
+        self.get_value(key)?.as_u64().map(|value| value as usize).ok_or(self.type_error(key, "a positive integer"))
+    }
+    pub fn get_bool(&self, key: &str) -> Result<bool, String> {
+        self.get_value(key)?.as_bool().ok_or(self.type_error(key, "a boolean"))
+    }
+    pub fn get_string(&self, key: &str) -> Result<String, String> {
+        self.get_value(key)?.as_str().map(|value| value.to_string()).ok_or(self.type_error(key, "a string"))
+    }
+    pub fn get_f32_vec(&self, key: &str) -> Result<Vec<f32>, String> {
+        let values = self.get_value(key)?.as_array().ok_or(self.type_error(key, "an array of numbers"))?;
+        values.iter()
+            .map(|value| value.as_f64().map(|value| value as f32).ok_or(self.type_error(key, "an array of numbers")))
+            .collect()
+    }
+}
+
+
+pub struct NodeWiring {
+    // type erased channel ends for one node, downcast by the typed factory once the connection types were checked
+    pub id: String,
+    pub graph_index: usize,
+    pub inputs: Vec<(Box<dyn Any + Send>, bool)>, // receiver and whether it is a feedback input primed with a default at startup
+    pub outputs: Vec<Box<dyn Any + Send>>,
+    pub reassemble: Option<usize>,
+}
+
+
+pub trait PreparedStep {
+    // a step which was constructed from its parameters but has no thread yet, so a bad definition never leaves threads behind
+    fn spawn(self: Box<Self>, wiring: NodeWiring, parameters: PipelineParameters, state: NodeStateCommunicator, graph: &GraphRecorder) -> PipelineThread;
+}
+
+
+pub trait StepFactory: Send + Sync {
+    fn input_type(&self) -> TypeId;
+    fn output_type(&self) -> TypeId;
+    fn input_type_name(&self) -> &'static str;
+    fn output_type_name(&self) -> &'static str;
+    fn create_output_channel(&self, backend: ChannelBackend, capacity: usize, policy: BackpressurePolicy) -> Result<(Box<dyn Any + Send>, Box<dyn Any + Send>), String>;
+    fn prepare(&self, parameters: &StepParameters) -> Result<Box<dyn PreparedStep>, String>;
+}
+
+
+struct TypedStepFactory<I, O, S, F> {
+    constructor: F,
+    _marker: PhantomData<fn() -> (I, O, S)>
+}
+impl<I: Sharable, O: Sharable, S: PipelineStep<I, O>, F> StepFactory for TypedStepFactory<I, O, S, F>
+where F: Fn(&StepParameters) -> Result<S, String> + Send + Sync {
+    fn input_type(&self) -> TypeId { TypeId::of::<I>() }
+    fn output_type(&self) -> TypeId { TypeId::of::<O>() }
+    fn input_type_name(&self) -> &'static str { type_name::<I>() }
+    fn output_type_name(&self) -> &'static str { type_name::<O>() }
+
+    fn create_output_channel(&self, backend: ChannelBackend, capacity: usize, policy: BackpressurePolicy) -> Result<(Box<dyn Any + Send>, Box<dyn Any + Send>), String> {
+        let (sender, receiver) = wrapped_link::<O>(backend, capacity);
+        receiver.set_backpressure(policy)?;
+        Ok((Box::new(sender), Box::new(receiver)))
+    }
+    fn prepare(&self, parameters: &StepParameters) -> Result<Box<dyn PreparedStep>, String> {
+        let step = (self.constructor)(parameters)?;
+        Ok(Box::new(TypedPreparedStep { step, _marker: PhantomData::<fn() -> (I, O)> }))
+    }
+}
+
+
+struct TypedPreparedStep<I, O, S> {
+    step: S,
+    _marker: PhantomData<fn() -> (I, O)>
+}
+impl<I: Sharable, O: Sharable, S: PipelineStep<I, O>> TypedPreparedStep<I, O, S> {
+    fn wire_node(wiring: NodeWiring, parameters: &PipelineParameters) -> PipelineNode<I, O> {
+        let mut node: PipelineNode<I, O> = PipelineNode::new();
+        node.set_id(&wiring.id);
+        node.graph_index = wiring.graph_index;
+
+        let mut receivers: Vec<WrappedReceiver<I>> = wiring.inputs.into_iter()
+            .map(|(receiver, feedback)| {
+                let receiver = *receiver.downcast::<WrappedReceiver<I>>().expect("connection types are checked before wiring");
+                if feedback { receiver.set_startup_flag(parameters.execution_model) } else { receiver }
+            })
+            .collect();
+        let mut senders: Vec<WrappedSender<O>> = wiring.outputs.into_iter()
+            .map(|sender| *sender.downcast::<WrappedSender<O>>().expect("connection types are checked before wiring"))
+            .collect();
+
+        node.input = match (receivers.len(), wiring.reassemble) {
+            (0, _) => NodeReceiver::Dummy,
+            (1, Some(quantity)) => NodeReceiver::REA(Reassembler::new(receivers.pop().unwrap(), quantity, parameters.timeout, parameters.retries)),
+            (1, None) => NodeReceiver::SI(SingleReceiver::new(receivers.pop().unwrap(), parameters.timeout, parameters.retries)),
+            (_, _) => {
+                let mut joint = MultichannelReceiver::new(parameters.timeout, parameters.retries);
+                receivers.into_iter().for_each(|receiver| joint.add_receiver(receiver));
+                NodeReceiver::MI(joint)
+            }
+        };
+        node.output = match senders.len() {
+            0 => NodeSender::Dummy,
+            1 => NodeSender::SO(SingleSender::new(senders.pop().unwrap())),
+            _ => {
+                let mut split = MultichannelSender::new();
+                senders.into_iter().for_each(|sender| split.add_sender(sender));
+                NodeSender::MO(split)
+            }
+        };
+
+        node
+    }
+}
+impl<I: Sharable, O: Sharable, S: PipelineStep<I, O>> PreparedStep for TypedPreparedStep<I, O, S> {
+    fn spawn(self: Box<Self>, wiring: NodeWiring, parameters: PipelineParameters, state: NodeStateCommunicator, graph: &GraphRecorder) -> PipelineThread {
+        let node = Self::wire_node(wiring, &parameters);
+        graph.lock().unwrap().register_node(&node);
+        PipelineThread::new(self.step, node, parameters, state)
+    }
+}
+
+
+pub struct StepRegistry {
+    // maps the step names used in pipeline definition files to constructors for the concrete steps
+    factories: HashMap<String, Box<dyn StepFactory>>
+}
+impl StepRegistry {
+    pub fn new() -> Self {
+        Self { factories: HashMap::new() }
+    }
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        crate::dsp::registry::register_default_steps(&mut registry);
+        registry
+    }
+    pub fn register<I: Sharable, O: Sharable, S: PipelineStep<I, O>>(&mut self, name: &str, constructor: impl Fn(&StepParameters) -> Result<S, String> + Send + Sync + 'static) {
+        let factory = TypedStepFactory { constructor, _marker: PhantomData::<fn() -> (I, O, S)> };
+        self.factories.insert(name.to_string(), Box::new(factory));
+    }
+    pub fn get(&self, name: &str) -> Option<&dyn StepFactory> {
+        self.factories.get(name).map(|factory| factory.as_ref())
+    }
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+}
@@ -0,0 +1,712 @@
+// A control server, so a pipeline running headless can be started, paused, killed, inspected and tapped from another process.
+// The server takes over the ActivePipeline and listens on loopback tcp and/or a unix domain socket, every connection gets a thread of
+// its own. It has no authentication, which is why it refuses to listen on anything but a loopback address. RemoteClient speaks the
+// protocol from the other end, but any language with sockets and json can.
+//
+// The protocol is one json object per line in each direction, every request is answered by exactly one reply before the next is read:
+//     requests:  {"command": "status"}                                        -> {"running": bool, "killed": bool, "nodes": n}
+//                {"command": "start"} / "stop" / "kill"                       -> null, kill ends the pipeline for every connection
+//                {"command": "diagnostics"}                                   -> a list of RemoteDiagnostic, one per node
+//                {"command": "graph"}                                         -> the graph as exported by PipelineGraph::to_json
+//                {"command": "select", "multiplexer": id, "branch": n}        -> null
+//                {"command": "control", "node": id, "key": k, "value": v}     -> null once the step accepted the message. The value is a
+//                                                                                ControlValue, eg "Flag", {"Float": 440.0} or {"Text": "hann"}
+//                {"command": "subscribe", "node": id, "capacity": n, "every": n} -> {"subscription": n, "type_code": n}
+//                {"command": "read", "subscription": n, "max": n}             -> {"buffers": [...], "dropped": n}, never waits for buffers
+//                {"command": "unsubscribe", "subscription": n}                -> null
+//     replies:   {"ok": true, "result": ...} or {"ok": false, "error": "..."}
+// Only the output of nodes exposed with RemoteServer::with_tap can be subscribed to, since the server must know the payload type,
+// and a subscription holds at most 65536 buffers.
+// A buffer is {"format": "standard", "value": v}, {"format": "series" or "decompose", "values": [v, ...]} or
+// {"format": "repeat", "value": v, "repeats": n}, where each v is the HasEncoding of one value in hex. Subscriptions belong to
+// the connection and are dropped with it, and like any tap they lose their oldest buffers when they are not read fast enough.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::marker::PhantomData;
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::fs::FileTypeExt;
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::panic::{self, AssertUnwindSafe};
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, sleep, JoinHandle};
+use std::time::Duration;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use super::pipeline::{ActivePipeline, ThreadDiagnostic};
+use super::pipeline_comms::ODFormat;
+use super::pipeline_control::{ControlMessage, ControlValue};
+use super::pipeline_errors::{RemoteError, TapError};
+use super::pipeline_recording::Recordable;
+use super::pipeline_taps::{TapHandle, TapOptions};
+use super::pipeline_traits::HasEncoding;
+use super::logging::{log_message, Level};
+
+
+const ACCEPT_POLL_TIME: Duration = Duration::from_millis(20);
+const DEFAULT_CONTROL_TIMEOUT: Duration = Duration::from_secs(5); // a node blocked on its input handles controls once the receive times out
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(10); // longest the client waits for a reply, a control can take the control timeout
+const MAX_TAP_CAPACITY: usize = 1 << 16; // buffers one remote subscription may hold, the client picks the capacity
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoteAddress {
+    Tcp(SocketAddr), // loopback only. Port 0 binds a free port, which the server reports in its addresses
+    #[cfg(unix)]
+    Unix(PathBuf)
+}
+impl fmt::Display for RemoteAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RemoteAddress::Tcp(address) => write!(f, "tcp://{}", address),
+            #[cfg(unix)]
+            RemoteAddress::Unix(path) => write!(f, "unix://{}", path.display())
+        }
+    }
+}
+
+
+enum RemoteStream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream)
+}
+impl RemoteStream {
+    fn connect(address: &RemoteAddress) -> io::Result<Self> {
+        match address {
+            RemoteAddress::Tcp(address) => Ok(Self::Tcp(TcpStream::connect(address)?)),
+            #[cfg(unix)]
+            RemoteAddress::Unix(path) => Ok(Self::Unix(UnixStream::connect(path)?))
+        }
+    }
+    fn try_clone(&self) -> io::Result<Self> {
+        match self {
+            Self::Tcp(stream) => Ok(Self::Tcp(stream.try_clone()?)),
+            #[cfg(unix)]
+            Self::Unix(stream) => Ok(Self::Unix(stream.try_clone()?))
+        }
+    }
+    fn prepare(&self, read_timeout: Option<Duration>) -> io::Result<()> { // accepted streams may inherit the nonblocking listener
+        match self {
+            Self::Tcp(stream) => { stream.set_nonblocking(false)?; stream.set_read_timeout(read_timeout) },
+            #[cfg(unix)]
+            Self::Unix(stream) => { stream.set_nonblocking(false)?; stream.set_read_timeout(read_timeout) }
+        }
+    }
+    fn shutdown(&self) { // unblocks the connection thread reading from the stream
+        let _ = match self {
+            Self::Tcp(stream) => stream.shutdown(Shutdown::Both),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.shutdown(Shutdown::Both)
+        };
+    }
+}
+impl Read for RemoteStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.read(buf),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.read(buf)
+        }
+    }
+}
+impl Write for RemoteStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.write(buf),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.write(buf)
+        }
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Tcp(stream) => stream.flush(),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.flush()
+        }
+    }
+}
+
+
+enum RemoteListener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener, PathBuf) // the socket file is removed when the listener closes
+}
+impl RemoteListener {
+    fn bind(address: &RemoteAddress) -> Result<(Self, RemoteAddress), RemoteError> {
+        let io_error = |error: io::Error| RemoteError::Io(format!("{}: {}", address, error));
+
+        let (listener, bound) = match address {
+            RemoteAddress::Tcp(socket_address) => {
+                if !socket_address.ip().is_loopback() {
+                    return Err(RemoteError::NotLoopback(socket_address.to_string()));
+                }
+                let listener = TcpListener::bind(socket_address).map_err(io_error)?;
+                let bound = RemoteAddress::Tcp(listener.local_addr().map_err(io_error)?);
+                (Self::Tcp(listener), bound)
+            },
+            #[cfg(unix)]
+            RemoteAddress::Unix(path) => {
+                match std::fs::symlink_metadata(path) {
+                    Ok(metadata) if !metadata.file_type().is_socket() || UnixStream::connect(path).is_ok() => {
+                        return Err(RemoteError::AddressInUse(address.to_string()));
+                    },
+                    Ok(_) => std::fs::remove_file(path).map_err(io_error)?, // left behind by a server which did not shut down
+                    Err(error) if error.kind() == ErrorKind::NotFound => (),
+                    Err(error) => return Err(io_error(error))
+                }
+                (Self::Unix(UnixListener::bind(path).map_err(io_error)?, path.clone()), address.clone())
+            }
+        };
+
+        match &listener {
+            Self::Tcp(tcp) => tcp.set_nonblocking(true).map_err(io_error)?,
+            #[cfg(unix)]
+            Self::Unix(unix, _) => unix.set_nonblocking(true).map_err(io_error)?
+        }
+        Ok((listener, bound))
+    }
+    fn accept(&self) -> io::Result<RemoteStream> {
+        match self {
+            Self::Tcp(listener) => Ok(RemoteStream::Tcp(listener.accept()?.0)),
+            #[cfg(unix)]
+            Self::Unix(listener, _) => Ok(RemoteStream::Unix(listener.accept()?.0))
+        }
+    }
+    fn close(self) {
+        #[cfg(unix)]
+        if let Self::Unix(listener, path) = self {
+            drop(listener);
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum RemoteRequest {
+    Status,
+    Start,
+    Stop,
+    Kill,
+    Diagnostics,
+    Graph,
+    Select { multiplexer: String, branch: usize },
+    Control { node: String, key: String, value: ControlValue },
+    Subscribe { node: String, capacity: usize, every: usize },
+    Read { subscription: usize, max: usize },
+    Unsubscribe { subscription: usize }
+}
+impl RemoteRequest {
+    fn name(&self) -> &'static str {
+        match self {
+            RemoteRequest::Status => "status",
+            RemoteRequest::Start => "start",
+            RemoteRequest::Stop => "stop",
+            RemoteRequest::Kill => "kill",
+            RemoteRequest::Diagnostics => "diagnostics",
+            RemoteRequest::Graph => "graph",
+            RemoteRequest::Select { .. } => "select",
+            RemoteRequest::Control { .. } => "control",
+            RemoteRequest::Subscribe { .. } => "subscribe",
+            RemoteRequest::Read { .. } => "read",
+            RemoteRequest::Unsubscribe { .. } => "unsubscribe"
+        }
+    }
+}
+
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RemoteReply {
+    ok: bool,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    result: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>
+}
+
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteStatus {
+    pub running: bool,
+    pub killed: bool, // by a kill command, the server keeps answering status so clients can tell
+    pub nodes: usize
+}
+
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteDiagnostic {
+    // a ThreadDiagnostic flattened to what survives json, errors become their messages and durations microseconds
+    pub id: String,
+    pub state: String,
+    pub return_code: String,
+    pub execution_time: u64,
+    pub last_error: Option<String>,
+    pub end_of_stream: bool,
+    pub panic_message: Option<String>,
+    pub stalled: bool,
+    pub restarts: usize,
+    pub dropped: usize,
+    pub scheduling_error: Option<String>,
+    pub items_in_per_second: f64,
+    pub items_out_per_second: f64,
+    pub samples_in_per_second: f64,
+    pub samples_out_per_second: f64,
+    pub compute_time_mean: u64,
+    pub compute_time_p99: u64,
+    pub total_items_in: u64,
+    pub total_items_out: u64,
+    pub queue_depth: usize,
+    pub queue_capacity: usize
+}
+impl From<&ThreadDiagnostic> for RemoteDiagnostic {
+    fn from(diagnostic: &ThreadDiagnostic) -> Self {
+        Self {
+            id: diagnostic.id.clone(),
+            state: diagnostic.thread_state.to_string(),
+            return_code: format!("{:?}", diagnostic.return_code),
+            execution_time: diagnostic.execution_time,
+            last_error: diagnostic.last_error.as_ref().map(|error| error.to_string()),
+            end_of_stream: diagnostic.end_of_stream,
+            panic_message: diagnostic.panic_message.clone(),
+            stalled: diagnostic.stalled,
+            restarts: diagnostic.restarts,
+            dropped: diagnostic.dropped,
+            scheduling_error: diagnostic.scheduling_error.as_ref().map(|error| error.to_string()),
+            items_in_per_second: diagnostic.metrics.items_in_per_second,
+            items_out_per_second: diagnostic.metrics.items_out_per_second,
+            samples_in_per_second: diagnostic.metrics.samples_in_per_second,
+            samples_out_per_second: diagnostic.metrics.samples_out_per_second,
+            compute_time_mean: diagnostic.metrics.compute_time_mean.as_micros() as u64,
+            compute_time_p99: diagnostic.metrics.compute_time_p99.as_micros() as u64,
+            total_items_in: diagnostic.metrics.total_items_in,
+            total_items_out: diagnostic.metrics.total_items_out,
+            queue_depth: diagnostic.metrics.queue_depth,
+            queue_capacity: diagnostic.metrics.queue_capacity
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "format", rename_all = "snake_case")]
+enum EncodedBuffer {
+    Standard { value: String },
+    Series { values: Vec<String> },
+    Decompose { values: Vec<String> },
+    Repeat { value: String, repeats: usize }
+}
+impl EncodedBuffer {
+    fn encode<T: Recordable>(buffer: &ODFormat<T>) -> Result<Self, String> {
+        let encode_all = |values: &Vec<T>| values.iter().map(encode_value).collect::<Result<Vec<String>, String>>();
+        Ok(match buffer {
+            ODFormat::Standard(value) => Self::Standard { value: encode_value(value)? },
+            ODFormat::Series(values) => Self::Series { values: encode_all(values)? },
+            ODFormat::Decompose(values) => Self::Decompose { values: encode_all(values)? },
+            ODFormat::Repeat(value, repeats) => Self::Repeat { value: encode_value(value)?, repeats: *repeats }
+        })
+    }
+    fn decode<T: Recordable>(&self) -> Result<ODFormat<T>, RemoteError> {
+        let decode_all = |values: &Vec<String>| values.iter().map(|value| decode_value(value)).collect::<Result<Vec<T>, RemoteError>>();
+        Ok(match self {
+            Self::Standard { value } => ODFormat::Standard(decode_value(value)?),
+            Self::Series { values } => ODFormat::Series(decode_all(values)?),
+            Self::Decompose { values } => ODFormat::Decompose(decode_all(values)?),
+            Self::Repeat { value, repeats } => ODFormat::Repeat(decode_value(value)?, *repeats)
+        })
+    }
+}
+
+fn encode_value<T: Recordable>(value: &T) -> Result<String, String> {
+    Ok(value.to_bytes()?.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+fn decode_value<T: Recordable>(hex: &str) -> Result<T, RemoteError> {
+    let invalid = || RemoteError::Protocol(format!("'{}' is not a valid encoded value", hex));
+    if hex.len() % 2 != 0 { return Err(invalid()) }
+
+    let bytes = (0..hex.len()).step_by(2)
+        .map(|start| hex.get(start..start + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(invalid)?;
+    T::from_bytes(&bytes).map_err(|_| invalid())
+}
+
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SubscribeReply {
+    subscription: usize,
+    type_code: u8
+}
+
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ReadReply {
+    buffers: Vec<EncodedBuffer>,
+    dropped: usize
+}
+
+
+trait RemoteTapSource: Send {
+    // the subscription of a connection to one exposed tap, with the payload type erased
+    fn read(&self, max: usize) -> Result<Vec<EncodedBuffer>, String>;
+    fn dropped(&self) -> usize;
+}
+impl<T: Recordable> RemoteTapSource for TapHandle<ODFormat<T>> {
+    fn read(&self, max: usize) -> Result<Vec<EncodedBuffer>, String> {
+        (0..max).map_while(|_| self.try_recv()).map(|buffer| EncodedBuffer::encode(&buffer)).collect()
+    }
+    fn dropped(&self) -> usize {
+        TapHandle::dropped(self)
+    }
+}
+
+
+type TapFactory = Box<dyn Fn(&ActivePipeline, &str, TapOptions) -> Result<Box<dyn RemoteTapSource>, TapError> + Send + Sync>;
+
+struct ExposedTap {
+    type_code: u8,
+    subscribe: TapFactory
+}
+
+
+type SharedPipeline = Arc<Mutex<Option<ActivePipeline>>>; // None once a client killed the pipeline
+
+
+struct ServerContext {
+    pipeline: SharedPipeline,
+    taps: HashMap<String, ExposedTap>,
+    control_timeout: Duration
+}
+impl ServerContext {
+    fn with_pipeline<R>(&self, action: impl FnOnce(&mut ActivePipeline) -> Result<R, String>) -> Result<R, String> {
+        match self.pipeline.lock().unwrap().as_mut() {
+            Some(pipeline) => action(pipeline),
+            None => Err(String::from("the pipeline was killed"))
+        }
+    }
+
+    fn handle(&self, request: RemoteRequest, subscriptions: &mut HashMap<usize, Box<dyn RemoteTapSource>>, next_subscription: &mut usize) -> Result<Value, String> {
+        match request {
+            RemoteRequest::Status => {
+                let status = match self.pipeline.lock().unwrap().as_ref() {
+                    Some(pipeline) => RemoteStatus { running: pipeline.is_running(), killed: false, nodes: pipeline.get_thread_diagnostics().len() },
+                    None => RemoteStatus { running: false, killed: true, nodes: 0 }
+                };
+                to_value(&status)
+            },
+            RemoteRequest::Start => self.with_pipeline(|pipeline| Ok(pipeline.start())).map(|_| Value::Null),
+            RemoteRequest::Stop => self.with_pipeline(|pipeline| Ok(pipeline.stop())).map(|_| Value::Null),
+            RemoteRequest::Kill => {
+                let pipeline = self.pipeline.lock().unwrap().take(); // joined outside the lock, so other connections can see it is gone
+                match pipeline {
+                    Some(pipeline) => { pipeline.kill(); Ok(Value::Null) },
+                    None => Err(String::from("the pipeline was killed"))
+                }
+            },
+            RemoteRequest::Diagnostics => {
+                let diagnostics = self.with_pipeline(|pipeline| Ok(pipeline.get_thread_diagnostics().iter().map(RemoteDiagnostic::from).collect::<Vec<_>>()))?;
+                to_value(&diagnostics)
+            },
+            RemoteRequest::Graph => {
+                let graph = self.with_pipeline(|pipeline| Ok(pipeline.get_graph().to_json()))?;
+                serde_json::from_str(&graph).map_err(|error| error.to_string())
+            },
+            RemoteRequest::Select { multiplexer, branch } => {
+                self.with_pipeline(|pipeline| pipeline.select_branch(&multiplexer, branch).map_err(|error| error.to_string())).map(|_| Value::Null)
+            },
+            RemoteRequest::Control { node, key, value } => {
+                // the ticket is waited on outside the lock, the node can take as long as a work call to handle it
+                let ticket = self.with_pipeline(|pipeline| pipeline.send_control(&node, ControlMessage { key, value }).map_err(|error| error.to_string()))?;
+                ticket.wait(self.control_timeout).map_err(|error| error.to_string()).map(|_| Value::Null)
+            },
+            RemoteRequest::Subscribe { node, capacity, every } => {
+                let exposed = self.taps.get(&node).ok_or_else(|| format!("node '{}' is not exposed for remote taps", node))?;
+                if capacity > MAX_TAP_CAPACITY {
+                    return Err(format!("a subscription holds at most {} buffers, {} were asked for", MAX_TAP_CAPACITY, capacity));
+                }
+                let options = TapOptions::new(capacity).with_decimation(every);
+                // the lock is shared by every connection, a subscription failing to allocate must not poison it
+                let handle = self.with_pipeline(|pipeline| {
+                    panic::catch_unwind(AssertUnwindSafe(|| (exposed.subscribe)(pipeline, &node, options)))
+                        .map_err(|_| format!("subscribing to '{}' failed", node))?
+                        .map_err(|error| error.to_string())
+                })?;
+
+                *next_subscription += 1;
+                subscriptions.insert(*next_subscription, handle);
+                to_value(&SubscribeReply { subscription: *next_subscription, type_code: exposed.type_code })
+            },
+            RemoteRequest::Read { subscription, max } => {
+                let handle = subscriptions.get(&subscription).ok_or_else(|| format!("no subscription {}", subscription))?;
+                to_value(&ReadReply { buffers: handle.read(max)?, dropped: handle.dropped() })
+            },
+            RemoteRequest::Unsubscribe { subscription } => {
+                subscriptions.remove(&subscription).map(|_| Value::Null).ok_or_else(|| format!("no subscription {}", subscription))
+            }
+        }
+    }
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|error| error.to_string())
+}
+
+
+fn serve_connection(stream: RemoteStream, context: Arc<ServerContext>) {
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(error) => { log_message(format!("Remote connection could not be set up: {}", error), Level::Warn); return }
+    };
+    let mut reader = BufReader::new(stream);
+    let mut subscriptions: HashMap<usize, Box<dyn RemoteTapSource>> = HashMap::new();
+    let mut next_subscription = 0;
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => break, // closed by the client, or by the server shutting down
+            Ok(_) => {}
+        }
+        if line.iter().all(|byte| byte.is_ascii_whitespace()) { continue }
+
+        let reply = match serde_json::from_slice::<RemoteRequest>(&line) {
+            Ok(request) => {
+                log_message(format!("Remote command '{}'", request.name()), Level::Debug);
+                match context.handle(request, &mut subscriptions, &mut next_subscription) {
+                    Ok(result) => RemoteReply { ok: true, result, error: None },
+                    Err(message) => RemoteReply { ok: false, result: Value::Null, error: Some(message) }
+                }
+            },
+            Err(error) => RemoteReply { ok: false, result: Value::Null, error: Some(format!("invalid request: {}", error)) }
+        };
+
+        let mut encoded = serde_json::to_vec(&reply).unwrap();
+        encoded.push(b'\n');
+        if writer.write_all(&encoded).and_then(|_| writer.flush()).is_err() { break }
+    }
+}
+
+
+type Connections = Arc<Mutex<Vec<(RemoteStream, JoinHandle<()>)>>>;
+
+fn accept_connections(listener: RemoteListener, context: Arc<ServerContext>, connections: Connections, running: Arc<AtomicBool>) {
+    while running.load(Ordering::Acquire) {
+        match listener.accept() {
+            Ok(stream) => {
+                let shutdown_handle = match stream.prepare(None).and_then(|_| stream.try_clone()) {
+                    Ok(shutdown_handle) => shutdown_handle,
+                    Err(error) => { log_message(format!("Remote connection refused: {}", error), Level::Warn); continue }
+                };
+                let context = context.clone();
+                let connection = thread::spawn(move || serve_connection(stream, context));
+
+                let mut connections = connections.lock().unwrap();
+                connections.retain(|(_, connection)| !connection.is_finished());
+                connections.push((shutdown_handle, connection));
+            },
+            Err(error) if error.kind() == ErrorKind::WouldBlock => sleep(ACCEPT_POLL_TIME),
+            Err(error) => {
+                log_message(format!("Remote listener failed: {}", error), Level::Error);
+                break;
+            }
+        }
+    }
+    listener.close();
+}
+
+
+pub struct RemoteServer {
+    // the listeners are bound before the pipeline is handed over, so a busy address never costs a running pipeline
+    listeners: Vec<(RemoteListener, RemoteAddress)>,
+    taps: HashMap<String, ExposedTap>,
+    control_timeout: Duration
+}
+impl RemoteServer {
+    pub fn bind(addresses: &[RemoteAddress]) -> Result<Self, RemoteError> {
+        let mut listeners = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            listeners.push(RemoteListener::bind(address)?);
+        }
+        Ok(Self { listeners, taps: HashMap::new(), control_timeout: DEFAULT_CONTROL_TIMEOUT })
+    }
+    pub fn with_tap<T: Recordable>(mut self, node_id: &str) -> Self {
+        // lets clients subscribe to the output of the node, T must be the type it outputs
+        let subscribe: TapFactory = Box::new(|pipeline, node_id, options| {
+            pipeline.tap::<T>(node_id, options).map(|handle| Box::new(handle) as Box<dyn RemoteTapSource>)
+        });
+        self.taps.insert(node_id.to_string(), ExposedTap { type_code: T::TYPE_CODE, subscribe });
+        self
+    }
+    pub fn with_control_timeout(mut self, control_timeout: Duration) -> Self {
+        // how long a control command waits for the node to handle the message before the client is told it timed out
+        self.control_timeout = control_timeout;
+        self
+    }
+    pub fn addresses(&self) -> Vec<RemoteAddress> {
+        self.listeners.iter().map(|(_, address)| address.clone()).collect()
+    }
+
+    pub fn serve(self, pipeline: ActivePipeline) -> RemoteServerHandle {
+        let addresses = self.addresses();
+        let context = Arc::new(ServerContext { pipeline: Arc::new(Mutex::new(Some(pipeline))), taps: self.taps, control_timeout: self.control_timeout });
+        let connections: Connections = Arc::new(Mutex::new(Vec::new()));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let listeners = self.listeners.into_iter().map(|(listener, address)| {
+            log_message(format!("Remote control listening on {}", address), Level::Info);
+            let (context, connections, running) = (context.clone(), connections.clone(), running.clone());
+            thread::spawn(move || accept_connections(listener, context, connections, running))
+        }).collect();
+
+        RemoteServerHandle { pipeline: context.pipeline.clone(), addresses, running, listeners, connections }
+    }
+}
+
+
+pub struct RemoteServerHandle {
+    pipeline: SharedPipeline,
+    addresses: Vec<RemoteAddress>,
+    running: Arc<AtomicBool>,
+    listeners: Vec<JoinHandle<()>>,
+    connections: Connections
+}
+impl RemoteServerHandle {
+    pub fn addresses(&self) -> &[RemoteAddress] {
+        &self.addresses
+    }
+    pub fn with_pipeline<R>(&self, action: impl FnOnce(&mut ActivePipeline) -> R) -> Option<R> {
+        // local access to the pipeline while it is served, None once a client killed it
+        self.pipeline.lock().unwrap().as_mut().map(action)
+    }
+    pub fn is_killed(&self) -> bool {
+        self.pipeline.lock().unwrap().is_none()
+    }
+    pub fn shutdown(self) -> Option<ActivePipeline> {
+        // stops listening and closes every connection, then hands the pipeline back in whatever state the clients left it
+        self.running.store(false, Ordering::Release);
+        for listener in self.listeners {
+            let _ = listener.join();
+        }
+        for (stream, connection) in self.connections.lock().unwrap().drain(..) {
+            stream.shutdown();
+            let _ = connection.join();
+        }
+        self.pipeline.lock().unwrap().take()
+    }
+}
+
+
+pub struct RemoteTap<T: Recordable> {
+    // a subscription to an exposed tap, only valid on the client which made it
+    subscription: usize,
+    node_id: String,
+    payload: PhantomData<T>
+}
+impl<T: Recordable> RemoteTap<T> {
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct RemoteTapBatch<T: Recordable> {
+    pub buffers: Vec<ODFormat<T>>,
+    pub dropped: usize // buffers the subscription lost so far because they were not read in time
+}
+
+
+pub struct RemoteClient {
+    reader: BufReader<RemoteStream>,
+    writer: RemoteStream
+}
+impl RemoteClient {
+    pub fn connect(address: &RemoteAddress) -> Result<Self, RemoteError> {
+        let io_error = |error: io::Error| RemoteError::Io(format!("{}: {}", address, error));
+        let stream = RemoteStream::connect(address).map_err(io_error)?;
+        stream.prepare(Some(CLIENT_TIMEOUT)).map_err(io_error)?;
+        let writer = stream.try_clone().map_err(io_error)?;
+
+        Ok(Self { reader: BufReader::new(stream), writer })
+    }
+
+    pub fn request(&mut self, request: &RemoteRequest) -> Result<Value, RemoteError> {
+        let mut encoded = serde_json::to_vec(request).map_err(|error| RemoteError::Protocol(error.to_string()))?;
+        encoded.push(b'\n');
+        self.writer.write_all(&encoded).and_then(|_| self.writer.flush()).map_err(|error| RemoteError::Io(error.to_string()))?;
+
+        let mut line = Vec::new();
+        match self.reader.read_until(b'\n', &mut line) {
+            Ok(0) => return Err(RemoteError::Io(String::from("the server closed the connection"))),
+            Ok(_) => {},
+            Err(error) => return Err(RemoteError::Io(error.to_string()))
+        }
+
+        let reply: RemoteReply = serde_json::from_slice(&line).map_err(|error| RemoteError::Protocol(error.to_string()))?;
+        match reply.ok {
+            true => Ok(reply.result),
+            false => Err(RemoteError::Rejected { command: request.name().to_string(), message: reply.error.unwrap_or_default() })
+        }
+    }
+    fn request_as<R: DeserializeOwned>(&mut self, request: &RemoteRequest) -> Result<R, RemoteError> {
+        serde_json::from_value(self.request(request)?).map_err(|error| RemoteError::Protocol(error.to_string()))
+    }
+
+    pub fn status(&mut self) -> Result<RemoteStatus, RemoteError> {
+        self.request_as(&RemoteRequest::Status)
+    }
+    pub fn start(&mut self) -> Result<(), RemoteError> {
+        self.request(&RemoteRequest::Start).map(|_| ())
+    }
+    pub fn stop(&mut self) -> Result<(), RemoteError> {
+        self.request(&RemoteRequest::Stop).map(|_| ())
+    }
+    pub fn kill(&mut self) -> Result<(), RemoteError> {
+        self.request(&RemoteRequest::Kill).map(|_| ())
+    }
+    pub fn diagnostics(&mut self) -> Result<Vec<RemoteDiagnostic>, RemoteError> {
+        self.request_as(&RemoteRequest::Diagnostics)
+    }
+    pub fn graph(&mut self) -> Result<Value, RemoteError> {
+        self.request(&RemoteRequest::Graph)
+    }
+    pub fn select_branch(&mut self, multiplexer_id: &str, branch: usize) -> Result<(), RemoteError> {
+        self.request(&RemoteRequest::Select { multiplexer: multiplexer_id.to_string(), branch }).map(|_| ())
+    }
+    pub fn send_control(&mut self, node_id: &str, message: ControlMessage) -> Result<(), RemoteError> {
+        // returns once the step accepted the message, a rejection comes back with the step's reason
+        self.request(&RemoteRequest::Control { node: node_id.to_string(), key: message.key, value: message.value }).map(|_| ())
+    }
+
+    pub fn subscribe<T: Recordable>(&mut self, node_id: &str, options: TapOptions) -> Result<RemoteTap<T>, RemoteError> {
+        let request = RemoteRequest::Subscribe { node: node_id.to_string(), capacity: options.capacity, every: options.every };
+        let reply: SubscribeReply = self.request_as(&request)?;
+        let tap = RemoteTap { subscription: reply.subscription, node_id: node_id.to_string(), payload: PhantomData };
+
+        if reply.type_code != T::TYPE_CODE {
+            self.unsubscribe(tap)?;
+            return Err(RemoteError::Protocol(format!("node '{}' carries payload type {}, not {}", node_id, reply.type_code, T::TYPE_CODE)));
+        }
+        Ok(tap)
+    }
+    pub fn read_tap<T: Recordable>(&mut self, tap: &RemoteTap<T>, max: usize) -> Result<RemoteTapBatch<T>, RemoteError> {
+        // whatever the subscription holds right now, up to max buffers. It does not wait for more to arrive
+        let reply: ReadReply = self.request_as(&RemoteRequest::Read { subscription: tap.subscription, max })?;
+        let buffers = reply.buffers.iter().map(|buffer| buffer.decode()).collect::<Result<Vec<_>, _>>()?;
+        Ok(RemoteTapBatch { buffers, dropped: reply.dropped })
+    }
+    pub fn unsubscribe<T: Recordable>(&mut self, tap: RemoteTap<T>) -> Result<(), RemoteError> {
+        self.request(&RemoteRequest::Unsubscribe { subscription: tap.subscription }).map(|_| ())
+    }
+}
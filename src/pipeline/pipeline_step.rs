@@ -1,15 +1,24 @@
 use std::fmt::Debug;
 use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize};
-use std::sync::mpsc;
 use std::sync::mpsc::{RecvTimeoutError};
 use std::sync::Arc;
+use std::time::Instant;
 use futures::future::Lazy;
 use log::Level;
-use crossbeam_queue::ArrayQueue;
+use tracing::field::Empty;
+use tracing::{trace_span, Span};
 use crate::pipeline::pipeline::{ConstructingPipeline, ConstructionQueue, PipelineParameters};
+use super::pipeline_graph::{GraphRecorder, GraphEdgeKind};
 use super::pipeline_thread::PipelineThread;
 use super::pipeline_traits::{Sharable, Unit, HasID, Source, Sink};
-use super::pipeline_comms::{WrappedReceiver, NodeReceiver, NodeSender, MultichannelReceiver, MultichannelSender, ReceiveType, SingleReceiver, ODFormat, SingleSender, Reassembler, Multiplexer, Demultiplexer};
+use super::pipeline_errors::StepError;
+use super::pipeline_metrics::{CallRecord, NodeMetrics};
+use super::stream_tags::{StreamTag, rescale_tags};
+use super::pipeline_control::ControlMessage;
+use super::pipeline_supervisor::SupervisionPolicy;
+use super::pipeline_checkpoint::Checkpointable;
+use super::pipeline_taps::{CapturedInput, TapHandle, TapOptions, TapPoint};
+use super::pipeline_comms::{wrapped_link, WrappedReceiver, NodeReceiver, NodeSender, MultichannelReceiver, MultichannelSender, ReceiveType, SingleReceiver, ODFormat, SingleSender, Reassembler, Multiplexer, Demultiplexer};
 use super::api::*;
 
 
@@ -18,9 +27,34 @@ pub enum PipelineStepResult {
     Success,
     SendError,
     RecvTimeoutError(RecvTimeoutError),
-    ComputeError(String),
+    ComputeError(StepError),
+    EndOfStream, // the node flushed its step and passed the end of stream on, it will not be called again
     Carryover
 }
+impl PipelineStepResult {
+    pub fn outcome(&self) -> &'static str { // a short name for the result, for the tracing spans
+        match self {
+            PipelineStepResult::Success => "success",
+            PipelineStepResult::SendError => "send_error",
+            PipelineStepResult::RecvTimeoutError(_) => "receive_timeout",
+            PipelineStepResult::ComputeError(_) => "compute_error",
+            PipelineStepResult::EndOfStream => "end_of_stream",
+            PipelineStepResult::Carryover => "carryover"
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, strum::Display, serde::Serialize)]
+pub enum StepHandler { // one variant per run_ method on PipelineStep, used to check that a step supports the shape of the node it is wired into
+    SISO,
+    REASO,
+    MISO,
+    SIMO,
+    REAMO,
+    MIMO,
+    DISO,
+    SIDO
+}
 
 // how can I make multiple input and output types more convenient?
 /*
@@ -28,38 +62,118 @@ pub enum PipelineStepResult {
 2. user can return whatever data scheme they want from each separate handler for the node to do with what it pleases
 3. at the beginning of runtime, depending on the receiver type assigned to the node, a different handler (node method) is chosen to receive, so no additional match is needed
  */
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a pipeline step from `{I}` to `{O}`",
+    label = "does not fit the edges around it"
+)]
 pub trait PipelineStep<I: Sharable, O: Sharable> : Send + 'static {
+    // declare which of the run_ methods below this step implements. Checked against the node topology in finish_pipeline
+    fn handlers(&self) -> Vec<StepHandler>;
     // There is a single input, and a single output
-    fn run_SISO(&mut self, input: I) -> Result<ODFormat<O>, String> { panic!("Single in Single Out Not Implemented") }
+    fn run_SISO(&mut self, input: I) -> Result<ODFormat<O>, StepError> { panic!("Single in Single Out Not Implemented") }
     // You are receiving a vector of values reassembled from a series branch, and are outputting to a single output
-    fn run_REASO(&mut self, input: Vec<I>) -> Result<ODFormat<O>, String> { panic!("Series In Single Out Not Implemented") }
+    fn run_REASO(&mut self, input: Vec<I>) -> Result<ODFormat<O>, StepError> { panic!("Series In Single Out Not Implemented") }
     // you are receiving a vector of values, each one representing the output of a distinct branch. Outputting to a single output
-    fn run_MISO(&mut self, input: Vec<I>) -> Result<ODFormat<O>, String> { panic!("Multiple In Single Out Not Implemented") }
+    fn run_MISO(&mut self, input: Vec<I>) -> Result<ODFormat<O>, StepError> { panic!("Multiple In Single Out Not Implemented") }
     // you are receiving a single value and outputting to multiple distinct pipeline branches
-    fn run_SIMO(&mut self, input: I) -> Result<ODFormat<O>, String> { panic!("Single In Multiple Out Not Implemented") }
+    fn run_SIMO(&mut self, input: I) -> Result<ODFormat<O>, StepError> { panic!("Single In Multiple Out Not Implemented") }
     // you are receiving a vector of values, representing a series receive, and outputing to multiple outputs
-    fn run_REAMO(&mut self, input: Vec<I>) -> Result<ODFormat<O>, String> { panic!("Series In Multiple Out Not Implemented") }
+    fn run_REAMO(&mut self, input: Vec<I>) -> Result<ODFormat<O>, StepError> { panic!("Series In Multiple Out Not Implemented") }
     // receiving a vector of outputs from distinct pipeline branches and outputting to multiple distinct pipeline branches
-    fn run_MIMO(&mut self, input: Vec<I>) -> Result<ODFormat<O>, String> { panic!("Multiple In Multiple Out Not Implemented") }
+    fn run_MIMO(&mut self, input: Vec<I>) -> Result<ODFormat<O>, StepError> { panic!("Multiple In Multiple Out Not Implemented") }
     // intended for source nodes. When the source receiver is 'Dummy'. This is a PRODUCER startpoint. Generates its own input
-    fn run_DISO(&mut self) -> Result<ODFormat<O>, String> {
+    fn run_DISO(&mut self) -> Result<ODFormat<O>, StepError> {
         panic!("Dummy in Not Implemented")
     }
     // intended for sink nodes. When the sink sender is 'Dummy'. This is a CONSUMER endpoint. Must put the output wherever it needs to go on its own
-    fn run_SIDO(&mut self, input: I) -> Result<ODFormat<O>, String> { panic!("Dummy out Not Implemented") }
+    fn run_SIDO(&mut self, input: I) -> Result<ODFormat<O>, StepError> { panic!("Dummy out Not Implemented") }
     // optional method to be run whenever a pause signal is received
     fn pause_behavior(&mut self) { () }
     // optional method to be run whenever a start signal is received
     fn start_behavior(&mut self) { () }
     // optional method to be run whenevr a kill signal is received
     fn kill_behavior(&mut self) { () }
+    // optional method to be run when the step returns a StepErrorKind::NeedsReset error. Should restore the step to its freshly constructed state
+    fn reset_behavior(&mut self) { () }
+    // optional method to be run once the stream has ended, before the end of stream is passed downstream. Returns whatever the step still holds
+    // (eg an overlap tail), which is sent on as the final output. Sinks should block here until they have consumed everything they were given
+    fn flush(&mut self) -> Option<ODFormat<O>> { None }
+    // the sample rate change of the step as (interpolation, decimation), used to move the offsets of the tags that pass through it
+    fn tag_rate(&self) -> (usize, usize) { (1, 1) }
+    // optional method to be run before every call with the tags of its input, already rescaled to the output. The step may read, add, move or
+    // remove tags here, whatever is left is sent on with the output. The tags are dropped if the call returns an error
+    fn tag_behavior(&mut self, tags: &mut Vec<StreamTag>) { () }
+    // optional method to be run between work calls for every control message sent to the node. Should only change the configuration
+    // if the whole message is valid, the error is passed back to the sender as the reason the change was rejected
+    fn handle_control(&mut self, message: &ControlMessage) -> Result<(), String> { Err(format!("step has no control '{}'", message.key)) }
+    // what the supervisor does with the node when the step panics or stalls
+    fn supervision_policy(&self) -> SupervisionPolicy { SupervisionPolicy::default() }
+    // optional method to replace the step with a fresh one when the supervisor restarts it. Returns false if it cannot, reset_behavior is run instead
+    fn restart_step(&mut self) -> bool { false }
+    // the state saved by a pipeline checkpoint, steps which keep state between calls return Some(self). None saves the links of the node only
+    fn checkpointable(&mut self) -> Option<&mut dyn Checkpointable> { None }
+}
+
+
+pub trait StepWrapper<I: Sharable, O: Sharable> : Send + 'static {
+    // a step which wraps another one and passes everything on to it, eg to supervise, pace or swap it. wrapped_step! implements
+    // PipelineStep for the wrapper on top of these, so a wrapper only spells out the hooks it changes
+    fn inner(&mut self) -> &mut dyn PipelineStep<I, O>;
+    fn inner_ref(&self) -> &dyn PipelineStep<I, O>;
+    // run around every run_ call of the inner step, which is made by call
+    fn around_call(&mut self, call: impl FnOnce(&mut dyn PipelineStep<I, O>) -> Result<ODFormat<O>, StepError>) -> Result<ODFormat<O>, StepError> {
+        call(self.inner())
+    }
+    // run before the pause or start signal is passed on to the inner step
+    fn before_pause(&mut self) { () }
+    fn before_start(&mut self) { () }
+    fn wrapped_policy(&self) -> SupervisionPolicy { self.inner_ref().supervision_policy() }
+    fn wrapped_restart(&mut self) -> bool { self.inner().restart_step() }
 }
 
+macro_rules! wrapped_step {
+    // implements PipelineStep through StepWrapper, eg wrapped_step!([I: Sharable, O: Sharable, S: PipelineStep<I, O>] Supervised<S>, I, O).
+    // A blanket impl over every StepWrapper would overlap with the generic impls of the plain steps
+    ([$($generics:tt)*] $wrapper:ty, $input:ty, $output:ty) => {
+        impl<$($generics)*> $crate::pipeline::api::PipelineStep<$input, $output> for $wrapper {
+            fn handlers(&self) -> Vec<$crate::pipeline::api::StepHandler> { self.inner_ref().handlers() }
+            fn run_SISO(&mut self, input: $input) -> Result<$crate::pipeline::api::ODFormat<$output>, $crate::pipeline::api::StepError> { self.around_call(|step| step.run_SISO(input)) }
+            fn run_REASO(&mut self, input: Vec<$input>) -> Result<$crate::pipeline::api::ODFormat<$output>, $crate::pipeline::api::StepError> { self.around_call(|step| step.run_REASO(input)) }
+            fn run_MISO(&mut self, input: Vec<$input>) -> Result<$crate::pipeline::api::ODFormat<$output>, $crate::pipeline::api::StepError> { self.around_call(|step| step.run_MISO(input)) }
+            fn run_SIMO(&mut self, input: $input) -> Result<$crate::pipeline::api::ODFormat<$output>, $crate::pipeline::api::StepError> { self.around_call(|step| step.run_SIMO(input)) }
+            fn run_REAMO(&mut self, input: Vec<$input>) -> Result<$crate::pipeline::api::ODFormat<$output>, $crate::pipeline::api::StepError> { self.around_call(|step| step.run_REAMO(input)) }
+            fn run_MIMO(&mut self, input: Vec<$input>) -> Result<$crate::pipeline::api::ODFormat<$output>, $crate::pipeline::api::StepError> { self.around_call(|step| step.run_MIMO(input)) }
+            fn run_DISO(&mut self) -> Result<$crate::pipeline::api::ODFormat<$output>, $crate::pipeline::api::StepError> { self.around_call(|step| step.run_DISO()) }
+            fn run_SIDO(&mut self, input: $input) -> Result<$crate::pipeline::api::ODFormat<$output>, $crate::pipeline::api::StepError> { self.around_call(|step| step.run_SIDO(input)) }
+            fn pause_behavior(&mut self) {
+                self.before_pause();
+                self.inner().pause_behavior()
+            }
+            fn start_behavior(&mut self) {
+                self.before_start();
+                self.inner().start_behavior()
+            }
+            fn kill_behavior(&mut self) { self.inner().kill_behavior() }
+            fn reset_behavior(&mut self) { self.inner().reset_behavior() }
+            fn flush(&mut self) -> Option<$crate::pipeline::api::ODFormat<$output>> { self.inner().flush() }
+            fn tag_rate(&self) -> (usize, usize) { self.inner_ref().tag_rate() }
+            fn tag_behavior(&mut self, tags: &mut Vec<$crate::pipeline::api::StreamTag>) { self.inner().tag_behavior(tags) }
+            fn handle_control(&mut self, message: &$crate::pipeline::api::ControlMessage) -> Result<(), String> { self.inner().handle_control(message) }
+            fn supervision_policy(&self) -> $crate::pipeline::api::SupervisionPolicy { self.wrapped_policy() }
+            fn restart_step(&mut self) -> bool { self.wrapped_restart() }
+            fn checkpointable(&mut self) -> Option<&mut dyn $crate::pipeline::api::Checkpointable> { self.inner().checkpointable() }
+        }
+    };
+}
+pub(crate) use wrapped_step;
+
 
 #[derive(Debug, Copy, Clone)]
 struct DummyStep {}
 impl<T: Sharable> PipelineStep<T, T> for DummyStep {
-    fn run_SISO(&mut self, input: T) -> Result<ODFormat<T>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+    fn run_SISO(&mut self, input: T) -> Result<ODFormat<T>, StepError> {
         Ok(ODFormat::Standard(input))
     }
 }
@@ -69,7 +183,11 @@ pub struct PipelineNode<I: Sharable, O: Sharable> {
     pub input: NodeReceiver<I>,
     pub output: NodeSender<O>,
     pub id: String,
-    tap: Option<Arc<ArrayQueue<ODFormat<O>>>>
+    pub graph_index: usize,
+    tap: Arc<TapPoint<ODFormat<O>>>,
+    input_tap: Arc<TapPoint<CapturedInput<I>>>,
+    pub thread_options: Option<ThreadOptions>, // set through the builder, otherwise the pipeline parameters decide
+    ended: bool
 }
 
 impl<I: Sharable, O: Sharable> HasID for PipelineNode<I, O> {
@@ -89,30 +207,133 @@ impl<I: Sharable, O: Sharable> PipelineNode<I, O> {
             input: NodeReceiver::Dummy,
             output: NodeSender::Dummy,
             id: "".to_string(),
-            tap: None
+            graph_index: 0,
+            tap: Arc::new(TapPoint::new()),
+            input_tap: Arc::new(TapPoint::new()),
+            thread_options: None,
+            ended: false
+        }
+    }
+    pub fn new_in_graph(graph: &GraphRecorder) -> PipelineNode<I, O> {
+        // every node gets a slot in the pipeline graph as soon as it exists, so edges can be recorded before its step is attached
+        let mut node = Self::new();
+        node.graph_index = graph.lock().unwrap().add_node();
+        node
+    }
+
+    pub fn is_ready(&self) -> bool { // inputs hold a value and the outputs have room, so a call should not block
+        !self.ended && self.input.has_input() && self.output.has_room()
+    }
+    pub fn is_source(&self) -> bool {
+        matches!(self.input, NodeReceiver::Dummy)
+    }
+    pub fn is_sink(&self) -> bool {
+        matches!(self.output, NodeSender::Dummy) && !self.is_source()
+    }
+    pub fn has_ended(&self) -> bool {
+        self.ended
+    }
+    pub fn tap_point(&self) -> Arc<TapPoint<ODFormat<O>>> {
+        self.tap.clone()
+    }
+    pub fn input_tap_point(&self) -> Arc<TapPoint<CapturedInput<I>>> {
+        self.input_tap.clone()
+    }
+    pub fn required_handler(&self) -> Option<StepHandler> {
+        // which run_ method route_computation will dispatch to for this node's receiver/sender shape. None if the shape cannot be dispatched at all
+        match (&self.input, &self.output) {
+            (NodeReceiver::SI(_) | NodeReceiver::DMI(_), NodeSender::SO(_) | NodeSender::MUO(_)) => Some(StepHandler::SISO),
+            (NodeReceiver::SI(_) | NodeReceiver::DMI(_), NodeSender::MO(_)) => Some(StepHandler::SIMO),
+            (NodeReceiver::MI(_), NodeSender::SO(_) | NodeSender::MUO(_)) => Some(StepHandler::MISO),
+            (NodeReceiver::MI(_), NodeSender::MO(_)) => Some(StepHandler::MIMO),
+            (NodeReceiver::REA(_), NodeSender::SO(_) | NodeSender::MUO(_)) => Some(StepHandler::REASO),
+            (NodeReceiver::REA(_), NodeSender::MO(_)) => Some(StepHandler::REAMO),
+            (NodeReceiver::Dummy, NodeSender::SO(_)) => Some(StepHandler::DISO),
+            (NodeReceiver::SI(_) | NodeReceiver::DMI(_), NodeSender::Dummy) => Some(StepHandler::SIDO),
+            (_, _) => None
         }
     }
 
-    pub fn call(&mut self, step: &mut impl PipelineStep<I, O>) -> PipelineStepResult {
-        let received_result = self.input.receive();
+    pub fn call(&mut self, step: &mut impl PipelineStep<I, O>, metrics: &NodeMetrics) -> PipelineStepResult {
+        // one span per call with a child span for each phase, the fields are filled in as the call goes. Free while nothing collects trace level spans
+        let span = trace_span!("node_call", node = %self.id, receive = Empty, items = Empty, samples = Empty, outcome = Empty).entered();
+        let result = self.traced_call(step, metrics, &span);
+        span.record("outcome", result.outcome()); // before the span is exited, which is when the exporters see it
+        result
+    }
+
+    fn traced_call(&mut self, step: &mut impl PipelineStep<I, O>, metrics: &NodeMetrics, span: &Span) -> PipelineStepResult {
+        let receive_start = Instant::now();
+        let received_result = trace_span!("receive").in_scope(|| self.input.receive());
+        let receive_time = receive_start.elapsed();
+        metrics.record_queue_depth(self.input.queue_depth());
+
+        if let Ok((received, _)) = &received_result {
+            self.capture_input(received);
+        }
+
         match received_result {
             Err(err) => {
+                metrics.record_receive_failure(receive_time);
                 let result = PipelineStepResult::RecvTimeoutError(err);
                 result
             }, // must have a way to handle if it is a dummy
-            Ok(val) => {
-                let result = self.route_computation(val, step);
+            Ok((ReceiveType::EndOfStream, _)) => self.end_stream(step),
+            Ok((val, mut tags)) => {
+                let (items_in, samples_in) = val.counts();
+                span.record("receive", val.to_string());
+                span.record("items", items_in);
+                span.record("samples", samples_in);
+                rescale_tags(&mut tags, step.tag_rate());
+
+                let compute_start = Instant::now();
+                let output_data = trace_span!("compute").in_scope(|| {
+                    step.tag_behavior(&mut tags); // before the computation so the step can act on the tags of the input it is given
+                    self.route_computation(val, step)
+                });
+                let compute_time = compute_start.elapsed();
+                let (items_out, samples_out) = match &output_data {
+                    Ok(extracted_data) => extracted_data.counts(),
+                    Err(_) => (0, 0)
+                };
+
+                if matches!(&output_data, Err(error) if error.kind() == StepErrorKind::EndOfStream) {
+                    return self.end_stream(step);
+                }
+
+                let send_start = Instant::now();
+                let result = trace_span!("send").in_scope(|| self.compute_handler(output_data, tags));
+                let send_time = send_start.elapsed();
+
+                metrics.record_call(CallRecord { items_in, items_out, samples_in, samples_out, receive_time, compute_time, send_time });
                 result
             }
         }
     }
 
-    fn compute_handler(&mut self, output_data: Result<ODFormat<O>, String>) -> PipelineStepResult {
+    pub fn end_stream(&mut self, step: &mut impl PipelineStep<I, O>) -> PipelineStepResult {
+        // flush the step, then pass the end of stream on so every node downstream does the same in turn
+        if let Some(residual) = step.flush() {
+            self.tap.push(&residual);
+            if self.output.send(residual, Vec::new()).is_err() {
+                log_message(format!("NodeID: {} could not send the output flushed at the end of stream", &self.id), Level::Warn);
+            }
+        }
+        if self.output.send_end_of_stream().is_err() {
+            log_message(format!("NodeID: {} could not pass the end of stream downstream", &self.id), Level::Warn);
+        }
+
+        self.ended = true;
+        log_message(format!("NodeID: {} reached the end of stream", &self.id), Level::Info);
+        PipelineStepResult::EndOfStream
+    }
+
+    fn compute_handler(&mut self, output_data: Result<ODFormat<O>, StepError>, tags: Vec<StreamTag>) -> PipelineStepResult {
         match output_data {
             Err(err) => PipelineStepResult::ComputeError(err),
             Ok(extracted_data) => {
-                let extracted_data = self.push_to_tap(extracted_data);
-                match self.output.send(extracted_data) {
+                self.tap.push(&extracted_data);
+                match self.output.send(extracted_data, tags) {
                     Err(_) => PipelineStepResult::SendError,
                     Ok(_) => {
                         PipelineStepResult::Success
@@ -121,20 +342,17 @@ impl<I: Sharable, O: Sharable> PipelineNode<I, O> {
             }
         }
     }
-    fn push_to_tap(&mut self, output_data: ODFormat<O>) -> ODFormat<O> {
-        match &mut self.tap {
-            None => output_data,
-            Some(tap) => {
-                let cloned_output = output_data.clone();
-                tap.push(output_data);
-                cloned_output
-            }
+
+    fn capture_input(&self, received: &ReceiveType<I>) {
+        // the input is only cloned while someone subscribes to it, sources have no input to capture
+        if self.input_tap.has_subscribers() && !matches!(received, ReceiveType::Dummy) {
+            self.input_tap.push(&CapturedInput { received: Instant::now(), input: received.clone() });
         }
     }
-    
-    fn route_computation(&mut self, input_data: ReceiveType<I>, step: &mut impl PipelineStep<I, O>) -> PipelineStepResult {
+
+    fn route_computation(&mut self, input_data: ReceiveType<I>, step: &mut impl PipelineStep<I, O>) -> Result<ODFormat<O>, StepError> {
         //log_message(format!("CRITICAL: NodeID: {}, Received message: {}", &self.id, &input_data), Level::Debug);
-        self.compute_handler(match (input_data, &self.output) {
+        match (input_data, &self.output) {
             (ReceiveType::Single(t), NodeSender::SO(_) | NodeSender::MUO(_)) => step.run_SISO(t),
             (ReceiveType::Single(t), NodeSender::MO(_)) => step.run_SIMO(t),
             (ReceiveType::Multichannel(t), NodeSender::SO(_) | NodeSender::MUO(_)) => step.run_MISO(t),
@@ -143,8 +361,8 @@ impl<I: Sharable, O: Sharable> PipelineNode<I, O> {
             (ReceiveType::Reassembled(t), NodeSender::MO(_)) => step.run_REAMO(t),
             (ReceiveType::Dummy, NodeSender::SO(_)) => step.run_DISO(),
             (ReceiveType::Single(t), NodeSender::Dummy) => step.run_SIDO(t),
-            (_, _) => Err(String::from("Received bad message from pipeline step")),
-        })
+            (_, _) => Err(StepError::fatal("Received bad message from pipeline step")),
+        }
     }
 }
 
@@ -153,33 +371,40 @@ pub struct NodeBuilder<I: Sharable, O: Sharable> {
     node: PipelineNode<I, O>,
     construction_queue: ConstructionQueue,
     parameters: PipelineParameters,
-    state: (Arc<AtomicU8>, mpsc::Sender<ThreadStateSpace>),
+    state: NodeStateCommunicator,
+    graph: GraphRecorder,
+    namespace: String, // prefix for node ids created through this builder, set while a recipe is being constructed
 }
 impl<I: Sharable, O: Sharable> NodeBuilder<I, O> {
     pub fn attach<F: Sharable>(mut self, id: &'static str, step: impl PipelineStep<I, O> + 'static) -> NodeBuilder<O, F> {
         // attach a step to the selected node (self) and create a thread
         // produce a successor node to continue the pipeline
-        let (sender, receiver) = mpsc::sync_channel::<O>(self.parameters.backpressure_val);
-        let mut successor: PipelineNode<O, F> = PipelineNode::new();
+        let (sender, receiver) = wrapped_link::<O>(self.parameters.channel_backend, self.parameters.backpressure_val);
+        let mut successor: PipelineNode<O, F> = PipelineNode::new_in_graph(&self.graph);
 
-        self.node.set_id(id);
+        self.node.set_id(&scoped_id(&self.namespace, id));
+        self.graph.lock().unwrap().add_edge(self.node.graph_index, successor.graph_index, self.parameters.backpressure_val, GraphEdgeKind::Standard);
 
         self.node.output = NodeSender::SO(SingleSender::new(sender));
-        successor.input = NodeReceiver::SI(SingleReceiver::new(WrappedReceiver::new(receiver), self.parameters.timeout, self.parameters.retries));
+        successor.input = NodeReceiver::SI(SingleReceiver::new(receiver, self.parameters.timeout, self.parameters.retries));
+
+        self.graph.lock().unwrap().register_node(&self.node);
 
         let new_thread = PipelineThread::new(step, self.node, self.parameters.clone(), self.state.clone());
         self.construction_queue.push(new_thread);
 
-        NodeBuilder { node: successor, construction_queue: self.construction_queue, parameters: self.parameters, state: self.state}
+        NodeBuilder { node: successor, construction_queue: self.construction_queue, parameters: self.parameters, state: self.state, graph: self.graph, namespace: self.namespace }
     }
 
     pub fn cap_pipeline(mut self, id: &'static str, step: impl PipelineStep<I, O> + 'static + Sink)
     where O: Unit {
         // End a linear pipeline branch, allowing the step itself to handle output to other parts of the program
-        self.node.set_id(id);
+        self.node.set_id(&scoped_id(&self.namespace, id));
 
         self.node.output = NodeSender::Dummy;
 
+        self.graph.lock().unwrap().register_node(&self.node);
+
         let new_thread = PipelineThread::new(step, self.node, self.parameters.clone(), self.state);
         self.construction_queue.push(new_thread);
     }
@@ -188,45 +413,51 @@ impl<I: Sharable, O: Sharable> NodeBuilder<I, O> {
     where I: Unit {
         let parameters = pipeline.get_cloned_parameters();
         // start a pipeline, allowing the step itself to handle input from other parts of the program
-        let (sender, receiver) = mpsc::sync_channel::<O>(parameters.backpressure_val);
+        let (sender, receiver) = wrapped_link::<O>(parameters.channel_backend, parameters.backpressure_val);
 
-        let start_node: PipelineNode<I, O> = PipelineNode {
-            input: NodeReceiver::Dummy,
-            output: NodeSender::SO(SingleSender::new(sender)),
-            id: start_id.to_string(),
-            tap: None,
-        };
+        let graph = pipeline.get_graph();
+        let mut start_node: PipelineNode<I, O> = PipelineNode::new_in_graph(&graph);
+        start_node.input = NodeReceiver::Dummy;
+        start_node.output = NodeSender::SO(SingleSender::new(sender));
+        start_node.set_id(start_id);
 
-        let mut successor: PipelineNode<O, F> = PipelineNode::new();
-        successor.input = NodeReceiver::SI(SingleReceiver::new(WrappedReceiver::new(receiver), parameters.timeout, parameters.retries));
+        let mut successor: PipelineNode<O, F> = PipelineNode::new_in_graph(&graph);
+        successor.input = NodeReceiver::SI(SingleReceiver::new(receiver, parameters.timeout, parameters.retries));
+
+        graph.lock().unwrap().add_edge(start_node.graph_index, successor.graph_index, parameters.backpressure_val, GraphEdgeKind::Standard);
+        graph.lock().unwrap().register_node(&start_node);
 
         let new_thread = PipelineThread::new(source_step, start_node, parameters.clone(), pipeline.get_state_communicators());
         pipeline.get_nodes().push(new_thread);
 
-        NodeBuilder { node: successor, parameters, construction_queue: pipeline.get_nodes(), state: pipeline.get_state_communicators() }
+        NodeBuilder { node: successor, parameters, construction_queue: pipeline.get_nodes(), state: pipeline.get_state_communicators(), graph: pipeline.get_graph(), namespace: String::new() }
     }
 
     pub fn split_begin(mut self, id: &'static str) -> SplitBuilder<I, O> {
         // take the node outputted by a previous step in the builder and declare it as multiple out
         // allows the node to have multiple outputs appended
-        self.node.set_id(id);
+        self.node.set_id(&scoped_id(&self.namespace, id));
         let sender: MultichannelSender<O> = MultichannelSender::new();
         self.node.output = NodeSender::MO(sender);
 
-        SplitBuilder { node: self.node, parameters: self.parameters, construction_queue: self.construction_queue, state: self.state.clone() }
+        SplitBuilder { node: self.node, parameters: self.parameters, construction_queue: self.construction_queue, state: self.state, graph: self.graph, namespace: self.namespace }
     }
 
     pub fn mutltiplexer_begin(mut self, id: &'static str, channel_selector: Arc<AtomicUsize>) -> MultiplexerBuilder<I, O> {
-        self.node.set_id(id);
+        self.node.set_id(&scoped_id(&self.namespace, id));
+        self.graph.lock().unwrap().add_selector(self.node.graph_index, channel_selector.clone());
         let sender: Multiplexer<O> = Multiplexer::new(channel_selector);
         self.node.output = NodeSender::MUO(sender);
 
-        MultiplexerBuilder { node: self.node, parameters: self.parameters, construction_queue: self.construction_queue, state: self.state }
+        MultiplexerBuilder { node: self.node, parameters: self.parameters, construction_queue: self.construction_queue, state: self.state, graph: self.graph, namespace: self.namespace }
     }
 
     pub fn branch_end(mut self, joint_builder: &mut JointBuilder<I, O>) {
         match self.node.input {
-            NodeReceiver::SI(receiver) => joint_builder.joint_add(receiver.extract_receiver()),
+            NodeReceiver::SI(receiver) => {
+                self.graph.lock().unwrap().merge_node(self.node.graph_index, joint_builder.node.graph_index);
+                joint_builder.joint_add(receiver.extract_receiver())
+            },
             NodeReceiver::Dummy => panic!("Cannot end branch with Dummy"),
             _ => panic!("Must end branch with single. This should be automatic behavior")
         }
@@ -234,16 +465,62 @@ impl<I: Sharable, O: Sharable> NodeBuilder<I, O> {
     
     pub fn multiplex_branch_end(mut self, demultiplexer_builder: &mut DemultiplexerBuilder<I, O>) {
         match self.node.input {
-            NodeReceiver::SI(receiver) => demultiplexer_builder.demultiplexer_add(receiver.extract_receiver()),
+            NodeReceiver::SI(receiver) => {
+                self.graph.lock().unwrap().merge_node(self.node.graph_index, demultiplexer_builder.node.graph_index);
+                demultiplexer_builder.demultiplexer_add(receiver.extract_receiver())
+            },
             NodeReceiver::Dummy => panic!("Cannot end multiplexed branch with Dummy"),
             _ => panic!("Must end branch with single. This should be automatic behavior")
         }
     }
 
+    pub fn recipe<M: Sharable, F: Sharable, R: PipelineRecipe<I, M, Entry = O>>(mut self, namespace: &str, recipe: R) -> NodeBuilder<M, F> {
+        // expand a recipe at this point of the pipeline, with every node it creates namespaced under this builder's namespace
+        let outer_namespace = self.namespace.clone();
+        self.namespace = scoped_id(&outer_namespace, namespace);
+
+        let mut exit = recipe.construct(self);
+        exit.namespace = outer_namespace;
+        exit
+    }
+
+    pub fn joint_begin<JI: Sharable, JO: Sharable>(&self, id: &str) -> JointBuilder<JI, JO> {
+        // joint_begin for recipes, which only have a builder and not the ConstructingPipeline. the joint shares this builder's namespace
+        let mut joint_node: PipelineNode<JI, JO> = PipelineNode::new_in_graph(&self.graph);
+        joint_node.set_id(&scoped_id(&self.namespace, id));
+        joint_node.input = NodeReceiver::MI(MultichannelReceiver::new(self.parameters.timeout, self.parameters.retries));
+
+        JointBuilder { node: joint_node, parameters: self.parameters.clone(), construction_queue: self.construction_queue.clone(), state: self.state.clone(), graph: self.graph.clone(), namespace: self.namespace.clone() }
+    }
+
+    pub fn with_tap(&self, options: TapOptions) -> TapHandle<ODFormat<O>> {
+        // subscribe to the output of the node attached next, before the pipeline is finished so not a single buffer is missed
+        self.node.tap_point().subscribe(options)
+    }
+
+    pub fn backpressure(self, policy: BackpressurePolicy) -> Self {
+        // what the link into the node attached next does when it is full. The policies which drop need a backpressure_val above 0,
+        // and cannot be used on a link into a reassembler
+        if let Err(reason) = self.node.input.set_backpressure(policy) {
+            panic!("Cannot set backpressure on this node, {}", reason)
+        }
+        self
+    }
+
+    pub fn thread_options(mut self, options: ThreadOptions) -> Self {
+        // the os thread settings of the node attached next, overriding whatever the pipeline parameters give it
+        self.node.thread_options = Some(options);
+        self
+    }
+
     pub fn add_reassembler(mut self, reassemble_quantity: usize) -> Self {
         match self.node.input {
             NodeReceiver::SI(receiver) => {
-                self.node.input = NodeReceiver::REA(Reassembler::new(receiver.extract_receiver(), reassemble_quantity, self.parameters.timeout, self.parameters.retries))
+                let receiver = receiver.extract_receiver();
+                if let Err(reason) = receiver.backpressure().check_link(None, true) {
+                    panic!("Cannot set reassembler on this node, {}", reason)
+                }
+                self.node.input = NodeReceiver::REA(Reassembler::new(receiver, reassemble_quantity, self.parameters.timeout, self.parameters.retries))
             }
             _ => panic!("Cannot set reassembler on this node, must be a single receiver node"),
         }
@@ -255,29 +532,32 @@ impl<I: Sharable, O: Sharable> NodeBuilder<I, O> {
 
 pub fn demultiplexer_begin<JI: Sharable, JO: Sharable>(id: &str, channel_selector: Arc<AtomicUsize>, pipeline: &ConstructingPipeline) -> DemultiplexerBuilder<JI, JO> {
     // create a node marked as a join which can take multiple input receivers. used to join multiple sub branches together (eg adder or something)
-    let mut demultiplexer_node: PipelineNode<JI, JO> = PipelineNode { input: NodeReceiver::Dummy, output: NodeSender::Dummy, id: id.to_string(), tap: None };
+    let mut demultiplexer_node: PipelineNode<JI, JO> = PipelineNode::new_in_graph(&pipeline.get_graph());
+    demultiplexer_node.set_id(id);
     let parameters = pipeline.get_cloned_parameters();
     demultiplexer_node.input = NodeReceiver::DMI(Demultiplexer::new(channel_selector, parameters.timeout, parameters.retries));
 
-    DemultiplexerBuilder { node: demultiplexer_node, parameters, construction_queue: pipeline.get_nodes(), state: pipeline.get_state_communicators() }
+    DemultiplexerBuilder { node: demultiplexer_node, parameters, construction_queue: pipeline.get_nodes(), state: pipeline.get_state_communicators(), graph: pipeline.get_graph(), namespace: String::new() }
 }
 
 pub fn joint_begin<JI: Sharable, JO: Sharable>(id: &str, pipeline: &ConstructingPipeline) -> JointBuilder<JI, JO> {
     // create a node marked as a joint which can take multiple input receivers. used to join multiple sub branches together (eg adder or something)
-    let mut joint_node: PipelineNode<JI, JO> = PipelineNode { input: NodeReceiver::Dummy, output: NodeSender::Dummy, id: id.to_string(), tap: None };
+    let mut joint_node: PipelineNode<JI, JO> = PipelineNode::new_in_graph(&pipeline.get_graph());
+    joint_node.set_id(id);
     let parameters = pipeline.get_cloned_parameters();
     joint_node.input = NodeReceiver::MI(MultichannelReceiver::new(parameters.timeout, parameters.retries));
 
-    JointBuilder { node: joint_node, parameters, construction_queue: pipeline.get_nodes(), state: pipeline.get_state_communicators() }
+    JointBuilder { node: joint_node, parameters, construction_queue: pipeline.get_nodes(), state: pipeline.get_state_communicators(), graph: pipeline.get_graph(), namespace: String::new() }
 }
 
 pub fn joint_feedback_begin<I: Sharable, O: Sharable>(id: &str, pipeline: &ConstructingPipeline) -> JointBuilder<I, O> {
     // Since there is no convenient origin point for a joint used in feedback in the pattern, a standalone function is needed to support type inference
-    let mut joint_node: PipelineNode<I, O> = PipelineNode { input: NodeReceiver::Dummy, output: NodeSender::Dummy, id: id.to_string(), tap: None };
+    let mut joint_node: PipelineNode<I, O> = PipelineNode::new_in_graph(&pipeline.get_graph());
+    joint_node.set_id(id);
     let parameters = pipeline.get_cloned_parameters();
     joint_node.input = NodeReceiver::MI(MultichannelReceiver::new(parameters.timeout, parameters.retries));
 
-    JointBuilder { node: joint_node, parameters, construction_queue: pipeline.get_nodes(), state: pipeline.get_state_communicators() }
+    JointBuilder { node: joint_node, parameters, construction_queue: pipeline.get_nodes(), state: pipeline.get_state_communicators(), graph: pipeline.get_graph(), namespace: String::new() }
 }
 
 
@@ -285,7 +565,9 @@ pub struct SplitBuilder<I: Sharable, O: Sharable> {
     node: PipelineNode<I, O>,
     construction_queue: ConstructionQueue,
     parameters: PipelineParameters,
-    state: (Arc<AtomicU8>, mpsc::Sender<ThreadStateSpace>),
+    state: NodeStateCommunicator,
+    graph: GraphRecorder,
+    namespace: String, // prefix for node ids created through this builder, set while a recipe is being constructed
 }
 impl<I: Sharable, O: Sharable> SplitBuilder<I, O> {
     pub fn split_add<F: Sharable>(&mut self) -> NodeBuilder<O, F> {
@@ -293,14 +575,15 @@ impl<I: Sharable, O: Sharable> SplitBuilder<I, O> {
         // returns the head of the new branch which can be attached to like a normal linear pipeline
         match &mut self.node.output {
             NodeSender::MO(node_sender) => {
-                let (split_sender, split_receiver) = mpsc::sync_channel::<O>(self.parameters.backpressure_val);
+                let (split_sender, split_receiver) = wrapped_link::<O>(self.parameters.channel_backend, self.parameters.backpressure_val);
                 node_sender.add_sender(split_sender);
 
-                let mut successor: PipelineNode<O, F> = PipelineNode::new();
+                let mut successor: PipelineNode<O, F> = PipelineNode::new_in_graph(&self.graph);
+                self.graph.lock().unwrap().add_edge(self.node.graph_index, successor.graph_index, self.parameters.backpressure_val, GraphEdgeKind::Split);
 
-                successor.input = NodeReceiver::SI(SingleReceiver::new(WrappedReceiver::new(split_receiver), self.parameters.timeout, self.parameters.retries));
+                successor.input = NodeReceiver::SI(SingleReceiver::new(split_receiver, self.parameters.timeout, self.parameters.retries));
 
-                NodeBuilder { node: successor, parameters: self.parameters.clone(), construction_queue: self.construction_queue.clone(), state: self.state.clone() }
+                NodeBuilder { node: successor, parameters: self.parameters.clone(), construction_queue: self.construction_queue.clone(), state: self.state.clone(), graph: self.graph.clone(), namespace: self.namespace.clone() }
             }
             _ => panic!("To add a split branch you must declare a node as a splitter with split_begin!")
         }
@@ -308,6 +591,7 @@ impl<I: Sharable, O: Sharable> SplitBuilder<I, O> {
 
     pub fn split_lock(self, step: impl PipelineStep<I, O> + 'static) {
         // submit the split to the thread pool, preventing any more branches from being added and making it computable
+        self.graph.lock().unwrap().register_node(&self.node);
         let new_thread = PipelineThread::new(step, self.node, self.parameters.clone(), self.state.clone());
         self.construction_queue.push(new_thread);
     }
@@ -318,16 +602,20 @@ pub struct LazyJointInputBuilder<I: Sharable, O: Sharable> {
     node:  PipelineNode<I, O>,
     construction_queue: ConstructionQueue,
     parameters: PipelineParameters,
-    state: (Arc<AtomicU8>, mpsc::Sender<ThreadStateSpace>),
+    state: NodeStateCommunicator,
+    graph: GraphRecorder,
+    namespace: String, // prefix for node ids created through this builder, set while a recipe is being constructed
 }
 impl<I: Sharable, O: Sharable> LazyJointInputBuilder<I, O> {
     pub fn joint_link_lazy(mut self, id: &'static str, step: impl PipelineStep<I, O>, source_node: NodeBuilder<I, O>) {
         // takes the final node of a branch and attaches it to a lazy node's input. You must still assign the lazy node input with joint_lazy_finalize
-        self.node.set_id(id);
+        self.node.set_id(&scoped_id(&self.namespace, id));
 
         match source_node.node.input {
             NodeReceiver::SI(receiver) => {
+                self.graph.lock().unwrap().merge_node(source_node.node.graph_index, self.node.graph_index);
                 self.node.input = NodeReceiver::SI(receiver);
+                self.graph.lock().unwrap().register_node(&self.node);
                 let new_thread = PipelineThread::new(step, self.node, self.parameters.clone(), self.state.clone());
                 self.construction_queue.push(new_thread);
             }
@@ -341,7 +629,9 @@ pub struct JointBuilder<I: Sharable, O: Sharable> {
     node: PipelineNode<I, O>,
     construction_queue: ConstructionQueue,
     parameters: PipelineParameters,
-    state: (Arc<AtomicU8>, mpsc::Sender<ThreadStateSpace>),
+    state: NodeStateCommunicator,
+    graph: GraphRecorder,
+    namespace: String, // prefix for node ids created through this builder, set while a recipe is being constructed
 }
 impl<I: Sharable, O: Sharable> JointBuilder<I, O> {
     fn joint_add(&mut self, receiver: WrappedReceiver<I>) {
@@ -355,16 +645,19 @@ impl<I: Sharable, O: Sharable> JointBuilder<I, O> {
     pub fn joint_lock<F: Sharable>(mut self, step: impl PipelineStep<I, O> + 'static) -> NodeBuilder<O, F> {
         match &mut self.node.input {
             NodeReceiver::MI(_) => {
-                let (sender, receiver) = mpsc::sync_channel::<O>(self.parameters.backpressure_val);
-                let mut successor: PipelineNode<O, F> = PipelineNode::new();
+                let (sender, receiver) = wrapped_link::<O>(self.parameters.channel_backend, self.parameters.backpressure_val);
+                let mut successor: PipelineNode<O, F> = PipelineNode::new_in_graph(&self.graph);
+                self.graph.lock().unwrap().add_edge(self.node.graph_index, successor.graph_index, self.parameters.backpressure_val, GraphEdgeKind::Standard);
 
                 self.node.output = NodeSender::SO(SingleSender::new(sender));
-                successor.input = NodeReceiver::SI(SingleReceiver::new(WrappedReceiver::new(receiver), self.parameters.timeout, self.parameters.retries));
+                successor.input = NodeReceiver::SI(SingleReceiver::new(receiver, self.parameters.timeout, self.parameters.retries));
+
+                self.graph.lock().unwrap().register_node(&self.node);
 
                 let new_thread = PipelineThread::new(step, self.node, self.parameters.clone(), self.state.clone());
                 self.construction_queue.push(new_thread);
 
-                NodeBuilder { node: successor, parameters: self.parameters.clone(), construction_queue: self.construction_queue.clone(), state: self.state.clone() }
+                NodeBuilder { node: successor, parameters: self.parameters.clone(), construction_queue: self.construction_queue.clone(), state: self.state.clone(), graph: self.graph.clone(), namespace: self.namespace.clone() }
             }
             _ => panic!("To joint lock a node it must be declared as a joint")
         }
@@ -372,16 +665,17 @@ impl<I: Sharable, O: Sharable> JointBuilder<I, O> {
 
     pub fn joint_add_lazy<F: Sharable>(&mut self) -> LazyJointInputBuilder<F, I> {
         // creates an empty placeholder node for a joint that can be made concrete later to facilitate feedback architecture
-        let (sender, receiver) = mpsc::sync_channel::<I>(self.parameters.backpressure_val);
+        let (sender, receiver) = wrapped_link::<I>(self.parameters.channel_backend, self.parameters.backpressure_val);
         match &mut self.node.input {
-            NodeReceiver::MI(node_receiver) => node_receiver.add_receiver(WrappedReceiver::new(receiver).set_startup_flag()),
+            NodeReceiver::MI(node_receiver) => node_receiver.add_receiver(receiver.set_startup_flag(self.parameters.execution_model)),
             _ => panic!("Cannot add lazy feedback node to a node which was not declared as a joint with joint_begin")
         };
 
-        let mut lazy_node = PipelineNode::new();
+        let mut lazy_node = PipelineNode::new_in_graph(&self.graph);
         lazy_node.output = NodeSender::SO(SingleSender::new(sender));
+        self.graph.lock().unwrap().add_edge(lazy_node.graph_index, self.node.graph_index, self.parameters.backpressure_val, GraphEdgeKind::Feedback);
 
-        LazyJointInputBuilder { node: lazy_node, parameters: self.parameters.clone(), construction_queue: self.construction_queue.clone(), state: self.state.clone() }
+        LazyJointInputBuilder { node: lazy_node, parameters: self.parameters.clone(), construction_queue: self.construction_queue.clone(), state: self.state.clone(), graph: self.graph.clone(), namespace: self.namespace.clone() }
     }
 }
 
@@ -389,7 +683,9 @@ pub struct MultiplexerBuilder<I: Sharable, O: Sharable> {
     node: PipelineNode<I, O>,
     construction_queue: ConstructionQueue,
     parameters: PipelineParameters,
-    state: (Arc<AtomicU8>, mpsc::Sender<ThreadStateSpace>),
+    state: NodeStateCommunicator,
+    graph: GraphRecorder,
+    namespace: String, // prefix for node ids created through this builder, set while a recipe is being constructed
 }
 impl<I: Sharable, O: Sharable> MultiplexerBuilder<I, O> {
     pub fn multiplexer_add<F: Sharable>(&mut self) -> NodeBuilder<O, F> {
@@ -397,19 +693,23 @@ impl<I: Sharable, O: Sharable> MultiplexerBuilder<I, O> {
         // returns the head of the new branch which can be attached to like a normal linear pipeline
         match &mut self.node.output {
             NodeSender::MUO(node_sender) => {
-                let (multiplexer_sender, multiplexer_receiver) = mpsc::sync_channel::<O>(self.parameters.backpressure_val);
+                let (multiplexer_sender, multiplexer_receiver) = wrapped_link::<O>(self.parameters.channel_backend, self.parameters.backpressure_val);
+                // the nodes of the branch only run while the selector points at it
+                let branch_state = self.state.for_branch(node_sender.channel_selector(), node_sender.branches());
                 node_sender.add_sender(multiplexer_sender);
 
-                let mut successor: PipelineNode<O, F> = PipelineNode::new();
+                let mut successor: PipelineNode<O, F> = PipelineNode::new_in_graph(&self.graph);
+                self.graph.lock().unwrap().add_edge(self.node.graph_index, successor.graph_index, self.parameters.backpressure_val, GraphEdgeKind::Multiplexed);
 
-                successor.input = NodeReceiver::SI(SingleReceiver::new(WrappedReceiver::new(multiplexer_receiver), self.parameters.timeout, self.parameters.retries));
-                NodeBuilder { node: successor, parameters: self.parameters.clone(), construction_queue: self.construction_queue.clone(), state: self.state.clone() }
+                successor.input = NodeReceiver::SI(SingleReceiver::new(multiplexer_receiver, self.parameters.timeout, self.parameters.retries));
+                NodeBuilder { node: successor, parameters: self.parameters.clone(), construction_queue: self.construction_queue.clone(), state: branch_state, graph: self.graph.clone(), namespace: self.namespace.clone() }
             }
             _ => panic!("To add a multiplexer branch you must declare it as a multiplexer with multiplexer_start")
         }
     }
     pub fn multiplexer_lock(self, step: impl PipelineStep<I, O> + 'static) {
         // submit the split to the thread pool, preventing any more branches from being added and making it computable
+        self.graph.lock().unwrap().register_node(&self.node);
         let new_thread = PipelineThread::new(step, self.node, self.parameters.clone(), self.state.clone());
         self.construction_queue.push(new_thread);
     }
@@ -420,7 +720,9 @@ pub struct DemultiplexerBuilder<I: Sharable, O: Sharable> {
     node: PipelineNode<I, O>,
     construction_queue: ConstructionQueue,
     parameters: PipelineParameters,
-    state: (Arc<AtomicU8>, mpsc::Sender<ThreadStateSpace>),
+    state: NodeStateCommunicator,
+    graph: GraphRecorder,
+    namespace: String, // prefix for node ids created through this builder, set while a recipe is being constructed
 }
 impl<I: Sharable, O: Sharable> DemultiplexerBuilder<I, O> {
     fn demultiplexer_add(&mut self, receiver: WrappedReceiver<I>) {
@@ -434,16 +736,19 @@ impl<I: Sharable, O: Sharable> DemultiplexerBuilder<I, O> {
     pub fn demultiplexer_lock<F: Sharable>(mut self, step: impl PipelineStep<I, O> + 'static) -> NodeBuilder<O, F> {
         match &mut self.node.input {
             NodeReceiver::DMI(_) => {
-                let (sender, receiver) = mpsc::sync_channel::<O>(self.parameters.backpressure_val);
-                let mut successor: PipelineNode<O, F> = PipelineNode::new();
+                let (sender, receiver) = wrapped_link::<O>(self.parameters.channel_backend, self.parameters.backpressure_val);
+                let mut successor: PipelineNode<O, F> = PipelineNode::new_in_graph(&self.graph);
+                self.graph.lock().unwrap().add_edge(self.node.graph_index, successor.graph_index, self.parameters.backpressure_val, GraphEdgeKind::Standard);
 
                 self.node.output = NodeSender::SO(SingleSender::new(sender));
-                successor.input = NodeReceiver::SI(SingleReceiver::new(WrappedReceiver::new(receiver), self.parameters.timeout, self.parameters.retries));
+                successor.input = NodeReceiver::SI(SingleReceiver::new(receiver, self.parameters.timeout, self.parameters.retries));
+
+                self.graph.lock().unwrap().register_node(&self.node);
 
                 let new_thread = PipelineThread::new(step, self.node, self.parameters.clone(), self.state.clone());
                 self.construction_queue.push(new_thread);
 
-                NodeBuilder { node: successor, parameters: self.parameters.clone(), construction_queue: self.construction_queue.clone(), state: self.state.clone() }
+                NodeBuilder { node: successor, parameters: self.parameters.clone(), construction_queue: self.construction_queue.clone(), state: self.state.clone(), graph: self.graph.clone(), namespace: self.namespace.clone() }
             }
             _ => panic!("To joint lock a node it must be declared as a joint")
         }
@@ -452,5 +757,17 @@ impl<I: Sharable, O: Sharable> DemultiplexerBuilder<I, O> {
 
 
 pub trait PipelineRecipe<I: Sharable, O: Sharable> { // allow to save and standardize macro-scale components that you dont wan tto repeatedly redefine
-    fn construct<FI: Sharable, FO: Sharable>(origin_node: PipelineNode<FI, I>, construction_queue: ConstructionQueue) -> PipelineNode<O, FO>;
+    /*
+    A reusable sub graph taking I in and giving O out, attached to a pipeline with NodeBuilder::recipe.
+    Entry is the output type of the first step inside the recipe, so construct gets a builder it can attach to directly.
+    Every node id created inside construct is prefixed with the namespace given to NodeBuilder::recipe (eg rx/agc), recipes inside recipes nest (rx/filter/fft)
+     */
+    type Entry: Sharable;
+
+    fn construct<F: Sharable>(self, builder: NodeBuilder<I, Self::Entry>) -> NodeBuilder<O, F>;
+}
+
+
+fn scoped_id(namespace: &str, id: &str) -> String {
+    if namespace.is_empty() { id.to_string() } else { format!("{}/{}", namespace, id) }
 }
\ No newline at end of file
@@ -0,0 +1,180 @@
+// Watchdog supervision, so a node whose step panics or gets stuck does not leave the rest of the pipeline running free around it.
+// Every node shares a NodeHealth with the supervisor: the node marks when each call starts and ends and reports panics caught around
+// its calls, and the supervisor (run by the pipeline manager thread, or by the deterministic executor after every call) applies the
+// node's SupervisionPolicy once it sees a panic, or a call which has been running for longer than the stall timeout.
+// A thread cannot be interrupted, so a stalled step is only restarted once its call returns. Until then pausing or killing the
+// pipeline is all that can be done, and kill leaves the stalled thread behind instead of waiting on it forever.
+
+use std::any::Any;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use super::pipeline_errors::StepError;
+use super::pipeline_step::{PipelineStep, StepWrapper, wrapped_step};
+use super::pipeline_traits::{Sharable, Sink, Source};
+use super::thread_state_space::ThreadStateSpace;
+use super::logging::{log_message, Level};
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SupervisionPolicy {
+    /*
+    What the supervisor does with a node whose step panicked or stalled
+    Restart: replace the step with a fresh one (see PipelineStep::restart_step) and carry on, the rest of the pipeline is left alone
+    PausePipeline: pause the whole pipeline and restart the step, so the pipeline can be started again once whoever watches it is ready
+    KillPipeline: kill the whole pipeline
+     */
+    Restart,
+    PausePipeline,
+    KillPipeline
+}
+impl Default for SupervisionPolicy {
+    fn default() -> Self { SupervisionPolicy::KillPipeline }
+}
+
+
+pub struct NodeHealth {
+    id: String,
+    policy: SupervisionPolicy,
+    epoch: Instant,
+    call_started: AtomicU64, // microseconds since the epoch plus one, 0 while the node is not in a call
+    failed: AtomicBool, // the step panicked and is not called again until it was restarted
+    stalled: AtomicBool,
+    handled: AtomicBool, // the supervisor already applied the policy to the current failure or stall
+    restart_requested: AtomicBool,
+    restarts: AtomicUsize,
+    panic_message: Mutex<Option<String>>,
+}
+impl NodeHealth {
+    pub fn new(id: &str, policy: SupervisionPolicy) -> Self {
+        Self {
+            id: id.to_string(),
+            policy,
+            epoch: Instant::now(),
+            call_started: AtomicU64::new(0),
+            failed: AtomicBool::new(false),
+            stalled: AtomicBool::new(false),
+            handled: AtomicBool::new(false),
+            restart_requested: AtomicBool::new(false),
+            restarts: AtomicUsize::new(0),
+            panic_message: Mutex::new(None),
+        }
+    }
+    pub fn begin_call(&self) {
+        self.call_started.store(self.epoch.elapsed().as_micros() as u64 + 1, Ordering::Release);
+    }
+    pub fn end_call(&self) {
+        self.call_started.store(0, Ordering::Release);
+        if self.stalled.swap(false, Ordering::AcqRel) && !self.failed.load(Ordering::Acquire) {
+            self.handled.store(false, Ordering::Release);
+            log_message(format!("Supervisor: node {} returned from its stalled call", &self.id), Level::Warn);
+        }
+    }
+    fn call_time(&self) -> Option<Duration> {
+        match self.call_started.load(Ordering::Acquire) {
+            0 => None,
+            started => Some(self.epoch.elapsed().saturating_sub(Duration::from_micros(started - 1)))
+        }
+    }
+    pub fn report_panic(&self, payload: Box<dyn Any + Send>) -> StepError {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&'static str>() {
+                Ok(message) => message.to_string(),
+                Err(_) => "unknown panic payload".to_string()
+            }
+        };
+        log_message(format!("Supervisor: node {} panicked: {}", &self.id, &message), Level::Error);
+        *self.panic_message.lock().unwrap() = Some(message.clone());
+        self.failed.store(true, Ordering::Release);
+        StepError::fatal(format!("step panicked: {}", message))
+    }
+    pub fn has_failed(&self) -> bool {
+        self.failed.load(Ordering::Acquire)
+    }
+    pub fn is_stalled(&self) -> bool {
+        self.stalled.load(Ordering::Acquire)
+    }
+    pub fn restart_requested(&self) -> bool {
+        self.restart_requested.load(Ordering::Acquire)
+    }
+    pub fn take_restart_request(&self) -> bool {
+        self.restart_requested.swap(false, Ordering::AcqRel)
+    }
+    pub fn restarted(&self) {
+        self.restarts.fetch_add(1, Ordering::AcqRel);
+        self.failed.store(false, Ordering::Release);
+        self.handled.store(false, Ordering::Release);
+        log_message(format!("Supervisor: node {} restarted its step", &self.id), Level::Info);
+    }
+    pub fn restarts(&self) -> usize {
+        self.restarts.load(Ordering::Acquire)
+    }
+    pub fn panic_message(&self) -> Option<String> { // the last panic, kept after a restart
+        self.panic_message.lock().unwrap().clone()
+    }
+}
+
+
+#[derive(Clone, Default)]
+pub struct Supervisor {
+    nodes: Vec<Arc<NodeHealth>>,
+    stall_timeout: Option<Duration> // None disables stall detection
+}
+impl Supervisor {
+    pub fn new(nodes: Vec<Arc<NodeHealth>>, stall_timeout: Option<Duration>) -> Self {
+        Self { nodes, stall_timeout }
+    }
+    pub fn check(&self, state: &Arc<AtomicU8>) {
+        for node in self.nodes.iter() {
+            let stalled = match (self.stall_timeout, node.call_time()) {
+                (Some(timeout), Some(call_time)) => call_time > timeout,
+                _ => false
+            };
+            if stalled && !node.stalled.swap(true, Ordering::AcqRel) {
+                log_message(format!("Supervisor: node {} has been in one call for longer than {:?}", &node.id, self.stall_timeout.unwrap()), Level::Error);
+            }
+
+            if (node.has_failed() || node.is_stalled()) && !node.handled.swap(true, Ordering::AcqRel) {
+                Self::apply_policy(node, state);
+            }
+        }
+    }
+    fn apply_policy(node: &NodeHealth, state: &Arc<AtomicU8>) {
+        match node.policy {
+            SupervisionPolicy::Restart => node.restart_requested.store(true, Ordering::Release),
+            SupervisionPolicy::PausePipeline => {
+                node.restart_requested.store(true, Ordering::Release);
+                // only moves a running pipeline, a killed one stays killed
+                let _ = state.compare_exchange(ThreadStateSpace::RUNNING as u8, ThreadStateSpace::PAUSED as u8, Ordering::AcqRel, Ordering::Acquire);
+            },
+            SupervisionPolicy::KillPipeline => state.store(ThreadStateSpace::KILLED as u8, Ordering::Release)
+        }
+        log_message(format!("Supervisor: applied {:?} to node {}", node.policy, &node.id), Level::Warn);
+    }
+}
+
+
+pub struct Supervised<S> {
+    // wraps a step with the factory it is built from, so the supervisor can replace it with a fresh one
+    step: S,
+    factory: Box<dyn Fn() -> S + Send>,
+    policy: SupervisionPolicy
+}
+impl<S> Supervised<S> {
+    pub fn new(policy: SupervisionPolicy, factory: impl Fn() -> S + Send + 'static) -> Self {
+        Self { step: factory(), factory: Box::new(factory), policy }
+    }
+}
+impl<I: Sharable, O: Sharable, S: PipelineStep<I, O>> StepWrapper<I, O> for Supervised<S> {
+    fn inner(&mut self) -> &mut dyn PipelineStep<I, O> { &mut self.step }
+    fn inner_ref(&self) -> &dyn PipelineStep<I, O> { &self.step }
+    fn wrapped_policy(&self) -> SupervisionPolicy { self.policy }
+    fn wrapped_restart(&mut self) -> bool {
+        self.step = (self.factory)();
+        true
+    }
+}
+wrapped_step!([I: Sharable, O: Sharable, S: PipelineStep<I, O>] Supervised<S>, I, O);
+impl<S: Source> Source for Supervised<S> {}
+impl<S: Sink> Sink for Supervised<S> {}
@@ -0,0 +1,128 @@
+// Hot swapping replaces the step of one node while the pipeline runs, eg a convolution with a new impulse response or another demodulator.
+// The node keeps its links, so upstream and downstream carry on and whatever is queued for the node is handled by the new step.
+// The replacement is a command on the node's control queue and is taken in between two work calls like a control message: the old step
+// is paused and killed, and the new one started if the node is running. Replacements which could not work in the node are rejected
+// before they are queued, a step of other input or output types, or one without the handler the node is wired for.
+// The node keeps the supervision policy of the step it was built with.
+
+use std::any::{type_name, Any, TypeId};
+use std::sync::mpsc;
+use std::time::Duration;
+use super::pipeline_control::{ControlSender, NodeCommand};
+use super::pipeline_errors::{SwapError, TopologyViolation};
+use super::pipeline_step::{PipelineStep, StepHandler, StepWrapper, wrapped_step};
+use super::pipeline_traits::Sharable;
+use super::logging::{log_message, Level};
+
+
+pub enum SwappableStep<I: Sharable, O: Sharable, S: PipelineStep<I, O>> {
+    // the step a node runs, the one it was built with until the first swap
+    Built(S),
+    Swapped(Box<dyn PipelineStep<I, O>>)
+}
+impl<I: Sharable, O: Sharable, S: PipelineStep<I, O>> SwappableStep<I, O, S> {
+    fn active(&mut self) -> &mut dyn PipelineStep<I, O> {
+        match self {
+            SwappableStep::Built(step) => step,
+            SwappableStep::Swapped(step) => step.as_mut()
+        }
+    }
+    fn active_ref(&self) -> &dyn PipelineStep<I, O> {
+        match self {
+            SwappableStep::Built(step) => step,
+            SwappableStep::Swapped(step) => step.as_ref()
+        }
+    }
+    fn replace(&mut self, mut replacement: Box<dyn PipelineStep<I, O>>, running: bool) {
+        let retired = self.active();
+        if running {
+            retired.pause_behavior();
+        }
+        retired.kill_behavior();
+
+        if running {
+            replacement.start_behavior();
+        }
+        *self = SwappableStep::Swapped(replacement);
+    }
+}
+impl<I: Sharable, O: Sharable, S: PipelineStep<I, O>> StepWrapper<I, O> for SwappableStep<I, O, S> {
+    fn inner(&mut self) -> &mut dyn PipelineStep<I, O> { self.active() }
+    fn inner_ref(&self) -> &dyn PipelineStep<I, O> { self.active_ref() }
+}
+wrapped_step!([I: Sharable, O: Sharable, S: PipelineStep<I, O>] SwappableStep<I, O, S>, I, O);
+
+
+pub struct SwapTarget {
+    // what a replacement must look like to work in a node, with the step types erased so the pipeline can hold one for every node
+    input_type: TypeId,
+    output_type: TypeId,
+    type_names: String,
+    required_handler: Option<StepHandler>,
+    node_id: String
+}
+impl SwapTarget {
+    pub fn new<I: Sharable, O: Sharable>(node_id: &str, required_handler: Option<StepHandler>) -> Self {
+        let type_names = format!("{} -> {}", type_name::<I>(), type_name::<O>());
+        Self { input_type: TypeId::of::<I>(), output_type: TypeId::of::<O>(), type_names, required_handler, node_id: node_id.to_string() }
+    }
+    pub fn send<I: Sharable, O: Sharable>(&self, control: &ControlSender, step: Box<dyn PipelineStep<I, O>>) -> Result<SwapTicket, SwapError> {
+        if (TypeId::of::<I>(), TypeId::of::<O>()) != (self.input_type, self.output_type) {
+            return Err(SwapError::TypeMismatch {
+                node_id: self.node_id.clone(),
+                expected: self.type_names.clone(),
+                provided: format!("{} -> {}", type_name::<I>(), type_name::<O>())
+            });
+        }
+
+        let supported_handlers = step.handlers();
+        if !self.required_handler.is_some_and(|handler| supported_handlers.contains(&handler)) {
+            return Err(SwapError::UnsupportedHandler(TopologyViolation { node_id: self.node_id.clone(), required_handler: self.required_handler, supported_handlers }));
+        }
+
+        match control.send_command(|reply| NodeCommand::Swap(Box::new(step), reply)) {
+            Some(reply) => Ok(SwapTicket { node_id: self.node_id.clone(), reply }),
+            None => Err(SwapError::NodeStopped { node_id: self.node_id.clone() })
+        }
+    }
+}
+
+
+pub fn take_swap<I: Sharable, O: Sharable, S: PipelineStep<I, O>>(step: &mut SwappableStep<I, O, S>, replacement: Box<dyn Any + Send>, running: bool, node_id: &str) -> bool {
+    // run by the node when the swap command comes off its control queue. False if the replacement is not a step of the node's types,
+    // which SwapTarget::send has already ruled out
+    match replacement.downcast::<Box<dyn PipelineStep<I, O>>>() {
+        Ok(replacement) => {
+            step.replace(*replacement, running);
+            log_message(format!("NodeID: {} swapped in a new step", node_id), Level::Info);
+            true
+        },
+        Err(_) => {
+            log_message(format!("NodeID: {} was sent a step of other types to swap in", node_id), Level::Error);
+            false
+        }
+    }
+}
+
+
+pub struct SwapTicket {
+    // the acknowledgement of one swap, which arrives once the node took the new step in between two work calls
+    node_id: String,
+    reply: mpsc::Receiver<()>
+}
+impl SwapTicket {
+    pub fn try_reply(&self) -> Option<Result<(), SwapError>> { // None while the node has not taken the step in yet
+        match self.reply.try_recv() {
+            Ok(_) => Some(Ok(())),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some(Err(SwapError::NodeStopped { node_id: self.node_id.clone() }))
+        }
+    }
+    pub fn wait(&self, timeout: Duration) -> Result<(), SwapError> {
+        match self.reply.recv_timeout(timeout) {
+            Ok(_) => Ok(()),
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(SwapError::Timeout { node_id: self.node_id.clone() }),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(SwapError::NodeStopped { node_id: self.node_id.clone() })
+        }
+    }
+}
@@ -0,0 +1,178 @@
+// Taps let whatever watches the pipeline (a scope, a debugger, a recorder) see the data of a node without being part of the graph.
+// Every node owns a TapPoint on its output and one on its input, which subscribers attach to and detach from at any time, even while
+// the pipeline runs. Each subscriber
+// gets a bounded queue of its own: when it falls behind the oldest buffer is discarded, so a slow subscriber never holds up the node.
+// A node without subscribers pays for one atomic load per call, nothing is cloned.
+
+use std::any::{type_name, Any};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use crossbeam_queue::ArrayQueue;
+use super::pipeline_comms::{ODFormat, ReceiveType};
+use super::pipeline_errors::TapError;
+use super::pipeline_traits::Sharable;
+
+
+const TAP_POLL_TIME: Duration = Duration::from_millis(1);
+
+
+pub trait TapBuffer = Clone + Send + Sync + 'static;
+
+
+#[derive(Debug, Clone)]
+pub struct CapturedInput<T: Sharable> { // what the input tap of a node carries, one per call
+    pub received: Instant,
+    pub input: ReceiveType<T>
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TapOptions {
+    pub capacity: usize, // buffers held for the subscriber before the oldest is discarded
+    pub every: usize // only every Nth buffer is copied to the subscriber
+}
+impl TapOptions {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), every: 1 }
+    }
+    pub fn with_decimation(mut self, every: usize) -> Self {
+        self.every = every.max(1);
+        self
+    }
+}
+impl Default for TapOptions {
+    fn default() -> Self { Self::new(16) }
+}
+
+
+struct TapSubscription<B: TapBuffer> {
+    queue: ArrayQueue<B>,
+    every: usize,
+    seen: AtomicUsize,
+    dropped: AtomicUsize
+}
+
+
+pub struct TapPoint<B: TapBuffer> {
+    subscribers: RwLock<Vec<Arc<TapSubscription<B>>>>,
+    attached: AtomicUsize // mirrors the length of subscribers, so the node can skip the lock when nobody listens
+}
+impl<B: TapBuffer> TapPoint<B> {
+    pub fn new() -> Self {
+        Self { subscribers: RwLock::new(Vec::new()), attached: AtomicUsize::new(0) }
+    }
+    pub fn subscribe(self: &Arc<Self>, options: TapOptions) -> TapHandle<B> {
+        let subscription = Arc::new(TapSubscription {
+            queue: ArrayQueue::new(options.capacity.max(1)),
+            every: options.every.max(1),
+            seen: AtomicUsize::new(0),
+            dropped: AtomicUsize::new(0)
+        });
+
+        self.attach(subscription.clone());
+        TapHandle { point: self.clone(), subscription, attached: true }
+    }
+    fn attach(&self, subscription: Arc<TapSubscription<B>>) {
+        let mut subscribers = self.subscribers.write().unwrap();
+        subscribers.push(subscription);
+        self.attached.store(subscribers.len(), Ordering::Release);
+    }
+    fn unsubscribe(&self, subscription: &Arc<TapSubscription<B>>) {
+        let mut subscribers = self.subscribers.write().unwrap();
+        subscribers.retain(|subscriber| !Arc::ptr_eq(subscriber, subscription));
+        self.attached.store(subscribers.len(), Ordering::Release);
+    }
+    pub fn has_subscribers(&self) -> bool {
+        self.attached.load(Ordering::Acquire) > 0
+    }
+    pub fn push(&self, data: &B) {
+        if !self.has_subscribers() { return }
+
+        for subscriber in self.subscribers.read().unwrap().iter() {
+            if subscriber.seen.fetch_add(1, Ordering::AcqRel) % subscriber.every != 0 { continue }
+            if subscriber.queue.force_push(data.clone()).is_some() {
+                subscriber.dropped.fetch_add(1, Ordering::AcqRel);
+            }
+        }
+    }
+}
+
+
+pub struct TapHandle<B: TapBuffer> {
+    /*
+    One subscriber of a tap point, detached when it is dropped. Output buffers come out in the format the step returned them in,
+    and the ones discarded because the queue was full are counted by dropped
+     */
+    point: Arc<TapPoint<B>>,
+    subscription: Arc<TapSubscription<B>>,
+    attached: bool
+}
+impl<B: TapBuffer> TapHandle<B> {
+    pub fn try_recv(&self) -> Option<B> {
+        self.subscription.queue.pop()
+    }
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<B> {
+        let start = Instant::now();
+        loop {
+            if let Some(data) = self.try_recv() { return Some(data) }
+            if start.elapsed() >= timeout { return None }
+            sleep(TAP_POLL_TIME);
+        }
+    }
+    pub fn drain(&self) -> Vec<B> {
+        let mut buffers = Vec::with_capacity(self.subscription.queue.len());
+        while let Some(data) = self.try_recv() {
+            buffers.push(data);
+        }
+        buffers
+    }
+    pub fn pending(&self) -> usize {
+        self.subscription.queue.len()
+    }
+    pub fn dropped(&self) -> usize {
+        self.subscription.dropped.load(Ordering::Acquire)
+    }
+    pub fn is_attached(&self) -> bool {
+        self.attached
+    }
+    pub fn detach(&mut self) { // stops new buffers arriving, whatever is already queued can still be received
+        if self.attached {
+            self.point.unsubscribe(&self.subscription);
+            self.attached = false;
+        }
+    }
+    pub fn reattach(&mut self) {
+        if !self.attached {
+            self.point.attach(self.subscription.clone());
+            self.attached = true;
+        }
+    }
+}
+impl<B: TapBuffer> Drop for TapHandle<B> {
+    fn drop(&mut self) {
+        self.detach();
+    }
+}
+
+
+pub struct ErasedTap {
+    // a tap point of a node with its type erased, so the pipeline can hand out typed handles after it was built
+    point: Arc<dyn Any + Send + Sync>,
+    data_type: &'static str // the type the node receives or outputs, not the buffer around it, for the error message
+}
+impl ErasedTap {
+    pub fn output<T: Sharable>(point: Arc<TapPoint<ODFormat<T>>>) -> Self {
+        Self { point, data_type: type_name::<T>() }
+    }
+    pub fn input<T: Sharable>(point: Arc<TapPoint<CapturedInput<T>>>) -> Self {
+        Self { point, data_type: type_name::<T>() }
+    }
+    pub fn subscribe<T: Sharable, B: TapBuffer>(&self, node_id: &str, options: TapOptions) -> Result<TapHandle<B>, TapError> {
+        match self.point.clone().downcast::<TapPoint<B>>() {
+            Ok(point) => Ok(point.subscribe(options)),
+            Err(_) => Err(TapError::WrongType { node_id: node_id.to_string(), requested: type_name::<T>(), data_type: self.data_type })
+        }
+    }
+}
@@ -1,16 +1,24 @@
 use std::fmt::Debug;
 use std::thread::{self, sleep, JoinHandle};
 use std::sync::{Mutex, Arc, RwLock, MutexGuard};
-use std::sync::atomic::{AtomicU64, Ordering, AtomicU8};
-use std::sync::mpsc;
-use std::sync::mpsc::RecvTimeoutError;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::time::{Duration, Instant};
+use std::path::Path;
 use num_enum::TryFromPrimitive;
-use std::convert::TryFrom;
 use strum::Display;
 use super::pipeline_step::{PipelineStep, PipelineNode, PipelineStepResult};
 use super::pipeline_traits::{HasID, Sharable};
 use super::api::*;
+use super::pipeline_metrics::NodeMetrics;
+use super::pipeline_checkpoint::{CheckpointRequest, CheckpointTicket};
+use super::pipeline_control::{control_port, ControlMessage, ControlPort, ControlSender, ControlTicket, NodeCommand};
+use super::pipeline_supervisor::NodeHealth;
+use super::pipeline_swap::{SwappableStep, SwapTarget, SwapTicket};
+use super::pipeline_taps::{CapturedInput, ErasedTap, TapHandle, TapOptions};
+use super::pipeline_recording::{InputRecorder, Recordable, RECORDER_CAPACITY};
+use super::pipeline_thread_orchestrator::{ExecutionModel, ScheduledNode};
 
 
 struct ThreadErrorCounter {
@@ -55,20 +63,22 @@ struct ThreadStateMachine {
     state: ThreadStateSpace,
     error_counter: ThreadErrorCounter,
     no_change_timer: u64,
-    state_change_messenger: mpsc::Sender<ThreadStateSpace>,
+    communicator: NodeStateCommunicator,
     id: String
 }
 impl ThreadStateMachine {
-    pub fn new(parameters: &PipelineParameters, id: String, messenger: mpsc::Sender<ThreadStateSpace>) -> Self {
+    pub fn new(parameters: &PipelineParameters, id: String, communicator: NodeStateCommunicator) -> Self {
         let error_counter = ThreadErrorCounter::new(parameters.max_infrastructure_errors, parameters.max_compute_errors);
-        Self { error_counter, state: ThreadStateSpace::PAUSED, id, no_change_timer: parameters.unchanged_state_time, state_change_messenger: messenger }
+        Self { error_counter, state: ThreadStateSpace::PAUSED, id, no_change_timer: parameters.unchanged_state_time, communicator }
     }
     fn state_transition<I: Sharable, O: Sharable>(&mut self, requested_state: ThreadStateSpace, mut previous_output: PipelineStepResult, step: &mut impl PipelineStep<I, O>) {
+        let state_before_errors = self.state.clone();
+
         if requested_state == ThreadStateSpace::KILLED {
             self.kill_request_handler(step);
         }
-        else if self.error_count_increment(&mut previous_output, step) {
-            return;
+        else if self.error_count_increment(&mut previous_output, step) && self.state != state_before_errors {
+            return; // the error paused or killed the node itself, which takes precedence over the request this round
         } else {
             match requested_state {
                 ThreadStateSpace::PAUSED => self.pause_request_handler(step),
@@ -94,16 +104,33 @@ impl ThreadStateMachine {
                 if self.error_counter.infrastructure_error_lim_check(&self.id) { self.set_kill_state_upstream(step) }
                 true
             }
-            PipelineStepResult::ComputeError(message) => {
-                self.error_counter.compute_error();
-                log_message(format!("ThreadID: {} compute error {}, pausing", &self.id, message), Level::Warn);
-                if self.error_counter.compute_error_lim_check(&self.id) { self.set_pause_state_upstream(step) };
+            PipelineStepResult::ComputeError(error) => {
+                self.compute_error_handler(error, step);
                 true
             }
-            PipelineStepResult::Success => { self.error_counter.success(); false }
+            PipelineStepResult::Success | PipelineStepResult::EndOfStream => { self.error_counter.success(); false }
             PipelineStepResult::Carryover => false
         }
     }
+    fn compute_error_handler<I: Sharable, O: Sharable>(&mut self, error: &StepError, step: &mut impl PipelineStep<I, O>) {
+        match error.kind() {
+            StepErrorKind::Recoverable => {
+                self.error_counter.compute_error();
+                log_message(format!("ThreadID: {} recoverable compute error {}, retrying", &self.id, error), Level::Warn);
+                if self.error_counter.compute_error_lim_check(&self.id) { self.set_pause_state_upstream(step) };
+            }
+            StepErrorKind::NeedsReset => {
+                log_message(format!("ThreadID: {} compute error {}, resetting step and pausing", &self.id, error), Level::Warn);
+                step.reset_behavior();
+                self.set_pause_state_upstream(step);
+            }
+            StepErrorKind::Fatal => {
+                log_message(format!("ThreadID: {} fatal compute error {}, killing", &self.id, error), Level::Error);
+                self.set_kill_state_upstream(step);
+            }
+            StepErrorKind::EndOfStream => () // handled by the node, it never comes back as a compute error
+        }
+    }
     fn set_kill_state<I: Sharable, O: Sharable>(&mut self, step: &mut impl PipelineStep<I, O>) {
         self.state = ThreadStateSpace::KILLED;
         step.kill_behavior();
@@ -112,7 +139,7 @@ impl ThreadStateMachine {
     fn set_kill_state_upstream<I: Sharable, O: Sharable>(&mut self, step: &mut impl PipelineStep<I, O>) {
         self.set_kill_state(step);
         log_message(format!("Thread ID: {}, sending kill upstream", &self.id), Level::Debug);
-        match self.state_change_messenger.send(ThreadStateSpace::KILLED) {
+        match self.communicator.messenger.send(ThreadStateSpace::KILLED) {
             Err(error) => panic!("Critical error, cannot reach management thread!"),
             Ok(_) => log_message(format!("Thread ID: {}, sent kill upstream", &self.id), Level::Debug)
         }
@@ -124,8 +151,13 @@ impl ThreadStateMachine {
     }
     fn set_pause_state_upstream<I: Sharable, O: Sharable>(&mut self, step: &mut impl PipelineStep<I, O>) {
         self.set_pause_state(step);
+        if let Some(branch) = &self.communicator.branch { // inside a multiplexed branch only the branch is paused
+            branch.request_pause();
+            log_message(format!("Thread ID: {}, paused its multiplexer branch", &self.id), Level::Debug);
+            return;
+        }
         log_message(format!("Thread ID: {}, sending pause upstream", &self.id), Level::Debug);
-        match self.state_change_messenger.send(ThreadStateSpace::PAUSED) {
+        match self.communicator.messenger.send(ThreadStateSpace::PAUSED) {
             Err(error) => panic!("Critical error, cannot reach management thread!"),
             Ok(_) => log_message(format!("Thread ID: {}, sent pause upstream", &self.id), Level::Debug)
         }
@@ -162,13 +194,18 @@ impl ThreadStateMachine {
             }
         }
     }
-    fn call<I: Sharable, O: Sharable>(&mut self, requested_state: ThreadStateSpace, previous_result: PipelineStepResult, node: &mut PipelineNode<I, O>, step: &mut impl PipelineStep<I, O>) -> PipelineStepResult {
+    fn call<I: Sharable, O: Sharable>(&mut self, requested_state: ThreadStateSpace, previous_result: PipelineStepResult, node: &mut PipelineNode<I, O>, step: &mut impl PipelineStep<I, O>, metrics: &NodeMetrics) -> PipelineStepResult {
         self.state_transition(requested_state, previous_result, step);
 
         let result = match self.state {
+            ThreadStateSpace::RUNNING if node.has_ended() => {
+                sleep(Duration::from_millis(self.no_change_timer));
+                PipelineStepResult::Carryover
+            },
+            ThreadStateSpace::RUNNING if node.is_source() && self.communicator.drain_requested() => node.end_stream(step),
             ThreadStateSpace::RUNNING => {
                 log_message(format!("ThreadID: {} call start", &self.id), Level::Debug);
-                let res = node.call(step);
+                let res = node.call(step, metrics);
                 log_message(format!("ThreadID: {} call done", &self.id), Level::Debug);
                 res
             },
@@ -176,80 +213,295 @@ impl ThreadStateMachine {
         };
         result
     }
+    fn transition<I: Sharable, O: Sharable>(&mut self, requested_state: ThreadStateSpace, previous_result: PipelineStepResult, step: &mut impl PipelineStep<I, O>) -> PipelineStepResult {
+        // a call without running the node, for the pool scheduler when a node has a state change to make but nothing to receive yet
+        self.state_transition(requested_state, previous_result, step);
+        PipelineStepResult::Carryover
+    }
+}
+
+
+struct NodeRunner<I: Sharable, O: Sharable, S: PipelineStep<I, O>> {
+    // one node with everything its action loop needs. The thread per node model loops over iterate, the pool scheduler calls it once per dispatch
+    state_machine: ThreadStateMachine,
+    previous_result: PipelineStepResult,
+    node: PipelineNode<I, O>,
+    step: SwappableStep<I, O, S>,
+    execution_time: Arc<AtomicU64>,
+    return_code: Arc<RwLock<PipelineStepResult>>,
+    last_error: Arc<RwLock<Option<StepError>>>,
+    metrics: Arc<NodeMetrics>,
+    end_of_stream: Arc<AtomicBool>,
+    control: ControlPort,
+    health: Arc<NodeHealth>,
+}
+impl<I: Sharable, O: Sharable, S: PipelineStep<I, O>> NodeRunner<I, O, S> {
+    fn requested_state(&self) -> ThreadStateSpace {
+        self.state_machine.communicator.requested_state()
+    }
+    fn iterate(&mut self, node_ready: bool) {
+        self.state_machine.communicator.clear_branch_pause_if_deselected();
+        let requested_state = self.requested_state();
+        log_message(format!("ThreadID: {} requested state {}, current state {}", self.node.get_id(), requested_state, self.state_machine.state), Level::Info);
+        if self.health.take_restart_request() {
+            self.restart_step();
+        }
+        if !self.is_killed() { // control messages are handled between work calls, whatever state the node is in
+            self.control.deliver(&mut self.step, &mut self.node.input, &self.state_machine.state, &self.state_machine.id);
+        }
+        let start_time = Instant::now();
+        let previous_result = std::mem::replace(&mut self.previous_result, PipelineStepResult::Carryover);
+        let node_ready = node_ready && !self.health.has_failed(); // a step which panicked is left alone until the supervisor restarts it
+
+        self.health.begin_call();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| match node_ready {
+            true => self.state_machine.call(requested_state, previous_result, &mut self.node, &mut self.step, &self.metrics),
+            false => self.state_machine.transition(requested_state, previous_result, &mut self.step)
+        }));
+        self.health.end_call();
+
+        self.previous_result = match result {
+            Ok(result) => result,
+            Err(payload) => { // reported straight to the diagnostics, the state machine must not count it as a fatal error and kill the node
+                let error = self.health.report_panic(payload);
+                *self.return_code.write().unwrap() = PipelineStepResult::ComputeError(error.clone());
+                *self.last_error.write().unwrap() = Some(error);
+                PipelineStepResult::Carryover
+            }
+        };
+
+        self.execution_time.store(start_time.elapsed().as_micros() as u64, Ordering::Release);
+
+        if self.previous_result != PipelineStepResult::Carryover {
+            let mut write_guard = self.return_code.write().unwrap();
+            *write_guard = self.previous_result.clone();
+        }
+        if let PipelineStepResult::ComputeError(error) = &self.previous_result {
+            let mut write_guard = self.last_error.write().unwrap();
+            *write_guard = Some(error.clone());
+        }
+        if self.previous_result == PipelineStepResult::EndOfStream {
+            self.end_of_stream.store(true, Ordering::Release);
+        }
+    }
+    fn restart_step(&mut self) {
+        if !self.step.restart_step() {
+            self.step.reset_behavior();
+        }
+        self.health.restarted();
+    }
+    fn is_killed(&self) -> bool {
+        self.state_machine.state == ThreadStateSpace::KILLED
+    }
+}
+impl<I: Sharable, O: Sharable, S: PipelineStep<I, O>> ScheduledNode for NodeRunner<I, O, S> {
+    fn wants_call(&self) -> bool {
+        if self.is_killed() { return false }
+
+        self.state_machine.communicator.clear_branch_pause_if_deselected(); // a paused node is only called again once its branch is free to run
+        let requested_state = self.requested_state();
+        // errors from the previous call are counted on the next dispatch, like the node thread counts them on its next iteration
+        if requested_state != self.state_machine.state || self.control.has_pending() || self.health.restart_requested() { true }
+        else { self.state_machine.state == ThreadStateSpace::RUNNING && !self.health.has_failed() && self.node.is_ready() }
+    }
+    fn call(&mut self) {
+        let node_ready = self.node.is_ready();
+        self.iterate(node_ready);
+    }
+    fn is_finished(&self) -> bool {
+        self.is_killed()
+    }
+    fn get_id(&self) -> String {
+        self.node.get_id()
+    }
+    fn graph_index(&self) -> usize {
+        self.node.graph_index
+    }
+}
+
+
+enum NodeExecution {
+    Thread(JoinHandle<()>),
+    Scheduled(Box<dyn ScheduledNode>), // waiting to be handed to the pool scheduler or the deterministic executor
+    Pooled // owned by the pool scheduler or the deterministic executor, there is no thread to join
 }
 
 
 pub struct PipelineThread {
-    pub requested_state: Arc<AtomicU8>,
     pub execution_time: Arc<AtomicU64>,
-    pipeline_step_thread: Option<JoinHandle<()>>,
+    execution: Option<NodeExecution>,
     pub return_code: Arc<RwLock<PipelineStepResult>>,
-    state_sender: Option<mpsc::Sender<ThreadStateSpace>>,
+    pub last_error: Arc<RwLock<Option<StepError>>>,
+    pub metrics: Arc<NodeMetrics>,
+    pub end_of_stream: Arc<AtomicBool>, // set once the node has flushed its step and passed the end of stream on
+    pub is_sink: bool,
+    pub dropped: Vec<Arc<AtomicUsize>>, // values discarded by the backpressure policies of the node's output links
+    control: ControlSender,
+    swap: SwapTarget,
+    pub health: Arc<NodeHealth>,
+    tap: ErasedTap,
+    input_tap: ErasedTap,
+    state: NodeStateCommunicator,
+    pub topology_violation: Option<TopologyViolation>,
+    pub thread_options: ThreadOptions,
+    pub scheduling_error: Option<SchedulingError>, // the thread options which could not be applied, reported once the node thread started
     pub id: String
 }
 
 impl PipelineThread {
     pub fn new<I: Sharable, O: Sharable>
-    (step: impl PipelineStep<I, O> + 'static, node: PipelineNode<I, O>, parameters: PipelineParameters, state: (Arc<AtomicU8>, mpsc::Sender<ThreadStateSpace>)) -> PipelineThread { // requires node to be borrowed as static?
+    (step: impl PipelineStep<I, O> + 'static, mut node: PipelineNode<I, O>, parameters: PipelineParameters, state: NodeStateCommunicator) -> PipelineThread { // requires node to be borrowed as static?
         let execution_time = Arc::new(AtomicU64::new(0));
+        let thread_options = node.thread_options.take().unwrap_or_else(|| parameters.thread_options_for(&node.get_id()));
+        let topology_violation = Self::check_topology(&step, &node);
+        let (control, control_port) = control_port(&node.get_id());
+        let swap = SwapTarget::new::<I, O>(&node.get_id(), node.required_handler());
+        let health = Arc::new(NodeHealth::new(&node.get_id(), step.supervision_policy()));
 
         let mut thread = PipelineThread {
             execution_time,
-            pipeline_step_thread: None,
+            execution: None,
             return_code: Arc::new(RwLock::new(PipelineStepResult::Success)),
+            last_error: Arc::new(RwLock::new(None)),
+            metrics: Arc::new(NodeMetrics::new()),
+            end_of_stream: Arc::new(AtomicBool::new(false)),
+            is_sink: node.is_sink(),
+            dropped: node.output.dropped_counters(),
+            control,
+            swap,
+            health,
+            tap: ErasedTap::output(node.tap_point()),
+            input_tap: ErasedTap::input(node.input_tap_point()),
             id: String::from("NoID"),
-            requested_state: state.0, // all threads start as paused initially
-            state_sender: Some(state.1)
+            state, // all threads start as paused initially
+            topology_violation,
+            thread_options,
+            scheduling_error: None
         };
         
-        thread.instantiate_thread(step, node, parameters);
+        thread.instantiate_thread(step, node, parameters, control_port);
         
         return thread;
     }
 
+    fn check_topology<I: Sharable, O: Sharable>(step: &impl PipelineStep<I, O>, node: &PipelineNode<I, O>) -> Option<TopologyViolation> {
+        // checked before the thread starts so finish_pipeline can reject the graph instead of panicking on the first message
+        let required_handler = node.required_handler();
+        let supported_handlers = step.handlers();
+
+        match required_handler {
+            Some(handler) if supported_handlers.contains(&handler) => None,
+            _ => Some(TopologyViolation { node_id: node.get_id(), required_handler, supported_handlers })
+        }
+    }
+
     fn instantiate_thread<I: Sharable, O: Sharable>
-    (&mut self, mut step: impl PipelineStep<I, O> + 'static, mut node: PipelineNode<I, O>, parameters: PipelineParameters) {
-        let execution_clone = self.execution_time.clone();
-        let return_code_clone = self.return_code.clone();
-        let state_receiver = self.requested_state.clone();
-        let state_sender = self.state_sender.take();
-
-        let state_sender = match state_sender {
-            None => panic!("Cannot start step thread without attaching the managerial thread broadcaster"),
-            Some(sender) => sender
+    (&mut self, step: impl PipelineStep<I, O> + 'static, node: PipelineNode<I, O>, parameters: PipelineParameters, control: ControlPort) {
+        self.id = node.get_id();
+
+        let mut runner = NodeRunner {
+            state_machine: ThreadStateMachine::new(&parameters, node.get_id(), self.state.clone()),
+            previous_result: PipelineStepResult::Carryover,
+            node,
+            step: SwappableStep::Built(step),
+            execution_time: self.execution_time.clone(),
+            return_code: self.return_code.clone(),
+            last_error: self.last_error.clone(),
+            metrics: self.metrics.clone(),
+            end_of_stream: self.end_of_stream.clone(),
+            control,
+            health: self.health.clone(),
         };
 
-        self.id = node.get_id();
+        let unchanged_state_time = parameters.unchanged_state_time;
+        let thread_options = self.thread_options.clone();
+        let (scheduling_sender, scheduling_receiver) = mpsc::sync_channel(1);
+
+        self.execution = Some(match parameters.execution_model {
+            ExecutionModel::ThreadPerNode => NodeExecution::Thread(thread_options.thread_builder(&self.id).spawn(move || { // refactor this so it isnt nonsense
+                let _ = scheduling_sender.send(thread_options.apply_to_current(&runner.state_machine.id).err());
+                while !runner.is_killed() {
+                    runner.iterate(true);
+                    if runner.health.has_failed() && !runner.health.restart_requested() { // waiting on the supervisor
+                        sleep(Duration::from_millis(unchanged_state_time));
+                    }
+                }
+                log_message(format!("ThreadID: {} state machine end of action loop", runner.node.get_id()), Level::Info);
+            }).expect("Failed to spawn a pipeline node thread")),
+            ExecutionModel::ThreadPool(_) | ExecutionModel::Deterministic => {
+                let unsupported = thread_options.changes_scheduling().then(|| {
+                    SchedulingError::Unsupported { node_id: self.id.clone(), message: format!("the {} execution model runs nodes on shared threads", parameters.execution_model) }
+                });
+                let _ = scheduling_sender.send(unsupported);
+                NodeExecution::Scheduled(Box::new(runner))
+            }
+        });
 
-        self.pipeline_step_thread = Some(thread::spawn(move || { // refactor this so it isnt nonsense
-            let mut state_machine = ThreadStateMachine::new(&parameters, node.get_id(), state_sender);
-            let mut previous_result = PipelineStepResult::Carryover;
-            let kill_comp = ThreadStateSpace::KILLED;
+        // waits for the node thread so the outcome is known before finish_pipeline, the thread stays paused until then anyway
+        self.scheduling_error = scheduling_receiver.recv().ok().flatten();
+        if let Some(error) = &self.scheduling_error {
+            let level = if self.thread_options.best_effort { Level::Warn } else { Level::Error };
+            log_message(format!("ThreadID: {} {}", self.id, error), level);
+        }
+    }
 
-            while state_machine.state != kill_comp {
-                let requested_state: ThreadStateSpace = ThreadStateSpace::try_from(state_receiver.load(Ordering::Acquire)).unwrap();
-                log_message(format!("ThreadID: {} requested state {}, current state {}", node.get_id(), requested_state, state_machine.state), Level::Info);
-                let start_time = Instant::now();
-                previous_result = state_machine.call(requested_state, previous_result, &mut node, &mut step);
+    pub fn requested_state(&self) -> ThreadStateSpace { // the pipeline state, or the branch state for nodes inside a multiplexed branch
+        self.state.requested_state()
+    }
 
-                execution_clone.store(start_time.elapsed().as_micros() as u64, Ordering::Release);
+    pub fn is_held_by_branch(&self) -> bool { // a node in a multiplexed branch which is paused while the rest of the pipeline runs
+        self.state.global_requested_state() == ThreadStateSpace::RUNNING && self.state.requested_state() != ThreadStateSpace::RUNNING
+    }
 
-                if previous_result != PipelineStepResult::Carryover {
-                    let mut write_guard = return_code_clone.write().unwrap();
-                    *write_guard = previous_result.clone();
-                }
+    pub fn send_control(&self, message: ControlMessage) -> Result<ControlTicket, ControlError> {
+        self.control.send(message)
+    }
+    pub fn swap_step<I: Sharable, O: Sharable>(&self, step: Box<dyn PipelineStep<I, O>>) -> Result<SwapTicket, SwapError> {
+        self.swap.send(&self.control, step)
+    }
+    pub fn checkpoint_request(&self, request: CheckpointRequest) -> Result<CheckpointTicket, CheckpointError> {
+        match self.control.send_command(|reply| NodeCommand::Checkpoint(request, reply)) {
+            Some(reply) => Ok(CheckpointTicket::new(&self.id, reply)),
+            None => Err(CheckpointError::NodeStopped { node_id: self.id.clone() })
+        }
+    }
+
+    pub fn tap<T: Sharable>(&self, options: TapOptions) -> Result<TapHandle<ODFormat<T>>, TapError> {
+        self.tap.subscribe::<T, ODFormat<T>>(&self.id, options)
+    }
+    pub fn input_tap<T: Sharable>(&self, options: TapOptions) -> Result<TapHandle<CapturedInput<T>>, TapError> {
+        self.input_tap.subscribe::<T, CapturedInput<T>>(&self.id, options)
+    }
+    pub fn record_inputs<T: Recordable>(&self, path: &Path) -> Result<InputRecorder, RecordingError> {
+        let tap = self.input_tap::<T>(TapOptions::new(RECORDER_CAPACITY)).map_err(RecordingError::Tap)?;
+        InputRecorder::start(&self.id, tap, path)
+    }
+
+    pub fn take_scheduled(&mut self) -> Option<Box<dyn ScheduledNode>> {
+        match self.execution.take() {
+            Some(NodeExecution::Scheduled(node)) => {
+                self.execution = Some(NodeExecution::Pooled);
+                Some(node)
+            },
+            other => {
+                self.execution = other;
+                None
             }
-            log_message(format!("ThreadID: {} state machine end of action loop", node.get_id()), Level::Info); })
-        );
+        }
     }
     
     pub fn join(self) {
-        match self.pipeline_step_thread {
+        match self.execution {
             None => panic!("pipeline thread already joined or was never created"),
-            Some(handle) => {
+            Some(NodeExecution::Thread(_)) if self.health.is_stalled() => {
+                log_message(format!("PipelineThread: Left stalled pipeline thread {} behind instead of joining it", self.id), Level::Error);
+            },
+            Some(NodeExecution::Thread(handle)) => {
                 handle.join().unwrap();
                 log_message(format!("PipelineThread: Joined pipeline thread {}", self.id), Level::Info);
             },
+            Some(NodeExecution::Scheduled(_)) => log_message(format!("PipelineThread: Discarded unscheduled node {}", self.id), Level::Info),
+            Some(NodeExecution::Pooled) => ()
         }
     }
 }
@@ -260,7 +512,7 @@ pub trait CollectibleThread {
 }
 
 
-impl<I: Sharable, O: Sharable> CollectibleThread for PipelineThread<I, O> {
+impl CollectibleThread for PipelineThread {
     fn call_thread(&mut self) {
         
     }
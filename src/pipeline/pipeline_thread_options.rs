@@ -0,0 +1,145 @@
+// Real time controls for the os thread of a node, so audio rate sinks and sample critical sources can be kept clear of heavy stages
+// like long ffts. The options are applied by the node thread itself as soon as it is spawned, and the outcome is passed back to the
+// builder before it moves on, so finish_pipeline can refuse a pipeline whose nodes did not get the scheduling they asked for.
+// Only the thread per node execution model gives every node a thread of its own, the other models report any options as unsupported.
+
+use std::thread;
+use strum::Display;
+use super::pipeline_errors::SchedulingError;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Display)]
+pub enum SchedulingPolicy {
+    /*
+    The os scheduling policy of a node thread
+    Other: the standard time sharing policy, the priority sets the niceness
+    Batch: time sharing for cpu heavy threads which are slightly disfavored on wakeup, the priority sets the niceness. Linux only
+    Idle: runs only when nothing else wants the core, takes no priority. Linux only
+    Fifo: real time, runs until it blocks or something of higher priority is ready. Usually needs privileges (CAP_SYS_NICE or rtprio limits)
+    RoundRobin: real time like Fifo, but threads of equal priority take turns
+     */
+    Other,
+    Batch,
+    Idle,
+    Fifo,
+    RoundRobin
+}
+
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ThreadOptions {
+    pub name: Option<String>, // the thread is named after the node when unset
+    pub priority: Option<u8>, // 0 (lowest) to 99 (highest), mapped onto the range of the policy. A policy without a priority uses its lowest
+    pub policy: Option<SchedulingPolicy>, // None keeps the policy the thread inherited from the process
+    pub cores: Vec<usize>, // the cpu cores the thread may run on, empty leaves it free to run anywhere
+    pub best_effort: bool // a failure to apply the options is only logged and kept in the diagnostics, instead of failing finish_pipeline
+}
+impl ThreadOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+    pub fn with_policy(mut self, policy: SchedulingPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+    pub fn pinned_to(mut self, cores: &[usize]) -> Self {
+        self.cores = cores.to_vec();
+        self
+    }
+    pub fn best_effort(mut self) -> Self {
+        self.best_effort = true;
+        self
+    }
+
+    pub fn changes_scheduling(&self) -> bool { // anything beyond the name, which only a thread of the node's own can be given
+        self.priority.is_some() || self.policy.is_some() || !self.cores.is_empty()
+    }
+
+    pub fn apply_to_current(&self, node_id: &str) -> Result<(), SchedulingError> {
+        // run by the node thread on itself, the priority before the pinning so a refused priority is the error reported
+        if self.priority.is_some() || self.policy.is_some() {
+            platform::apply_priority(self.priority, self.policy).map_err(|message| SchedulingError::Priority { node_id: node_id.to_string(), message })?;
+        }
+        if !self.cores.is_empty() {
+            platform::apply_affinity(&self.cores).map_err(|message| SchedulingError::Affinity { node_id: node_id.to_string(), message })?;
+        }
+        Ok(())
+    }
+
+    pub fn thread_builder(&self, node_id: &str) -> thread::Builder {
+        thread::Builder::new().name(self.name.clone().unwrap_or_else(|| node_id.to_string()))
+    }
+}
+
+
+#[cfg(unix)]
+mod platform {
+    use thread_priority::{set_current_thread_priority, set_thread_priority_and_policy, thread_native_id, NormalThreadSchedulePolicy, RealtimeThreadSchedulePolicy, ThreadPriority, ThreadPriorityValue, ThreadSchedulePolicy};
+    use super::SchedulingPolicy;
+
+    fn os_policy(policy: SchedulingPolicy) -> Result<ThreadSchedulePolicy, String> {
+        match policy {
+            SchedulingPolicy::Other => Ok(ThreadSchedulePolicy::Normal(NormalThreadSchedulePolicy::Other)),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            SchedulingPolicy::Batch => Ok(ThreadSchedulePolicy::Normal(NormalThreadSchedulePolicy::Batch)),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            SchedulingPolicy::Idle => Ok(ThreadSchedulePolicy::Normal(NormalThreadSchedulePolicy::Idle)),
+            SchedulingPolicy::Fifo => Ok(ThreadSchedulePolicy::Realtime(RealtimeThreadSchedulePolicy::Fifo)),
+            SchedulingPolicy::RoundRobin => Ok(ThreadSchedulePolicy::Realtime(RealtimeThreadSchedulePolicy::RoundRobin)),
+            #[allow(unreachable_patterns)]
+            policy => Err(format!("the {} policy is not available on this platform", policy))
+        }
+    }
+
+    pub fn apply_priority(priority: Option<u8>, policy: Option<SchedulingPolicy>) -> Result<(), String> {
+        let priority = match priority {
+            Some(priority) => ThreadPriority::Crossplatform(ThreadPriorityValue::try_from(priority)?),
+            None => ThreadPriority::Min
+        };
+        let result = match policy {
+            Some(policy) => set_thread_priority_and_policy(thread_native_id(), priority, os_policy(policy)?),
+            None => set_current_thread_priority(priority)
+        };
+        result.map_err(|error| format!("{:?}", error))
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn apply_affinity(cores: &[usize]) -> Result<(), String> {
+        let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        for core in cores {
+            if *core >= libc::CPU_SETSIZE as usize {
+                return Err(format!("core {} is beyond the {} cores the os can address", core, libc::CPU_SETSIZE));
+            }
+            unsafe { libc::CPU_SET(*core, &mut set) };
+        }
+
+        match unsafe { libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) } { // 0 is the calling thread
+            0 => Ok(()),
+            _ => Err(format!("could not pin to cores {:?}: {}", cores, std::io::Error::last_os_error()))
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    pub fn apply_affinity(cores: &[usize]) -> Result<(), String> {
+        Err(String::from("pinning threads to cores is only supported on linux"))
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    use super::SchedulingPolicy;
+
+    pub fn apply_priority(priority: Option<u8>, policy: Option<SchedulingPolicy>) -> Result<(), String> {
+        Err(String::from("thread priorities and policies are only supported on unix"))
+    }
+    pub fn apply_affinity(cores: &[usize]) -> Result<(), String> {
+        Err(String::from("pinning threads to cores is only supported on linux"))
+    }
+}
@@ -1,4 +1,254 @@
-use rayon;
+// Thread pool execution for pipelines with many cheap nodes. Instead of every node owning an os thread which blocks on its channels,
+// the nodes become tasks on a fixed rayon pool and a dispatcher thread only hands a node to the pool once its inputs hold data and its
+// outputs have room. The state machine of each node is the same one the thread per node model runs, so run/pause/kill and the
+// thread diagnostics behave identically under both models.
+// For tests there is also a deterministic executor, which steps the same nodes in topological order on the calling thread.
 
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, sleep, JoinHandle};
+use std::time::Duration;
+use std::path::Path;
+use crossbeam::utils::Backoff;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use serde::Deserialize;
+use strum::Display;
+use super::api::*;
+use super::pipeline::CommandStatePasser;
+use super::pipeline_graph::PipelineGraph;
 
 
+#[derive(Debug, Clone, Copy, PartialEq, Default, Display, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionModel {
+    /*
+    How the nodes of a pipeline are executed, selected in PipelineParameters
+    ThreadPerNode: every node runs its own os thread, blocking on its channels. The default
+    ThreadPool: nodes are scheduled onto a fixed pool of worker threads when they are ready to run. 0 workers uses one per cpu
+    Deterministic: nothing runs until ActivePipeline::into_executor, then the caller drives the nodes with DeterministicExecutor. Links are unbounded
+     */
+    #[default]
+    ThreadPerNode,
+    ThreadPool(usize),
+    Deterministic
+}
+
+
+const DISPATCHER_IDLE_TIME: Duration = Duration::from_micros(100); // sleep between passes once spinning has not found a ready node
+
+
+pub trait ScheduledNode: Send {
+    /*
+    One node of a pipeline as seen by the pool scheduler
+    wants_call: whether the node has a state transition to make, or is running and its inputs and outputs are ready
+    call: runs one iteration of the node state machine, the same iteration the node thread loops over in the thread per node model
+    is_finished: the node was killed and will never be called again
+     */
+    fn wants_call(&self) -> bool;
+    fn call(&mut self);
+    fn is_finished(&self) -> bool;
+    fn get_id(&self) -> String;
+    fn graph_index(&self) -> usize;
+}
+
+
+struct PooledNode {
+    node: Mutex<Box<dyn ScheduledNode>>, // only locked by the dispatcher while idle, or by the one worker running it
+    scheduled: AtomicBool,
+    finished: AtomicBool,
+}
+
+
+pub struct PoolScheduler {
+    dispatcher_handle: Option<JoinHandle<()>>,
+}
+impl PoolScheduler {
+    pub fn spawn(nodes: Vec<Box<dyn ScheduledNode>>, workers: usize, requested_state: Arc<AtomicU8>, unchanged_state_time: u64) -> Self {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(workers)
+            .thread_name(|index| format!("pipeline-worker-{}", index))
+            .build()
+            .expect("Failed to build the pipeline worker pool");
+
+        let nodes: Vec<Arc<PooledNode>> = nodes.into_iter()
+            .map(|node| Arc::new(PooledNode { node: Mutex::new(node), scheduled: AtomicBool::new(false), finished: AtomicBool::new(false) }))
+            .collect();
+
+        log_message(format!("PoolScheduler: scheduling {} nodes on {} workers", nodes.len(), pool.current_num_threads()), Level::Info);
+
+        let dispatcher_handle = thread::Builder::new()
+            .name("pipeline-dispatcher".to_string())
+            .spawn(move || Self::dispatch(pool, nodes, requested_state, unchanged_state_time))
+            .expect("Failed to spawn the pipeline dispatcher thread");
+
+        Self { dispatcher_handle: Some(dispatcher_handle) }
+    }
+
+    fn dispatch(pool: ThreadPool, nodes: Vec<Arc<PooledNode>>, requested_state: Arc<AtomicU8>, unchanged_state_time: u64) {
+        let backoff = Backoff::new();
+
+        while !nodes.iter().all(|node| node.finished.load(Ordering::Acquire)) {
+            let mut dispatched = false;
+
+            for pooled in nodes.iter() {
+                if pooled.scheduled.load(Ordering::Acquire) || pooled.finished.load(Ordering::Acquire) {
+                    continue;
+                }
+                if !pooled.node.lock().unwrap().wants_call() {
+                    continue;
+                }
+
+                pooled.scheduled.store(true, Ordering::Release);
+                dispatched = true;
+
+                let pooled = pooled.clone();
+                pool.spawn(move || {
+                    let mut node = pooled.node.lock().unwrap();
+                    node.call();
+                    pooled.finished.store(node.is_finished(), Ordering::Release);
+                    pooled.scheduled.store(false, Ordering::Release);
+                });
+            }
+
+            if dispatched { backoff.reset() }
+            else if requested_state.load(Ordering::Acquire) == ThreadStateSpace::PAUSED as u8 { sleep(Duration::from_millis(unchanged_state_time)) }
+            else if backoff.is_completed() { sleep(DISPATCHER_IDLE_TIME) }
+            else { backoff.snooze() }
+        }
+
+        log_message(format!("PoolScheduler: every node killed, dispatcher exiting"), Level::Info);
+    } // the pool is dropped here, its workers exit once the last node call returns
+
+    pub fn join(mut self) {
+        match self.dispatcher_handle.take() {
+            None => panic!("pool scheduler already joined"),
+            Some(handle) => {
+                handle.join().unwrap();
+                log_message(format!("PoolScheduler: Joined dispatcher thread"), Level::Info);
+            }
+        }
+    }
+}
+
+
+pub struct DeterministicExecutor {
+    /*
+    Runs a pipeline on the calling thread with no timing involved, so tests can feed a fixed input and compare the output exactly.
+    Every run round calls each source once and then settles the graph: the other nodes are called in topological order for as long
+    as they have input, with passes repeated until nothing is left to do so values travelling around feedback loops are picked up too.
+    State changes requested by the nodes themselves (pause or kill on errors) are applied right after the call which made them
+     */
+    nodes: Vec<Box<dyn ScheduledNode>>, // in topological order, feedback edges ignored
+    sources: usize, // the first nodes of the order have no inputs
+    threads: Vec<PipelineThread>, // kept for the thread diagnostics
+    state_passer: CommandStatePasser,
+    graph: PipelineGraph,
+}
+impl DeterministicExecutor {
+    pub fn new(mut nodes: Vec<Box<dyn ScheduledNode>>, threads: Vec<PipelineThread>, mut state_passer: CommandStatePasser, graph: PipelineGraph) -> Self {
+        // sources go first, which keeps the order topological since nothing flows into them
+        let order = Self::topological_order(&graph);
+        nodes.sort_by_key(|node| (!graph.predecessors(node.graph_index()).is_empty(), order.iter().position(|index| *index == node.graph_index()).unwrap_or(usize::MAX)));
+        let sources = nodes.iter().take_while(|node| graph.predecessors(node.graph_index()).is_empty()).count();
+
+        state_passer.broadcast_requested_state(ThreadStateSpace::RUNNING);
+        Self { nodes, sources, threads, state_passer, graph }
+    }
+
+    fn topological_order(graph: &PipelineGraph) -> Vec<usize> {
+        // kahn's algorithm, always taking the lowest ready index so the order only depends on how the pipeline was built
+        let edges: Vec<(usize, usize)> = graph.edges().into_iter()
+            .filter(|edge| edge.kind != GraphEdgeKind::Feedback)
+            .map(|edge| (edge.from, edge.to))
+            .collect();
+        let mut remaining: Vec<usize> = graph.nodes().into_iter().map(|node| node.index).collect();
+        let mut order = Vec::with_capacity(remaining.len());
+
+        while !remaining.is_empty() {
+            let next = remaining.iter().position(|index| !edges.iter().any(|(from, to)| to == index && remaining.contains(from)))
+                .unwrap_or(0); // a cycle without a feedback edge cannot be built, but never loop forever on one
+            order.push(remaining.remove(next));
+        }
+        order
+    }
+
+    fn call_node(&mut self, position: usize) -> bool {
+        if !self.nodes[position].wants_call() { return false }
+
+        self.nodes[position].call();
+        self.state_passer.apply_node_requests();
+        true
+    }
+
+    pub fn settle(&mut self) -> usize { // calls every non source node until none has input left, returns how many calls were made
+        let mut calls = 0;
+        let mut progressed = true;
+
+        while progressed {
+            progressed = false;
+            for position in self.sources..self.nodes.len() {
+                while self.call_node(position) {
+                    calls += 1;
+                    progressed = true;
+                }
+            }
+        }
+        calls
+    }
+
+    pub fn run(&mut self, rounds: usize) {
+        for _ in 0..rounds {
+            for position in 0..self.sources {
+                self.call_node(position);
+            }
+            self.settle();
+        }
+    }
+
+    pub fn drain(&mut self) { // ends the stream at every source and settles, so every step is flushed and every sink has seen the end of stream
+        self.state_passer.request_drain();
+        self.run(1);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state_passer.get_requested_state() == ThreadStateSpace::RUNNING
+    }
+    pub fn send_control(&self, node_id: &str, message: ControlMessage) -> Result<ControlTicket, ControlError> { // handled on the next run or settle
+        match self.threads.iter().find(|thread| thread.id == node_id) {
+            Some(thread) => thread.send_control(message),
+            None => Err(ControlError::UnknownNode { node_id: node_id.to_string() })
+        }
+    }
+    pub fn swap_step<I: Sharable, O: Sharable>(&self, node_id: &str, step: impl PipelineStep<I, O>) -> Result<SwapTicket, SwapError> { // taken on the next run or settle
+        match self.threads.iter().find(|thread| thread.id == node_id) {
+            Some(thread) => thread.swap_step(Box::new(step)),
+            None => Err(SwapError::UnknownNode { node_id: node_id.to_string() })
+        }
+    }
+    pub fn tap<T: Sharable>(&self, node_id: &str, options: TapOptions) -> Result<TapHandle<ODFormat<T>>, TapError> {
+        match self.threads.iter().find(|thread| thread.id == node_id) {
+            Some(thread) => thread.tap(options),
+            None => Err(TapError::UnknownNode { node_id: node_id.to_string() })
+        }
+    }
+    pub fn record_inputs<T: Recordable>(&self, node_id: &str, path: impl AsRef<Path>) -> Result<InputRecorder, RecordingError> {
+        match self.threads.iter().find(|thread| thread.id == node_id) {
+            Some(thread) => thread.record_inputs::<T>(path.as_ref()),
+            None => Err(RecordingError::Tap(TapError::UnknownNode { node_id: node_id.to_string() }))
+        }
+    }
+    pub fn get_thread_diagnostics(&self) -> Vec<ThreadDiagnostic> {
+        self.threads.iter().map(ThreadDiagnostic::new).collect()
+    }
+    pub fn get_graph(&self) -> &PipelineGraph {
+        &self.graph
+    }
+
+    pub fn kill(mut self) { // gives every node its kill behavior, there are no threads to join
+        self.state_passer.broadcast_requested_state(ThreadStateSpace::KILLED);
+        for position in 0..self.nodes.len() {
+            self.call_node(position);
+        }
+        log_message(format!("DeterministicExecutor: killed {} nodes", self.nodes.len()), Level::Info);
+    }
+}
@@ -0,0 +1,201 @@
+// Chrome trace export of the tracing spans, so the time of a running pipeline can be looked at in chrome://tracing or Perfetto without
+// any external service. Every work call of a node is a node_call span (node id, receive shape, items, samples and outcome) with child
+// spans for its receive, compute and send phases, so the waits on the links show up next to the work itself.
+// The spans are trace level and cost next to nothing until a subscriber collects them. ChromeTraceLayer is a tracing_subscriber layer
+// which writes every span as a complete event when it is exited, named after its node when it has one, on a track per thread.
+//
+// The file is the json array format of the trace event format, written as the spans close. ChromeTraceGuard closes the array when it
+// is finished or dropped, a trace cut short by a crash is still accepted by the viewers, which do not need the closing bracket.
+
+use std::collections::HashSet;
+use std::cell::Cell;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+use serde_json::{json, Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+thread_local! {
+    static THREAD: Cell<u64> = const { Cell::new(0) }; // the track of the thread in the trace, assigned on its first span
+}
+
+fn thread_track() -> u64 {
+    THREAD.with(|track| {
+        if track.get() == 0 {
+            track.set(NEXT_THREAD.fetch_add(1, Ordering::Relaxed));
+        }
+        track.get()
+    })
+}
+
+
+struct FieldVisitor<'a>(&'a mut Map<String, Value>);
+impl Visit for FieldVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) { self.0.insert(field.name().to_string(), json!(value)); }
+    fn record_i64(&mut self, field: &Field, value: i64) { self.0.insert(field.name().to_string(), json!(value)); }
+    fn record_u64(&mut self, field: &Field, value: u64) { self.0.insert(field.name().to_string(), json!(value)); }
+    fn record_bool(&mut self, field: &Field, value: bool) { self.0.insert(field.name().to_string(), json!(value)); }
+    fn record_str(&mut self, field: &Field, value: &str) { self.0.insert(field.name().to_string(), json!(value)); }
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().to_string(), json!(format!("{:?}", value)));
+    }
+}
+
+
+struct SpanTiming { // kept in the extensions of every span while it exists
+    fields: Map<String, Value>,
+    entered: Vec<Instant> // a stack, a span can be entered again before it was exited
+}
+
+
+struct TraceWriter {
+    out: Box<dyn Write + Send>,
+    events: usize,
+    named_threads: HashSet<u64>,
+    finished: bool
+}
+impl TraceWriter {
+    fn write_event(&mut self, event: &Value) {
+        if self.finished { return }
+        let separator = if self.events == 0 { "[\n" } else { ",\n" };
+        if write!(self.out, "{}{}", separator, event).is_ok() {
+            self.events += 1;
+        }
+    }
+    fn name_thread(&mut self, track: u64) { // a metadata event before the first span of each thread, so its track has a name
+        if self.named_threads.insert(track) {
+            let name = thread::current().name().unwrap_or("unnamed").to_string();
+            self.write_event(&json!({ "name": "thread_name", "ph": "M", "pid": std::process::id(), "tid": track, "args": { "name": name } }));
+        }
+    }
+    fn finish(&mut self) -> io::Result<()> {
+        if self.finished { return Ok(()) }
+        self.finished = true;
+        if self.events == 0 {
+            write!(self.out, "[")?;
+        }
+        write!(self.out, "\n]\n")?;
+        self.out.flush()
+    }
+}
+
+
+#[derive(Clone)]
+pub struct ChromeTraceLayer {
+    writer: Arc<Mutex<TraceWriter>>,
+    start: Instant // timestamps are microseconds since the layer was made
+}
+impl ChromeTraceLayer {
+    pub fn to_file(path: impl AsRef<Path>) -> io::Result<(Self, ChromeTraceGuard)> {
+        Ok(Self::to_writer(BufWriter::new(File::create(path)?)))
+    }
+    pub fn to_writer(out: impl Write + Send + 'static) -> (Self, ChromeTraceGuard) {
+        let writer = Arc::new(Mutex::new(TraceWriter { out: Box::new(out), events: 0, named_threads: HashSet::new(), finished: false }));
+        (Self { writer: writer.clone(), start: Instant::now() }, ChromeTraceGuard { writer })
+    }
+
+    fn timestamp(&self, instant: Instant) -> f64 {
+        instant.saturating_duration_since(self.start).as_nanos() as f64 / 1000.0
+    }
+    fn write(&self, event: Value) {
+        let track = thread_track();
+        let mut writer = self.writer.lock().unwrap();
+        writer.name_thread(track);
+        writer.write_event(&event);
+    }
+}
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for ChromeTraceLayer {
+    fn on_new_span(&self, attributes: &Attributes<'_>, id: &Id, context: Context<'_, S>) {
+        let Some(span) = context.span(id) else { return };
+        let mut fields = Map::new();
+        attributes.record(&mut FieldVisitor(&mut fields));
+        span.extensions_mut().insert(SpanTiming { fields, entered: Vec::new() });
+    }
+    fn on_record(&self, id: &Id, values: &Record<'_>, context: Context<'_, S>) {
+        let Some(span) = context.span(id) else { return };
+        let mut extensions = span.extensions_mut();
+        if let Some(timing) = extensions.get_mut::<SpanTiming>() {
+            values.record(&mut FieldVisitor(&mut timing.fields));
+        }
+    }
+    fn on_enter(&self, id: &Id, context: Context<'_, S>) {
+        let Some(span) = context.span(id) else { return };
+        let mut extensions = span.extensions_mut();
+        if let Some(timing) = extensions.get_mut::<SpanTiming>() {
+            timing.entered.push(Instant::now());
+        }
+    }
+    fn on_exit(&self, id: &Id, context: Context<'_, S>) {
+        let exited = Instant::now();
+        let Some(span) = context.span(id) else { return };
+        let event = {
+            let mut extensions = span.extensions_mut();
+            let Some(timing) = extensions.get_mut::<SpanTiming>() else { return };
+            let Some(entered) = timing.entered.pop() else { return };
+
+            let name = timing.fields.get("node").and_then(Value::as_str).unwrap_or(span.name()).to_string();
+            json!({
+                "name": name,
+                "cat": span.name(),
+                "ph": "X",
+                "ts": self.timestamp(entered),
+                "dur": self.timestamp(exited) - self.timestamp(entered),
+                "pid": std::process::id(),
+                "tid": thread_track(),
+                "args": timing.fields
+            })
+        };
+        self.write(event);
+    }
+    fn on_event(&self, event: &Event<'_>, _context: Context<'_, S>) {
+        let mut fields = Map::new();
+        event.record(&mut FieldVisitor(&mut fields));
+        let name = fields.remove("message").and_then(|message| message.as_str().map(str::to_string)).unwrap_or_else(|| event.metadata().name().to_string());
+
+        self.write(json!({
+            "name": name,
+            "cat": event.metadata().target(),
+            "ph": "i",
+            "s": "t",
+            "ts": self.timestamp(Instant::now()),
+            "pid": std::process::id(),
+            "tid": thread_track(),
+            "args": fields
+        }));
+    }
+}
+
+
+pub struct ChromeTraceGuard {
+    writer: Arc<Mutex<TraceWriter>>
+}
+impl ChromeTraceGuard {
+    pub fn finish(self) -> io::Result<()> { // closes the trace, spans which end afterwards are no longer written
+        self.writer.lock().unwrap().finish()
+    }
+}
+impl Drop for ChromeTraceGuard {
+    fn drop(&mut self) {
+        let _ = self.writer.lock().unwrap().finish();
+    }
+}
+
+
+pub fn install_chrome_trace(path: impl AsRef<Path>) -> io::Result<ChromeTraceGuard> {
+    // makes the trace the global subscriber, for a whole program run. Fails if a global subscriber was already set
+    let (layer, guard) = ChromeTraceLayer::to_file(path)?;
+    tracing::subscriber::set_global_default(tracing_subscriber::registry().with(layer))
+        .map_err(|error| io::Error::new(io::ErrorKind::AlreadyExists, error))?;
+    Ok(guard)
+}
@@ -1,13 +1,42 @@
+use std::any::type_name;
 use std::fmt::Debug;
 use std::sync::mpmc::{RecvError, RecvTimeoutError, SendError};
 use std::sync::mpsc::{Receiver, SyncSender};
+use super::byte_codec::{ByteReader, ByteWriter};
 
 
 pub trait HasDefault {
     fn default() -> Self;
 }
 
-pub trait Sharable = Send + Sync + Debug + Clone + HasDefault + 'static;
+pub trait HasSampleCount { // how many samples a payload carries, for the samples per second node metrics
+    fn sample_count(&self) -> usize { 1 }
+}
+
+pub trait HasEncoding { // how checkpoints, recordings and remote taps save a value. Without one, only saving a value of the type fails
+    fn write_to(&self, _out: &mut ByteWriter) -> Result<(), String> {
+        Err(format!("{} is not encodable", type_name::<Self>()))
+    }
+    fn read_from(_reader: &mut ByteReader) -> Result<Self, String> where Self: Sized {
+        Err(format!("{} is not encodable", type_name::<Self>()))
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>, String> { // the value on its own, as held in a byte block
+        let mut out = ByteWriter::new();
+        self.write_to(&mut out)?;
+        Ok(out.into_bytes())
+    }
+    fn from_bytes(bytes: &[u8]) -> Result<Self, String> where Self: Sized {
+        let mut reader = ByteReader::new(bytes);
+        let value = Self::read_from(&mut reader)?;
+        match reader.at_end() {
+            true => Ok(value),
+            false => Err(String::from("unexpected data after the value"))
+        }
+    }
+}
+
+pub trait Sharable = Send + Sync + Debug + Clone + HasDefault + HasSampleCount + HasEncoding + 'static;
 
 pub trait Source {}
 pub trait Sink {}
@@ -30,6 +30,7 @@ pub struct AudioFileSource {
     read_retries: usize,
     pub buffer: Vec<f32>,
     eof_flag: bool,
+    decoded_samples: u64, // every sample taken from the decoder, the read position a checkpoint restores
 }
 
 impl AudioFileSource {
@@ -62,7 +63,7 @@ impl AudioFileSource {
         let mut decoder =
             symphonia::default::get_codecs().make(&track.codec_params, &decoder_opts).unwrap();
 
-        Self { buffer_size, decoder, formatter: format, buffer: Vec::new(), read_retries, eof_flag: false }
+        Self { buffer_size, decoder, formatter: format, buffer: Vec::new(), read_retries, eof_flag: false, decoded_samples: 0 }
     }
     fn extract_packet(&mut self) -> AudioReadResult {
         let mut internal_buffer = None;
@@ -85,6 +86,7 @@ impl AudioFileSource {
                 }
                 
                 let vectorized_buffer = internal_buffer.unwrap().samples().to_vec();
+                self.decoded_samples += vectorized_buffer.len() as u64;
                 
                 AudioReadResult::Ok(vectorized_buffer)
             }
@@ -93,11 +95,13 @@ impl AudioFileSource {
     }
 }
 impl PipelineStep<(), Vec<f32>> for AudioFileSource {
-    fn run_DISO(&mut self) -> Result<ODFormat<Vec<f32>>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+    fn run_DISO(&mut self) -> Result<ODFormat<Vec<f32>>, StepError> {
         let mut error_count = 0;
         
         if self.eof_flag {
-            return Err("EOF reached".to_string());
+            return Err(StepError::end_of_stream());
         }
         while self.buffer.len() < self.buffer_size && error_count < self.read_retries {
             match self.extract_packet() {
@@ -110,7 +114,7 @@ impl PipelineStep<(), Vec<f32>> for AudioFileSource {
             }
         }
         if error_count > self.read_retries {
-            Err("Error in reading from audio file".to_string())
+            Err(StepError::needs_reset("Error in reading from audio file"))
         }
         else if self.buffer.len() >= self.buffer_size {
             let to_return = self.buffer.drain(0..self.buffer_size).collect();
@@ -125,5 +129,33 @@ impl PipelineStep<(), Vec<f32>> for AudioFileSource {
             Ok(ODFormat::Standard(to_return))
         }
     }
+
+    fn checkpointable(&mut self) -> Option<&mut dyn Checkpointable> { Some(self) }
+}
+impl Checkpointable for AudioFileSource {
+    fn save_state(&self, state: &mut ByteWriter) {
+        state.put_u64(self.decoded_samples);
+        state.put_bool(self.eof_flag);
+        state.put_f32s(&self.buffer);
+    }
+    fn restore_state(&mut self, state: &mut ByteReader) -> Result<(), String> {
+        // the decoder only reads forward, so the packets up to the saved position are decoded again and dropped. Restoring into a
+        // source which already read past the position is rejected, a file shorter than the position leaves the source at its end
+        let (decoded_samples, eof_flag, buffer) = (state.u64()?, state.bool()?, state.f32s()?);
+        if self.decoded_samples > decoded_samples {
+            return Err(format!("the source already read {} samples, past the {} of the checkpoint", self.decoded_samples, decoded_samples));
+        }
+        while self.decoded_samples < decoded_samples {
+            if let EOF = self.extract_packet() {
+                return Err(format!("the file ends after {} samples, before the {} of the checkpoint", self.decoded_samples, decoded_samples));
+            }
+        }
+        if self.decoded_samples != decoded_samples {
+            return Err(format!("the packets of the file do not end at sample {} of the checkpoint", decoded_samples));
+        }
+        self.eof_flag = eof_flag;
+        self.buffer = buffer;
+        Ok(())
+    }
 }
 impl Source for AudioFileSource {}
\ No newline at end of file
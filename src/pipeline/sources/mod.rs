@@ -3,4 +3,5 @@ mod live_audio_source;
 mod socket_source;
 pub mod audio_file_source;
 mod tests;
-mod sinusoid;
\ No newline at end of file
+pub mod sinusoid;
+pub mod vector_source;
\ No newline at end of file
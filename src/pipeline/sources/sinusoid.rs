@@ -1,6 +1,11 @@
 use std::f32::consts::PI;
 use crate::pipeline::api::ODFormat;
-use crate::pipeline::pipeline_step::PipelineStep;
+use crate::pipeline::pipeline_step::{PipelineStep, StepHandler};
+use crate::pipeline::pipeline_traits::Source;
+use crate::pipeline::pipeline_errors::StepError;
+use crate::pipeline::pipeline_control::ControlMessage;
+use crate::pipeline::byte_codec::{ByteReader, ByteWriter};
+use crate::pipeline::pipeline_checkpoint::Checkpointable;
 
 pub struct SinusoidalSource {
     frequency: f32,
@@ -29,7 +34,9 @@ impl SinusoidalSource {
     }
 }
 impl PipelineStep<(), Vec<f32>> for SinusoidalSource {
-    fn run_DISO(&mut self) -> Result<ODFormat<Vec<f32>>, String> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+    fn run_DISO(&mut self) -> Result<ODFormat<Vec<f32>>, StepError> {
         let mut buffer = Vec::with_capacity(self.buff_size);
         
         let mut time = self.increment_time(self.previous_time);
@@ -43,4 +50,36 @@ impl PipelineStep<(), Vec<f32>> for SinusoidalSource {
         
         Ok(ODFormat::Standard(buffer))
     }
-}
\ No newline at end of file
+
+    fn handle_control(&mut self, message: &ControlMessage) -> Result<(), String> {
+        match message.key.as_str() {
+            "frequency" => {
+                let frequency = message.get_f32()?;
+                if frequency < 0.0 || frequency > self.sampling_frequency / 2.0 {
+                    return Err(format!("frequency {} is outside 0 to {} (half the sampling frequency)", frequency, self.sampling_frequency / 2.0));
+                }
+                self.frequency = frequency;
+                Ok(())
+            },
+            "phase" => { self.phase = message.get_f32()?; Ok(()) },
+            _ => Err(format!("step has no control '{}'", message.key))
+        }
+    }
+
+    fn checkpointable(&mut self) -> Option<&mut dyn Checkpointable> { Some(self) }
+}
+impl Checkpointable for SinusoidalSource {
+    fn save_state(&self, state: &mut ByteWriter) { // frequency and phase may have been changed by controls since the step was built
+        state.put_f32(self.previous_time);
+        state.put_f32(self.frequency);
+        state.put_f32(self.phase);
+    }
+    fn restore_state(&mut self, state: &mut ByteReader) -> Result<(), String> {
+        let (previous_time, frequency, phase) = (state.f32()?, state.f32()?, state.f32()?);
+        self.previous_time = previous_time;
+        self.frequency = frequency;
+        self.phase = phase;
+        Ok(())
+    }
+}
+impl Source for SinusoidalSource {}
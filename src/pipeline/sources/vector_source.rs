@@ -0,0 +1,28 @@
+use std::collections::VecDeque;
+use crate::pipeline::api::ODFormat;
+use crate::pipeline::pipeline_step::{PipelineStep, StepHandler};
+use crate::pipeline::pipeline_traits::{Sharable, Source};
+use crate::pipeline::pipeline_errors::StepError;
+
+pub struct VectorSource<T: Sharable> { // emits a fixed list of values in order, mostly for driving pipelines with the deterministic executor
+    values: VecDeque<T>
+}
+impl<T: Sharable> VectorSource<T> {
+    pub fn new(values: Vec<T>) -> Self {
+        Self { values: values.into() }
+    }
+    pub fn remaining(&self) -> usize {
+        self.values.len()
+    }
+}
+impl<T: Sharable> PipelineStep<(), T> for VectorSource<T> {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+    fn run_DISO(&mut self) -> Result<ODFormat<T>, StepError> {
+        match self.values.pop_front() {
+            Some(value) => Ok(ODFormat::Standard(value)),
+            None => Err(StepError::end_of_stream())
+        }
+    }
+}
+impl<T: Sharable> Source for VectorSource<T> {}
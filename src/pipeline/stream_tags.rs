@@ -0,0 +1,99 @@
+// Stream tags carry metadata alongside the samples, like the sample rate, the center frequency, a timestamp or the start of a frame.
+// Every value sent over a link has its own (usually empty) list of tags, each pointing at a sample within that value by its offset.
+// Nodes forward the tags of their input with their output, so a step only has to deal with them if it reads or adds tags,
+// or if it changes the sample rate (see PipelineStep::tag_rate).
+
+use super::pipeline_traits::HasSampleCount;
+
+
+pub mod tag_keys { // keys for the metadata most steps will care about, any other key works just the same
+    pub const SAMPLE_RATE: &str = "sample_rate";
+    pub const CENTER_FREQUENCY: &str = "center_frequency";
+    pub const TIMESTAMP: &str = "timestamp";
+    pub const START_OF_FRAME: &str = "start_of_frame";
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagValue {
+    Flag, // the tag itself is the information, eg a start of frame marker
+    Integer(i64),
+    Float(f64),
+    Text(String)
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamTag {
+    pub key: String,
+    pub offset: usize, // sample within the value the tag travels with, counted the same way as HasSampleCount
+    pub value: TagValue
+}
+impl StreamTag {
+    pub fn new(key: &str, offset: usize, value: TagValue) -> Self {
+        Self { key: key.to_string(), offset, value }
+    }
+    pub fn flag(key: &str, offset: usize) -> Self {
+        Self::new(key, offset, TagValue::Flag)
+    }
+}
+
+
+pub fn find_tag<'a>(tags: &'a [StreamTag], key: &str) -> Option<&'a StreamTag> {
+    tags.iter().find(|tag| tag.key == key)
+}
+
+
+pub fn rescale_tags(tags: &mut Vec<StreamTag>, (interpolation, decimation): (usize, usize)) {
+    // moves the offsets to where their samples ended up after a rate change of interpolation / decimation
+    if interpolation == decimation { return }
+    for tag in tags.iter_mut() {
+        tag.offset = tag.offset * interpolation / decimation;
+    }
+}
+
+
+pub fn join_series_tags<T: HasSampleCount>(values: &[T], tags: Vec<Vec<StreamTag>>) -> Vec<StreamTag> {
+    // the tags of a reassembled series, with their offsets counted from the start of the first value
+    let mut start = 0;
+    let mut joined = Vec::new();
+
+    for (value, value_tags) in values.iter().zip(tags) {
+        joined.extend(value_tags.into_iter().map(|tag| StreamTag { offset: tag.offset + start, ..tag }));
+        start += value.sample_count();
+    }
+    joined
+}
+
+
+pub fn merge_parallel_tags(tags: Vec<Vec<StreamTag>>) -> Vec<StreamTag> {
+    // the tags of values received side by side by a joint. A tag which went down several branches of a split is only kept once
+    let mut merged: Vec<StreamTag> = Vec::new();
+
+    for tag in tags.into_iter().flatten() {
+        if !merged.contains(&tag) {
+            merged.push(tag);
+        }
+    }
+    merged
+}
+
+
+pub fn split_series_tags<T: HasSampleCount>(values: &[T], tags: Vec<StreamTag>) -> Vec<Vec<StreamTag>> {
+    // hands every tag to the value of a series its offset falls into, counted from the start of that value.
+    // Tags past the end stay with the last value, so they are not lost
+    let mut starts = Vec::with_capacity(values.len());
+    let mut start = 0;
+    for value in values.iter() {
+        starts.push(start);
+        start += value.sample_count();
+    }
+
+    let mut split = vec![Vec::new(); values.len()];
+    for tag in tags {
+        if let Some(index) = starts.iter().rposition(|start| *start <= tag.offset) {
+            split[index].push(StreamTag { offset: tag.offset - starts[index], ..tag });
+        }
+    }
+    split
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use std::thread::sleep;
+    use std::time::{Duration, Instant};
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+
+
+    struct Dummy1 {
+        receiver: mpsc::Receiver<u32>
+    }
+    impl PipelineStep<(), u32> for Dummy1 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
+            match self.receiver.recv_timeout(Duration::from_millis(200)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for Dummy1 {}
+
+    struct GatedSink { // reports every value, then holds on to it until the test releases it, like a consumer which fell behind
+        sender: mpsc::Sender<u32>,
+        release: mpsc::Receiver<()>
+    }
+    impl PipelineStep<u32, ()> for GatedSink {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            let _ = self.release.recv_timeout(Duration::from_secs(5));
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for GatedSink {}
+
+    fn source_dropped(pipeline: &ActivePipeline) -> usize {
+        pipeline.get_thread_diagnostics().into_iter().find(|diagnostic| diagnostic.id == "source").unwrap().dropped
+    }
+
+    fn run_behind_consumer(backend: ChannelBackend, policy: BackpressurePolicy) -> (Vec<u32>, usize) {
+        // the sink takes 1 and stalls, 2 to 6 then arrive at a link with room for 2
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100).with_channel_backend(backend);
+        let (input_sender, input_receiver) = mpsc::sync_channel(0);
+        let (output_sender, output_receiver) = mpsc::channel();
+        let (release_sender, release_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .backpressure(policy)
+            .cap_pipeline("sink", GatedSink { sender: output_sender, release: release_receiver });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        input_sender.send(1).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), 1);
+        for value in 2..7 {
+            input_sender.send(value).unwrap();
+        }
+
+        let start = Instant::now();
+        while source_dropped(&pipeline) < 3 && start.elapsed() < Duration::from_secs(5) {
+            sleep(Duration::from_millis(10));
+        }
+        let dropped = source_dropped(&pipeline);
+
+        for _ in 0..3 {
+            release_sender.send(()).unwrap();
+        }
+        let received = (0..2).map(|_| output_receiver.recv_timeout(Duration::from_secs(5)).unwrap()).collect();
+        pipeline.kill();
+        (received, dropped)
+    }
+
+    #[test]
+    fn test_drop_newest() {
+        initialize_logger();
+        assert_eq!(run_behind_consumer(ChannelBackend::Std, BackpressurePolicy::DropNewest), (vec![2, 3], 3));
+    }
+
+    #[test]
+    fn test_drop_oldest() {
+        initialize_logger();
+        assert_eq!(run_behind_consumer(ChannelBackend::Std, BackpressurePolicy::DropOldest), (vec![5, 6], 3));
+        assert_eq!(run_behind_consumer(ChannelBackend::SpscRing, BackpressurePolicy::DropOldest), (vec![5, 6], 3));
+    }
+
+    #[test]
+    fn test_block_then_drop() {
+        initialize_logger();
+        assert_eq!(run_behind_consumer(ChannelBackend::Crossbeam, BackpressurePolicy::BlockThenDrop(20)), (vec![2, 3], 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "rendezvous link")]
+    fn test_drop_rejected_on_rendezvous_link() {
+        let pipeline = ConstructingPipeline::new(3, 1000, 0, 2, 0, 100);
+        let (_input_sender, input_receiver) = mpsc::sync_channel(0);
+        let (output_sender, _output_receiver) = mpsc::channel();
+        let (_release_sender, release_receiver) = mpsc::channel();
+
+        // a full rendezvous link has nothing queued to evict, the sender would spin until the receiver came by
+        NodeBuilder::start_pipeline("source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .backpressure(BackpressurePolicy::DropOldest)
+            .cap_pipeline("sink", GatedSink { sender: output_sender, release: release_receiver });
+    }
+
+    #[test]
+    #[should_panic(expected = "reassembler")]
+    fn test_drop_rejected_into_reassembler() {
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100);
+        let (_input_sender, input_receiver) = mpsc::sync_channel(0);
+
+        // dropping single values would shift every series after the first drop
+        NodeBuilder::start_pipeline::<u32>("source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .backpressure(BackpressurePolicy::DropNewest)
+            .add_reassembler(3);
+    }
+}
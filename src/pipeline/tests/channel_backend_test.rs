@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+    use crate::pipeline::sources::sinusoid::SinusoidalSource;
+    use crate::dsp::casting::{ComplexCaster, RealCaster};
+    extern crate test;
+
+
+    struct Dummy1 {
+        receiver: mpsc::Receiver<u32>
+    }
+    impl PipelineStep<(), u32> for Dummy1 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
+            match self.receiver.recv_timeout(std::time::Duration::from_millis(2000)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for Dummy1 {}
+
+    struct Dummy2{}
+    impl PipelineStep<u32, u32> for Dummy2 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO, StepHandler::MISO, StepHandler::SIMO, StepHandler::REASO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Series(vec![input, input + 1]))
+        }
+
+        fn run_MISO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input.iter().sum()))
+        }
+
+        fn run_SIMO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + 1))
+        }
+
+        fn run_REASO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input.iter().sum()))
+        }
+    }
+
+    struct Dummy3<T: Sharable> {
+        sender: mpsc::Sender<T>,
+    }
+    impl<T: Sharable> PipelineStep<T, ()> for Dummy3<T> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: T) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl<T: Sharable> Sink for Dummy3<T> {}
+
+    fn run_split_pipeline(backend: ChannelBackend) {
+        // source -> split -> [series branch, empty branch] -> joint -> sink
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100).with_channel_backend(backend);
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        let mut split = NodeBuilder::start_pipeline("test_source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .split_begin("test_split");
+        let mut joint = joint_begin("test_joint", &pipeline);
+
+        split.split_add().attach("series node", Dummy2 {}).add_reassembler(2).attach("sum node", Dummy2 {}).branch_end(&mut joint);
+        split.split_add().branch_end(&mut joint);
+        split.split_lock(Dummy2 {});
+
+        joint.joint_lock(Dummy2 {})
+            .cap_pipeline("test_sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        for value in 0..20 {
+            input_sender.send(value).unwrap();
+            // split gives x + 1, the series branch sums (x + 1) and (x + 2) back up, the empty branch passes x + 1
+            assert_eq!(output_receiver.recv().unwrap(), 3 * value + 4, "backend {}", backend);
+        }
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_std_backend() {
+        initialize_logger();
+        run_split_pipeline(ChannelBackend::Std);
+    }
+
+    #[test]
+    fn test_crossbeam_backend() {
+        initialize_logger();
+        run_split_pipeline(ChannelBackend::Crossbeam);
+    }
+
+    #[test]
+    fn test_spsc_ring_backend() {
+        initialize_logger();
+        run_split_pipeline(ChannelBackend::SpscRing);
+    }
+
+    fn bench_sinusoid_chain(b: &mut test::Bencher, backend: ChannelBackend) {
+        // SinusoidalSource -> ComplexCaster -> RealCaster -> sink, timed per 100 buffers arriving at the sink
+        let pipeline = ConstructingPipeline::new(3, 1000, 4, 2, 3, 100).with_channel_backend(backend);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("sinusoid", SinusoidalSource::new(1000.0, 48000.0, 0.0, 256), &pipeline)
+            .attach("complex cast", ComplexCaster {})
+            .attach("real cast", RealCaster {})
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        b.iter(|| {
+            for _ in 0..100 {
+                test::black_box(output_receiver.recv().unwrap());
+            }
+        });
+
+        pipeline.kill();
+    }
+
+    #[bench]
+    fn bench_std_backend(b: &mut test::Bencher) {
+        bench_sinusoid_chain(b, ChannelBackend::Std);
+    }
+
+    #[bench]
+    fn bench_crossbeam_backend(b: &mut test::Bencher) {
+        bench_sinusoid_chain(b, ChannelBackend::Crossbeam);
+    }
+
+    #[bench]
+    fn bench_spsc_ring_backend(b: &mut test::Bencher) {
+        bench_sinusoid_chain(b, ChannelBackend::SpscRing);
+    }
+}
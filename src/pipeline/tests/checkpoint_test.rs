@@ -0,0 +1,376 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use std::thread::sleep;
+    use std::time::{Duration, Instant};
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+    use crate::pipeline::sources::sinusoid::SinusoidalSource;
+    use crate::dsp::filtering::iir::shared::filter::{IIRFilterRunner, ZDomainCoefficients};
+
+
+    struct Feed<T: ValidFloat> {
+        receiver: mpsc::Receiver<Vec<T>>
+    }
+    impl<T: ValidFloat> PipelineStep<(), Vec<T>> for Feed<T> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<Vec<T>>, StepError> {
+            match self.receiver.recv_timeout(Duration::from_millis(100)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl<T: ValidFloat> Source for Feed<T> {}
+
+    struct RunningSum<T: ValidFloat> { // slow enough that values queue up in front of it
+        sum: T
+    }
+    impl<T: ValidFloat> PipelineStep<Vec<T>, Vec<T>> for RunningSum<T> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: Vec<T>) -> Result<ODFormat<Vec<T>>, StepError> {
+            sleep(Duration::from_millis(100));
+            self.sum += input.into_iter().sum::<T>();
+            Ok(ODFormat::Standard(vec![self.sum]))
+        }
+        fn checkpointable(&mut self) -> Option<&mut dyn Checkpointable> { Some(self) }
+    }
+    impl<T: ValidFloat> Checkpointable for RunningSum<T> {
+        fn save_state(&self, state: &mut ByteWriter) { self.sum.write_to(state).unwrap() }
+        fn restore_state(&mut self, state: &mut ByteReader) -> Result<(), String> {
+            self.sum = T::read_from(state)?;
+            Ok(())
+        }
+    }
+
+    struct Collect<T: ValidFloat> {
+        sender: mpsc::Sender<T>,
+    }
+    impl<T: ValidFloat> PipelineStep<Vec<T>, ()> for Collect<T> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: Vec<T>) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input[0]).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl<T: ValidFloat> Sink for Collect<T> {}
+
+    struct CountingCollect { // a sink with state of its own, which a plain Collect cannot take
+        sender: mpsc::Sender<f32>,
+        count: u32
+    }
+    impl PipelineStep<Vec<f32>, ()> for CountingCollect {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: Vec<f32>) -> Result<ODFormat<()>, StepError> {
+            self.count += 1;
+            self.sender.send(input[0]).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+        fn checkpointable(&mut self) -> Option<&mut dyn Checkpointable> { Some(self) }
+    }
+    impl Checkpointable for CountingCollect {
+        fn save_state(&self, state: &mut ByteWriter) { state.put_u32(self.count) }
+        fn restore_state(&mut self, state: &mut ByteReader) -> Result<(), String> {
+            self.count = state.u32()?;
+            Ok(())
+        }
+    }
+    impl Sink for CountingCollect {}
+
+    struct CarelessSum { // takes the sum before it finds the rest of the state is no good
+        sum: f32
+    }
+    impl PipelineStep<Vec<f32>, Vec<f32>> for CarelessSum {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<Vec<f32>>, StepError> {
+            self.sum += input.iter().sum::<f32>();
+            Ok(ODFormat::Standard(vec![self.sum]))
+        }
+        fn checkpointable(&mut self) -> Option<&mut dyn Checkpointable> { Some(self) }
+    }
+    impl Checkpointable for CarelessSum {
+        fn save_state(&self, state: &mut ByteWriter) { state.put_f32(self.sum) }
+        fn restore_state(&mut self, state: &mut ByteReader) -> Result<(), String> {
+            self.sum = state.f32()?;
+            match state.at_end() {
+                true => Err(String::from("the state has no limit")),
+                false => Ok(())
+            }
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct Opaque(u32); // a payload nobody gave an encoding, which runs fine but cannot be saved on a link
+    impl HasDefault for Opaque {
+        fn default() -> Self { Opaque(0) }
+    }
+    impl HasSampleCount for Opaque {}
+    impl HasEncoding for Opaque {}
+
+    struct OpaqueFeed {
+        receiver: mpsc::Receiver<Opaque>
+    }
+    impl PipelineStep<(), Opaque> for OpaqueFeed {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<Opaque>, StepError> {
+            match self.receiver.recv_timeout(Duration::from_millis(100)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for OpaqueFeed {}
+
+    struct OpaqueSink {
+        sender: mpsc::Sender<u32>
+    }
+    impl PipelineStep<Opaque, ()> for OpaqueSink {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: Opaque) -> Result<ODFormat<()>, StepError> {
+            sleep(Duration::from_millis(100));
+            self.sender.send(input.0).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for OpaqueSink {}
+
+    fn build<T: ValidFloat>(input_receiver: mpsc::Receiver<Vec<T>>, output_sender: mpsc::Sender<T>) -> ActivePipeline {
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 20);
+        NodeBuilder::start_pipeline("feed", Feed { receiver: input_receiver }, &pipeline)
+            .attach("sum", RunningSum { sum: T::zero() })
+            .cap_pipeline("collect", Collect { sender: output_sender });
+        pipeline.finish_pipeline().unwrap()
+    }
+
+    fn checkpoint_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("zubr_checkpoint_{}_{}.zckp", name, std::process::id()))
+    }
+
+    fn resume_in_flight_values<T: ValidFloat>(name: &str) {
+        initialize_logger();
+        let path = checkpoint_path(name);
+        let (input_sender, input_receiver) = mpsc::channel();
+        let (output_sender, output_receiver) = mpsc::channel();
+        let mut pipeline = build(input_receiver, output_sender);
+        pipeline.start();
+
+        for value in 1..=5 {
+            input_sender.send(vec![T::constant(value as f64)]).unwrap();
+        }
+        sleep(Duration::from_millis(150));
+        pipeline.stop();
+        let summary = pipeline.checkpoint(&path).unwrap();
+        assert_eq!(summary.nodes, 3);
+        assert_eq!(summary.stateful_steps, 1);
+        assert!(summary.in_flight > 0); // the sum is far slower than the feed, the rest waits on its input
+        assert!(!pipeline.is_running());
+        let mut outputs: Vec<T> = output_receiver.try_iter().collect();
+        pipeline.kill();
+
+        let (_input_sender, input_receiver) = mpsc::channel();
+        let (output_sender, output_receiver) = mpsc::channel();
+        let mut resumed = build(input_receiver, output_sender);
+        assert_eq!(resumed.restore(&path).unwrap(), summary);
+        resumed.start();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while outputs.len() < 5 && Instant::now() < deadline {
+            if let Ok(value) = output_receiver.recv_timeout(Duration::from_millis(100)) {
+                outputs.push(value);
+            }
+        }
+        assert_eq!(outputs, [1.0, 3.0, 6.0, 10.0, 15.0].map(T::constant).to_vec());
+        resumed.kill();
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_checkpoint_resumes_in_flight_values() {
+        resume_in_flight_values::<f32>("resume");
+    }
+
+    #[test]
+    fn test_checkpoint_resumes_f64_pipeline() {
+        resume_in_flight_values::<f64>("resume_f64");
+    }
+
+    #[test]
+    fn test_checkpoint_running_pipeline_carries_on() {
+        initialize_logger();
+        let path = checkpoint_path("running");
+        let (input_sender, input_receiver) = mpsc::channel();
+        let (output_sender, output_receiver) = mpsc::channel();
+        let mut pipeline = build(input_receiver, output_sender);
+        pipeline.start();
+
+        for value in 1..=3 {
+            input_sender.send(vec![value as f32]).unwrap();
+        }
+        pipeline.checkpoint(&path).unwrap();
+        assert!(pipeline.is_running());
+
+        let outputs: Vec<f32> = (0..3).map(|_| output_receiver.recv_timeout(Duration::from_secs(5)).unwrap()).collect();
+        assert_eq!(outputs, vec![1.0, 3.0, 6.0]);
+        pipeline.kill();
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_restore_rejected() {
+        initialize_logger();
+        let path = checkpoint_path("rejected");
+        let (_input_sender, input_receiver) = mpsc::channel();
+        let (output_sender, _output_receiver) = mpsc::channel();
+        let mut pipeline = build::<f32>(input_receiver, output_sender);
+        pipeline.start();
+        pipeline.checkpoint(&path).unwrap();
+
+        let missing_file = pipeline.restore(checkpoint_path("missing")).err();
+        assert!(matches!(missing_file, Some(CheckpointError::Io(_))));
+        pipeline.kill();
+
+        let other = ConstructingPipeline::new(3, 1000, 2, 2, 0, 20);
+        let (_input_sender, input_receiver) = mpsc::channel();
+        let (output_sender, _output_receiver) = mpsc::channel();
+        NodeBuilder::start_pipeline("feed", Feed { receiver: input_receiver }, &other)
+            .attach("total", RunningSum { sum: 0.0f32 })
+            .cap_pipeline("collect", Collect { sender: output_sender });
+        let mut other = other.finish_pipeline().unwrap();
+        other.start();
+        assert_eq!(other.restore(&path).err(), Some(CheckpointError::UnknownNode { node_id: "sum".to_string() }));
+        other.kill();
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_rejected_restore_changes_no_node() {
+        // the last node cannot take its state, so the sum in front of it must keep its own and get none of the saved values
+        initialize_logger();
+        let path = checkpoint_path("atomic");
+        let (input_sender, input_receiver) = mpsc::channel();
+        let (output_sender, _output_receiver) = mpsc::channel();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 20);
+        NodeBuilder::start_pipeline("feed", Feed { receiver: input_receiver }, &pipeline)
+            .attach("sum", RunningSum { sum: 0.0f32 })
+            .cap_pipeline("collect", CountingCollect { sender: output_sender, count: 0 });
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        for value in 1..=5 {
+            input_sender.send(vec![value as f32]).unwrap();
+        }
+        sleep(Duration::from_millis(150));
+        pipeline.stop();
+        let summary = pipeline.checkpoint(&path).unwrap();
+        assert!(summary.in_flight > 0);
+        pipeline.kill();
+
+        let (input_sender, input_receiver) = mpsc::channel();
+        let (output_sender, output_receiver) = mpsc::channel();
+        let mut resumed = build::<f32>(input_receiver, output_sender);
+        assert_eq!(resumed.restore(&path).err(), Some(CheckpointError::NotCheckpointable { node_id: "collect".to_string() }));
+        resumed.start();
+
+        input_sender.send(vec![7.0]).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), 7.0);
+        assert!(output_receiver.recv_timeout(Duration::from_millis(300)).is_err());
+        resumed.kill();
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_step_rejecting_halfway_keeps_its_state() {
+        initialize_logger();
+        let path = checkpoint_path("halfway");
+        let (input_sender, input_receiver) = mpsc::channel();
+        let (output_sender, _output_receiver) = mpsc::channel();
+        let mut pipeline = build::<f32>(input_receiver, output_sender);
+        pipeline.start();
+
+        input_sender.send(vec![5.0]).unwrap();
+        sleep(Duration::from_millis(250));
+        pipeline.checkpoint(&path).unwrap();
+        pipeline.kill();
+
+        let other = ConstructingPipeline::new(3, 1000, 2, 2, 0, 20);
+        let (input_sender, input_receiver) = mpsc::channel();
+        let (output_sender, output_receiver) = mpsc::channel();
+        NodeBuilder::start_pipeline("feed", Feed { receiver: input_receiver }, &other)
+            .attach("sum", CarelessSum { sum: 1.0 })
+            .cap_pipeline("collect", Collect { sender: output_sender });
+        let mut other = other.finish_pipeline().unwrap();
+        assert!(matches!(other.restore(&path), Err(CheckpointError::Rejected { .. })));
+        other.start();
+
+        input_sender.send(vec![7.0]).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), 8.0);
+        other.kill();
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_sinusoid_state_continues() {
+        let mut original = SinusoidalSource::new(440.0, 48000.0, 0.0, 64);
+        original.run_DISO().unwrap();
+        let mut state = ByteWriter::new();
+        original.checkpointable().unwrap().save_state(&mut state);
+        let state = state.into_bytes();
+
+        let mut restored = SinusoidalSource::new(440.0, 48000.0, 0.0, 64);
+        restored.checkpointable().unwrap().restore_state(&mut ByteReader::new(&state)).unwrap();
+        assert_eq!(restored.run_DISO().unwrap().unwrap_standard(), original.run_DISO().unwrap().unwrap_standard());
+    }
+
+    #[test]
+    fn test_iir_delay_lines_round_trip() {
+        let coefficients = || ZDomainCoefficients { order: 2, numerator: vec![0.2, 0.3, 0.1], denominator: vec![1.0, -0.4, 0.2] };
+        let mut original: IIRFilterRunner<f32> = IIRFilterRunner::new(coefficients());
+        original.run_iir_filter(vec![1.0, 0.5, -0.25, 0.75]);
+        let mut state = ByteWriter::new();
+        original.save_state(&mut state);
+        let state = state.into_bytes();
+
+        let mut restored: IIRFilterRunner<f32> = IIRFilterRunner::new(coefficients());
+        restored.restore_state(&mut ByteReader::new(&state)).unwrap();
+        assert_eq!(restored.run_iir_filter(vec![0.5, 0.5]), original.run_iir_filter(vec![0.5, 0.5]));
+
+        let mut higher_order: IIRFilterRunner<f32> = IIRFilterRunner::new(ZDomainCoefficients { order: 3, numerator: vec![0.2; 4], denominator: vec![0.1; 4] });
+        assert!(higher_order.restore_state(&mut ByteReader::new(&state)).is_err());
+    }
+
+    #[test]
+    fn test_checkpoint_of_unencodable_link_fails() {
+        initialize_logger();
+        let path = checkpoint_path("unencodable");
+        let (input_sender, input_receiver) = mpsc::channel();
+        let (output_sender, output_receiver) = mpsc::channel();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 20);
+        NodeBuilder::start_pipeline("feed", OpaqueFeed { receiver: input_receiver }, &pipeline)
+            .cap_pipeline("sink", OpaqueSink { sender: output_sender });
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        input_sender.send(Opaque(1)).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), 1);
+        pipeline.stop();
+        pipeline.checkpoint(&path).unwrap(); // nothing is waiting on the link, so there is nothing to encode
+
+        pipeline.start();
+        for value in 2..=4 {
+            input_sender.send(Opaque(value)).unwrap();
+        }
+        sleep(Duration::from_millis(150));
+        pipeline.stop();
+        let error = pipeline.checkpoint(&path).err();
+        assert!(matches!(&error, Some(CheckpointError::NotEncodable { node_id, .. }) if node_id == "sink"), "{:?}", error);
+        pipeline.kill();
+        let _ = std::fs::remove_file(&path);
+    }
+}
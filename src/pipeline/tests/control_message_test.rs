@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use num::Complex;
+    use crate::dsp::core::power::PowerAtFrequency;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+    use crate::pipeline::sources::vector_source::VectorSource;
+
+
+    struct Dummy1 {
+        receiver: mpsc::Receiver<u32>
+    }
+    impl PipelineStep<(), u32> for Dummy1 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
+            match self.receiver.recv_timeout(Duration::from_millis(200)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for Dummy1 {}
+
+    struct OffsetStep { // adds a configurable offset to every value
+        offset: u32
+    }
+    impl PipelineStep<u32, u32> for OffsetStep {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + self.offset))
+        }
+
+        fn handle_control(&mut self, message: &ControlMessage) -> Result<(), String> {
+            match message.key.as_str() {
+                "offset" => { self.offset = message.get_usize()? as u32; Ok(()) },
+                _ => Err(format!("step has no control '{}'", message.key))
+            }
+        }
+    }
+
+    struct Dummy3 {
+        sender: mpsc::Sender<u32>,
+    }
+    impl PipelineStep<u32, ()> for Dummy3 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for Dummy3 {}
+
+    fn offset(value: i64) -> ControlMessage {
+        ControlMessage::new("offset", ControlValue::Integer(value))
+    }
+
+    #[test]
+    fn test_deterministic_control_between_calls() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100).with_execution_model(ExecutionModel::Deterministic);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", VectorSource::new(vec![1, 2, 3]), &pipeline)
+            .attach("offset", OffsetStep { offset: 0 })
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut executor = pipeline.finish_pipeline().unwrap().into_executor();
+        executor.run(1);
+
+        // nothing is handled until the executor runs again, then the change applies from the very next value
+        let ticket = executor.send_control("offset", offset(10)).unwrap();
+        assert_eq!(ticket.try_reply(), None);
+        executor.run(1);
+        assert_eq!(ticket.try_reply(), Some(Ok(())));
+
+        let rejected = executor.send_control("offset", ControlMessage::new("gain", ControlValue::Float(2.0))).unwrap();
+        let mistyped = executor.send_control("offset", ControlMessage::new("offset", ControlValue::Text("ten".to_string()))).unwrap();
+        executor.run(1);
+        assert!(matches!(rejected.try_reply(), Some(Err(ControlError::Rejected { key, .. })) if key == "gain"));
+        assert!(matches!(mistyped.try_reply(), Some(Err(ControlError::Rejected { .. }))));
+
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![1, 12, 13]);
+        assert_eq!(executor.send_control("missing", offset(1)).err(), Some(ControlError::UnknownNode { node_id: "missing".to_string() }));
+        executor.kill();
+    }
+
+    #[test]
+    fn test_control_running_and_paused_pipeline() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100);
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .attach("offset", OffsetStep { offset: 0 })
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        input_sender.send(1).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 1);
+
+        // the node is blocked waiting on input, the message still gets handled once its receive times out
+        pipeline.send_control("offset", offset(5)).unwrap().wait(Duration::from_secs(5)).unwrap();
+        input_sender.send(1).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 6);
+
+        pipeline.stop();
+        pipeline.send_control("offset", offset(7)).unwrap().wait(Duration::from_secs(5)).unwrap();
+        pipeline.start();
+        input_sender.send(1).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 8);
+
+        let rejected = pipeline.send_control("offset", offset(-1)).unwrap().wait(Duration::from_secs(5));
+        assert!(matches!(rejected, Err(ControlError::Rejected { .. })));
+        pipeline.kill();
+    }
+
+    fn power(step: &mut PowerAtFrequency, spectrum: &Vec<Complex<f32>>) -> f32 {
+        match step.run_SISO(spectrum.clone()) {
+            Ok(ODFormat::Standard(power)) => power,
+            _ => panic!("PowerAtFrequency did not return a single value")
+        }
+    }
+
+    #[test]
+    fn test_power_at_frequency_retarget() {
+        let mut step = PowerAtFrequency::new(100.0, 1000.0, 10);
+        let mut spectrum = vec![Complex::new(0.0, 0.0); 10];
+        spectrum[1] = Complex::new(1.0, 0.0);
+        spectrum[3] = Complex::new(2.0, 0.0);
+
+        assert_eq!(power(&mut step, &spectrum), 1.0);
+        step.handle_control(&ControlMessage::new("frequency", ControlValue::Float(300.0))).unwrap();
+        assert_eq!(power(&mut step, &spectrum), 4.0);
+
+        // above the nyquist frequency, the target is left where it was
+        assert!(step.handle_control(&ControlMessage::new("frequency", ControlValue::Float(600.0))).is_err());
+        assert_eq!(power(&mut step, &spectrum), 4.0);
+    }
+}
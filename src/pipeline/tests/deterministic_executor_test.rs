@@ -0,0 +1,212 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+    use crate::pipeline::sources::vector_source::VectorSource;
+
+
+    struct Dummy2{}
+    impl PipelineStep<u32, u32> for Dummy2 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO, StepHandler::MISO, StepHandler::SIMO, StepHandler::REASO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + 1))
+        }
+
+        fn run_MISO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input.iter().sum()))
+        }
+
+        fn run_SIMO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + 1))
+        }
+
+        fn run_REASO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input.iter().sum()))
+        }
+    }
+
+    struct SeriesStep{}
+    impl PipelineStep<u32, u32> for SeriesStep {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Series(vec![input + 1, input + 2, input + 3]))
+        }
+    }
+
+    struct DecomposeStep{}
+    impl PipelineStep<u32, u32> for DecomposeStep {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIMO] }
+
+        fn run_SIMO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Decompose(vec![input, 10 * input]))
+        }
+    }
+
+    struct RepeatStep{}
+    impl PipelineStep<u32, u32> for RepeatStep {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Repeat(input, 2))
+        }
+    }
+
+    struct FailingStep{}
+    impl PipelineStep<u32, u32> for FailingStep {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            if input == 2 { Err(StepError::fatal("cannot handle 2")) } else { Ok(ODFormat::Standard(input)) }
+        }
+    }
+
+    struct Dummy3 {
+        sender: mpsc::Sender<u32>,
+    }
+    impl PipelineStep<u32, ()> for Dummy3 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for Dummy3 {}
+
+    fn deterministic_pipeline() -> ConstructingPipeline {
+        // timeouts are irrelevant, nodes are only called once their input is there
+        ConstructingPipeline::new(3, 1000, 1, 2, 3, 100).with_execution_model(ExecutionModel::Deterministic)
+    }
+
+    #[test]
+    fn test_deterministic_series_chain() {
+        initialize_logger();
+        let pipeline = deterministic_pipeline();
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", VectorSource::new(vec![1, 2, 3]), &pipeline)
+            .attach("series expander", SeriesStep {})
+            .attach("series operator", Dummy2 {})
+            .add_reassembler(3)
+            .attach("series aggregator", Dummy2 {})
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut executor = pipeline.finish_pipeline().unwrap().into_executor();
+        executor.run(3);
+
+        // x expands to (x + 1, x + 2, x + 3), each gets + 1 and the three are summed back up to 3x + 9
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![12, 15, 18]);
+        executor.kill();
+    }
+
+    #[test]
+    fn test_deterministic_decompose_and_repeat() {
+        initialize_logger();
+        let pipeline = deterministic_pipeline();
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        let mut split = NodeBuilder::start_pipeline("source", VectorSource::new(vec![1, 2, 3, 4]), &pipeline)
+            .split_begin("decompose split");
+        let mut joint = joint_begin("joint", &pipeline);
+
+        split.split_add().attach("plus one", Dummy2 {}).branch_end(&mut joint);
+        split.split_add().attach("repeat", RepeatStep {}).add_reassembler(2).attach("repeat sum", Dummy2 {}).branch_end(&mut joint);
+        split.split_lock(DecomposeStep {});
+
+        joint.joint_lock(Dummy2 {})
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut executor = pipeline.finish_pipeline().unwrap().into_executor();
+        executor.run(4);
+
+        // x goes to the first branch as x + 1, 10x to the second where it is repeated and summed to 20x
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![22, 43, 64, 85]);
+        executor.kill();
+    }
+
+    #[test]
+    fn test_deterministic_feedback_joint() {
+        initialize_logger();
+        let mut pipeline = deterministic_pipeline();
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        let mut feedback_joint = joint_feedback_begin("feedback joint", &mut pipeline);
+
+        NodeBuilder::start_pipeline("source", VectorSource::new(vec![1, 1, 1]), &mut pipeline)
+            .attach("step 1", Dummy2 {})
+            .branch_end(&mut feedback_joint);
+
+        let lazy_node = feedback_joint.joint_add_lazy();
+
+        let mut split = feedback_joint.joint_lock(Dummy2 {})
+            .split_begin("split");
+
+        split.split_add()
+            .cap_pipeline("exit", Dummy3 { sender: output_sender });
+
+        lazy_node.joint_link_lazy("feedback node", Dummy2 {}, split.split_add());
+
+        split.split_lock(Dummy2 {});
+
+        let mut executor = pipeline.finish_pipeline().unwrap().into_executor();
+
+        // the first joint call gets the startup default, every later one what the previous output fed back
+        executor.run(1);
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![3]);
+        executor.run(2);
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![7, 11]);
+
+        executor.kill();
+    }
+
+    #[test]
+    fn test_deterministic_fatal_error_kills_pipeline() {
+        initialize_logger();
+        let pipeline = deterministic_pipeline();
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", VectorSource::new(vec![1, 2, 3]), &pipeline)
+            .attach("failing step", FailingStep {})
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut executor = pipeline.finish_pipeline().unwrap().into_executor();
+        executor.run(3);
+
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![1]);
+        assert!(!executor.is_running());
+
+        let diagnostics = executor.get_thread_diagnostics();
+        let failing = diagnostics.iter().find(|diagnostic| diagnostic.id == "failing step").unwrap();
+        assert_eq!(failing.last_error_kind(), Some(StepErrorKind::Fatal));
+        assert_eq!(failing.thread_state, ThreadStateSpace::KILLED);
+
+        executor.kill();
+    }
+
+    #[test]
+    fn test_deterministic_rejects_bounded_links() {
+        initialize_logger();
+        for pipeline in [
+            deterministic_pipeline().with_channel_backend(ChannelBackend::Std),
+            ConstructingPipeline::new(3, 1000, 1, 2, 3, 100).with_channel_backend(ChannelBackend::Crossbeam).with_execution_model(ExecutionModel::Deterministic)
+        ] {
+            let (output_sender, _output_receiver) = mpsc::channel();
+            NodeBuilder::start_pipeline("source", VectorSource::new(vec![1, 2, 3]), &pipeline)
+                .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+            assert!(matches!(pipeline.finish_pipeline().err(), Some(PipelineBuildError::ConflictingParameters(_))));
+        }
+
+        let pipeline = deterministic_pipeline().with_channel_backend(ChannelBackend::Unbounded);
+        let (output_sender, output_receiver) = mpsc::channel();
+        NodeBuilder::start_pipeline("source", VectorSource::new(vec![1, 2, 3]), &pipeline)
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+        let mut executor = pipeline.finish_pipeline().unwrap().into_executor();
+        executor.run(3);
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![1, 2, 3]);
+        executor.kill();
+    }
+}
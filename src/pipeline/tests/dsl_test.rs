@@ -0,0 +1,162 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+
+
+    struct Dummy1 {
+        receiver: mpsc::Receiver<u32>
+    }
+    impl PipelineStep<(), u32> for Dummy1 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
+            match self.receiver.recv_timeout(Duration::from_millis(200)) {
+                Ok(val) => Ok(ODFormat::Standard(val + 1)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for Dummy1 {}
+
+    struct Dummy2 {}
+    impl PipelineStep<u32, u32> for Dummy2 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO, StepHandler::MISO, StepHandler::SIMO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + 1))
+        }
+        fn run_MISO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input.iter().sum()))
+        }
+        fn run_SIMO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + 1))
+        }
+    }
+
+    struct Dummy3 {
+        sender: mpsc::Sender<u32>,
+    }
+    impl PipelineStep<u32, ()> for Dummy3 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for Dummy3 {}
+
+    fn node_ids(pipeline: &ActivePipeline) -> Vec<String> {
+        let mut ids: Vec<String> = pipeline.get_graph().nodes().iter().map(|node| node.id.clone()).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_split_and_joint() {
+        // the graph of split_pipeline_test, declared through the macro
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 1, 2, 3, 1000);
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        pipeline!(&pipeline;
+            "source" (Dummy1 { receiver: input_receiver }) -> "step 1" (Dummy2 {}) -> "split" (Dummy2 {}) -> [
+                "branch 1 node 1" (Dummy2 {}) -> "branch 1 node 2" (Dummy2 {}),
+                "branch 2 node 1" (Dummy2 {}),
+            ] -> "joint" (Dummy2 {}) -> "post joint" (Dummy2 {}) -> "sink" (Dummy3 { sender: output_sender })
+        );
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        assert_eq!(node_ids(&pipeline).len(), 9);
+        pipeline.start();
+
+        input_sender.send(1).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), 12);
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_direct_branches_and_series_splits() {
+        // the graph of mimo_pipeline_test, the branches of _ link each split straight to its joint
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 1, 2, 3, 1000);
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        pipeline!(&pipeline;
+            "source" (Dummy1 { receiver: input_receiver }) -> "split 1" (Dummy2 {}) -> [_, _] -> "joint 1" (Dummy2 {})
+                -> "split 2" (Dummy2 {}) -> [_, _] -> "joint 2" (Dummy2 {}) -> "sink" (Dummy3 { sender: output_sender })
+        );
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        input_sender.send(1).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), 14);
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_nested_split_merges_into_outer_joint() {
+        // the inner split has no joint of its own, so both of its branches end in the joint of the outer split
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 1, 2, 3, 1000);
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        pipeline!(&pipeline;
+            "source" (Dummy1 { receiver: input_receiver }) -> "outer split" (Dummy2 {}) -> [
+                "inner split" (Dummy2 {}) -> ["x" (Dummy2 {}), "y" (Dummy2 {})],
+                _
+            ] -> "joint" (Dummy2 {}) -> "sink" (Dummy3 { sender: output_sender })
+        );
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        input_sender.send(1).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), 13);
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_sink_branches_and_several_chains() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 1, 2, 3, 1000);
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (second_sender, second_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        pipeline!(&pipeline;
+            "source" (Dummy1 { receiver: input_receiver }) -> "split" (Dummy2 {}) -> [
+                "a" (Dummy2 {}) -> "sink a" (Dummy3 { sender: output_sender.clone() }),
+                "sink b" (Dummy3 { sender: output_sender.clone() })
+            ];
+            "second source" (Dummy1 { receiver: second_receiver }) -> "sink c" (Dummy3 { sender: output_sender })
+        );
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        assert_eq!(node_ids(&pipeline), vec!["a", "second source", "sink a", "sink b", "sink c", "source", "split"]);
+        pipeline.start();
+
+        input_sender.send(1).unwrap();
+        let mut outputs = vec![output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), output_receiver.recv_timeout(Duration::from_secs(5)).unwrap()];
+        outputs.sort();
+        assert_eq!(outputs, vec![3, 4]);
+
+        second_sender.send(10).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), 11);
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        // the cases in ui/ must fail to build with the error on the step of the edge which is wrong, see the .stderr next to each
+        let cases = trybuild::TestCases::new();
+        cases.compile_fail("src/pipeline/tests/ui/*.rs");
+    }
+}
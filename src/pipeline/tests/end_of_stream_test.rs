@@ -0,0 +1,244 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::{mpsc, Arc};
+    use std::time::Duration;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+    use crate::pipeline::sources::vector_source::VectorSource;
+
+
+    const FLUSHED: u32 = u32::MAX; // sent by the sink from its flush, so the tests can see it came after every value
+
+    struct Dummy1 {
+        receiver: mpsc::Receiver<u32>
+    }
+    impl PipelineStep<(), u32> for Dummy1 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
+            match self.receiver.recv_timeout(Duration::from_millis(200)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for Dummy1 {}
+
+    struct Dummy2{}
+    impl PipelineStep<u32, u32> for Dummy2 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO, StepHandler::MISO, StepHandler::SIMO, StepHandler::REASO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + 1))
+        }
+
+        fn run_MISO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input.iter().sum()))
+        }
+
+        fn run_SIMO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + 1))
+        }
+
+        fn run_REASO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input.iter().sum()))
+        }
+    }
+
+    struct SeriesStep{}
+    impl PipelineStep<u32, u32> for SeriesStep {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Series(vec![input + 1, input + 2, input + 3]))
+        }
+    }
+
+    struct DelayStep { // outputs the previous input, so the newest value is only ever sent by flush
+        held: Option<u32>
+    }
+    impl PipelineStep<u32, u32> for DelayStep {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Series(self.held.replace(input).into_iter().collect()))
+        }
+
+        fn flush(&mut self) -> Option<ODFormat<u32>> {
+            self.held.take().map(ODFormat::Standard)
+        }
+    }
+
+    struct Dummy3 {
+        sender: mpsc::Sender<u32>,
+    }
+    impl PipelineStep<u32, ()> for Dummy3 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+
+        fn flush(&mut self) -> Option<ODFormat<()>> {
+            self.sender.send(FLUSHED).unwrap();
+            None
+        }
+    }
+    impl Sink for Dummy3 {}
+
+    #[test]
+    fn test_source_end_of_stream_through_reassembler() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", VectorSource::new(vec![1, 2, 3]), &pipeline)
+            .attach("series expander", SeriesStep {})
+            .add_reassembler(3)
+            .attach("series sum", Dummy2 {})
+            .attach("delay", DelayStep { held: None })
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        // x expands to (x + 1, x + 2, x + 3) and is summed back to 3x + 6, the last sum is only sent once the delay is flushed
+        assert!(pipeline.wait_for_end_of_stream(Duration::from_secs(10)));
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![9, 12, 15, FLUSHED]);
+
+        for diagnostic in pipeline.get_thread_diagnostics() {
+            assert!(diagnostic.end_of_stream, "{} did not reach the end of stream", diagnostic.id);
+            assert_eq!(diagnostic.return_code, PipelineStepResult::EndOfStream);
+        }
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_joint_ends_with_shortest_input() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        let mut joint = joint_begin("joint", &pipeline);
+        NodeBuilder::start_pipeline("short source", VectorSource::new(vec![1, 2]), &pipeline)
+            .branch_end(&mut joint);
+        NodeBuilder::start_pipeline("long source", VectorSource::new(vec![10, 20, 30, 40]), &pipeline)
+            .branch_end(&mut joint);
+        joint.joint_lock(Dummy2 {})
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        // the values left on the longer input are never summed against made up values from the ended one
+        assert!(pipeline.wait_for_end_of_stream(Duration::from_secs(10)));
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![11, 22, FLUSHED]);
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_drain_and_stop_split_joint() {
+        initialize_logger();
+        // compute errors are unlimited since the source keeps timing out while it waits for input
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100);
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        let mut split = NodeBuilder::start_pipeline("source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .split_begin("split");
+        let mut joint = joint_begin("joint", &pipeline);
+
+        split.split_add().attach("delay 1", DelayStep { held: None }).branch_end(&mut joint);
+        split.split_add().attach("delay 2", DelayStep { held: None }).branch_end(&mut joint);
+        split.split_lock(Dummy2 {});
+
+        joint.joint_lock(Dummy2 {})
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        // x + 1 goes down both branches and is summed back up by the joint one input later
+        for value in 1..4 {
+            input_sender.send(value).unwrap();
+        }
+        assert_eq!(output_receiver.recv().unwrap(), 4);
+        assert_eq!(output_receiver.recv().unwrap(), 6);
+
+        // the values still held by the delays must come out before the sink sees the end of stream
+        assert!(pipeline.drain_and_stop(Duration::from_secs(10)));
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![8, FLUSHED]);
+    }
+
+    #[test]
+    fn test_deterministic_drain_feedback_joint() {
+        initialize_logger();
+        let mut pipeline = ConstructingPipeline::new(3, 1000, 1, 2, 3, 100).with_execution_model(ExecutionModel::Deterministic);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        let mut feedback_joint = joint_feedback_begin("feedback joint", &mut pipeline);
+
+        NodeBuilder::start_pipeline("source", VectorSource::new(vec![1, 1, 1]), &mut pipeline)
+            .attach("step 1", Dummy2 {})
+            .branch_end(&mut feedback_joint);
+
+        let lazy_node = feedback_joint.joint_add_lazy();
+
+        let mut split = feedback_joint.joint_lock(Dummy2 {})
+            .split_begin("split");
+
+        split.split_add()
+            .cap_pipeline("exit", Dummy3 { sender: output_sender });
+
+        lazy_node.joint_link_lazy("feedback node", Dummy2 {}, split.split_add());
+
+        split.split_lock(Dummy2 {});
+
+        let mut executor = pipeline.finish_pipeline().unwrap().into_executor();
+
+        executor.run(2);
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![3, 7]);
+
+        // the source ends before producing its last value, the end of stream goes round the feedback loop too
+        executor.drain();
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![FLUSHED]);
+        for diagnostic in executor.get_thread_diagnostics() {
+            assert!(diagnostic.end_of_stream, "{} did not reach the end of stream", diagnostic.id);
+        }
+
+        executor.kill();
+    }
+
+    #[test]
+    fn test_pool_drain_multiplexed_branch() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100).with_execution_model(ExecutionModel::ThreadPool(2));
+        let selector = Arc::new(AtomicUsize::new(0));
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        let mut multiplexer = NodeBuilder::start_pipeline("source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .mutltiplexer_begin("multiplexer", selector.clone());
+        let mut demultiplexer = demultiplexer_begin("demultiplexer", selector.clone(), &pipeline);
+
+        multiplexer.multiplexer_add().attach("delay 0", DelayStep { held: None }).multiplex_branch_end(&mut demultiplexer);
+        multiplexer.multiplexer_add().attach("delay 1", DelayStep { held: None }).multiplex_branch_end(&mut demultiplexer);
+        multiplexer.multiplexer_lock(Dummy2 {});
+
+        demultiplexer.demultiplexer_lock(Dummy2 {})
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        input_sender.send(1).unwrap();
+        input_sender.send(2).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 3);
+
+        // only the selected branch is ended, the paused one has nothing to flush and never sees the end of stream
+        assert!(pipeline.drain_and_stop(Duration::from_secs(10)));
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![4, FLUSHED]);
+    }
+}
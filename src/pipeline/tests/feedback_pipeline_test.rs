@@ -13,10 +13,12 @@ mod pipeline_tests {
         receiver: mpsc::Receiver<u32>
     }
     impl PipelineStep<(), u32> for Dummy1 {
-        fn run_DISO(&mut self) -> Result<ODFormat<u32>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
             match self.receiver.recv_timeout(std::time::Duration::from_millis(2000)) {
                 Ok(val) => Ok(ODFormat::Standard(val + 1)),
-                Err(_) => Err("Timeout error".to_string())
+                Err(_) => Err(StepError::recoverable("Timeout error"))
             }
         }
     }
@@ -24,15 +26,17 @@ mod pipeline_tests {
 
     struct Dummy2{}
     impl PipelineStep<u32, u32> for Dummy2 {
-        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO, StepHandler::MISO, StepHandler::SIMO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
             Ok(ODFormat::Standard(input + 1))
         }
 
-        fn run_MISO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, String> {
+        fn run_MISO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
             Ok(ODFormat::Standard(input.iter().sum()))
         }
 
-        fn run_SIMO(&mut self, input: u32) -> Result<ODFormat<u32>, String> {
+        fn run_SIMO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
             Ok(ODFormat::Standard(input + 1))
         }
     }
@@ -41,7 +45,9 @@ mod pipeline_tests {
         sender: mpsc::Sender<u32>,
     }
     impl PipelineStep<u32, ()> for Dummy3 {
-        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
             self.sender.send(input).unwrap();
             Ok(ODFormat::Standard(()))
         }
@@ -81,7 +87,7 @@ mod pipeline_tests {
         
         test_split.split_lock(Dummy2 {});
 
-        let mut pipeline = pipeline.finish_pipeline();
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
         pipeline.start();
 
         input_pair.0.send(1);
@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+
+
+    struct Dummy1 {
+        receiver: mpsc::Receiver<u32>
+    }
+    impl PipelineStep<(), u32> for Dummy1 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
+            match self.receiver.recv_timeout(std::time::Duration::from_millis(2000)) {
+                Ok(val) => Ok(ODFormat::Standard(val + 1)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for Dummy1 {}
+
+    struct Dummy2{}
+    impl PipelineStep<u32, u32> for Dummy2 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO, StepHandler::MISO, StepHandler::SIMO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + 1))
+        }
+
+        fn run_MISO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input.iter().sum()))
+        }
+
+        fn run_SIMO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + 1))
+        }
+    }
+
+    struct Dummy3{
+        sender: mpsc::Sender<u32>,
+    }
+    impl PipelineStep<u32, ()> for Dummy3 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for Dummy3 {}
+
+    fn edge_between<'a>(graph: &'a PipelineGraph, from: &str, to: &str) -> Option<&'a GraphEdge> {
+        let from = graph.find_node(from)?.index;
+        let to = graph.find_node(to)?.index;
+        graph.edges().into_iter().find(|edge| edge.from == from && edge.to == to)
+    }
+
+    #[test]
+    fn test_split_graph_export() {
+        initialize_logger();
+
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100);
+        let input_pair = mpsc::sync_channel(1);
+        let (output_sender, _output_receiver) = mpsc::channel();
+
+        let mut split = NodeBuilder::start_pipeline("test_source", Dummy1 { receiver: input_pair.1 }, &pipeline)
+            .split_begin("test_split");
+        let mut test_joint = joint_begin("test_joint", &pipeline);
+
+        split.split_add()
+            .attach("branch_1 node 1", Dummy2 {})
+            .branch_end(&mut test_joint);
+        split.split_add()
+            .branch_end(&mut test_joint);
+        split.split_lock(Dummy2 {});
+
+        test_joint.joint_lock(Dummy2 {})
+            .cap_pipeline("test_sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        let graph = pipeline.get_graph();
+
+        assert_eq!(graph.nodes().len(), 5);
+        assert_eq!(graph.edges().len(), 5);
+        assert_eq!(graph.find_node("test_source").unwrap().kind, GraphNodeKind::Source);
+        assert_eq!(graph.find_node("test_split").unwrap().kind, GraphNodeKind::Split);
+        assert_eq!(graph.find_node("test_joint").unwrap().kind, GraphNodeKind::Joint);
+        assert_eq!(graph.find_node("test_sink").unwrap().kind, GraphNodeKind::Sink);
+
+        assert_eq!(edge_between(graph, "test_source", "test_split").unwrap().kind, GraphEdgeKind::Standard);
+        assert_eq!(edge_between(graph, "test_split", "branch_1 node 1").unwrap().kind, GraphEdgeKind::Split);
+        assert!(edge_between(graph, "branch_1 node 1", "test_joint").is_some());
+        assert!(edge_between(graph, "test_split", "test_joint").is_some()); // empty branch goes straight to the joint
+        assert_eq!(edge_between(graph, "test_joint", "test_sink").unwrap().capacity, 2);
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph pipeline {"));
+        assert_eq!(dot.matches("->").count(), 5);
+
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json()).unwrap();
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 5);
+        assert_eq!(json["edges"].as_array().unwrap().len(), 5);
+
+        pipeline.start();
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_feedback_graph_export() {
+        initialize_logger();
+
+        let pipeline = ConstructingPipeline::new(3, 1000, 1, 2, 3, 100);
+        let input_pair = mpsc::sync_channel(1);
+        let (output_sender, _output_receiver) = mpsc::channel();
+
+        let mut feedback_joint = joint_feedback_begin("feedback_joint", &pipeline);
+
+        NodeBuilder::start_pipeline("test_source", Dummy1 { receiver: input_pair.1 }, &pipeline)
+            .branch_end(&mut feedback_joint);
+
+        let lazy_node = feedback_joint.joint_add_lazy();
+        let mut test_split = feedback_joint.joint_lock(Dummy2 {})
+            .split_begin("test_split");
+
+        test_split.split_add()
+            .cap_pipeline("test_sink", Dummy3 { sender: output_sender });
+        lazy_node.joint_link_lazy("feedback_node", Dummy2 {}, test_split.split_add());
+        test_split.split_lock(Dummy2 {});
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        let graph = pipeline.get_graph();
+
+        assert_eq!(graph.nodes().len(), 5);
+        assert_eq!(edge_between(graph, "feedback_node", "feedback_joint").unwrap().kind, GraphEdgeKind::Feedback);
+        assert_eq!(edge_between(graph, "test_split", "feedback_node").unwrap().kind, GraphEdgeKind::Split);
+        assert!(edge_between(graph, "test_source", "feedback_joint").is_some());
+
+        pipeline.start();
+        pipeline.kill();
+    }
+}
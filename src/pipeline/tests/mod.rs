@@ -7,4 +7,25 @@ mod pipeline_error_tests;
 mod multiplexer_pipeline_test;
 mod series_pipeline_test;
 mod mimo_pipeline_test;
-mod special_behavior_pipelines;
\ No newline at end of file
+mod special_behavior_pipelines;
+mod graph_export_test;
+mod pipeline_config_test;
+mod recipe_pipeline_test;
+mod node_metrics_test;
+mod channel_backend_test;
+mod scheduler_test;
+mod deterministic_executor_test;
+mod end_of_stream_test;
+mod stream_tags_test;
+mod control_message_test;
+mod supervisor_test;
+mod backpressure_test;
+mod tap_test;
+mod recording_test;
+mod thread_options_test;
+mod time_sync_test;
+mod remote_test;
+mod tracing_test;
+mod swap_test;
+mod dsl_test;
+mod checkpoint_test;
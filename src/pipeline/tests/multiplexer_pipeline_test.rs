@@ -13,10 +13,12 @@ mod pipeline_tests {
         receiver: mpsc::Receiver<u32>
     }
     impl PipelineStep<(), u32> for Dummy1 {
-        fn run_DISO(&mut self) -> Result<ODFormat<u32>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
             match self.receiver.recv_timeout(std::time::Duration::from_millis(2000)) {
                 Ok(val) => Ok(ODFormat::Standard(val + 1)),
-                Err(_) => Err("Timeout error".to_string())
+                Err(_) => Err(StepError::recoverable("Timeout error"))
             }
         }
     }
@@ -24,14 +26,18 @@ mod pipeline_tests {
 
     struct Dummy2a{}
     impl PipelineStep<u32, u32> for Dummy2a {
-        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
             Ok(ODFormat::Standard(input + 1))
         }
     }
 
     struct Dummy2b{}
     impl PipelineStep<u32, u32> for Dummy2b {
-        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
             Ok(ODFormat::Standard(input + 2))
         }
     }
@@ -40,7 +46,9 @@ mod pipeline_tests {
         sender: mpsc::Sender<u32>,
     }
     impl PipelineStep<u32, ()> for Dummy3 {
-        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
             self.sender.send(input).unwrap();
             Ok(ODFormat::Standard(()))
         }
@@ -82,7 +90,7 @@ mod pipeline_tests {
         
         log_message("Pipeline Path Designed".to_string(), Level::Debug);
 
-        let mut pipeline = pipeline.finish_pipeline();
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
 
         log_message("Pipeline finished construction".to_string(), Level::Debug);
 
@@ -108,5 +116,89 @@ mod pipeline_tests {
 
         log_message("Pipeline execution ended".to_string(), Level::Debug);
     }
+
+    struct BranchStep {
+        amount: u32,
+        starts: Arc<AtomicUsize>,
+        pauses: Arc<AtomicUsize>,
+    }
+    impl PipelineStep<u32, u32> for BranchStep {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            if input == 100 { return Err(StepError::needs_reset("cannot handle 100")) }
+            Ok(ODFormat::Standard(input + self.amount))
+        }
+        fn start_behavior(&mut self) {
+            self.starts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+        fn pause_behavior(&mut self) {
+            self.pauses.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    fn branch_state(pipeline: &ActivePipeline, id: &str) -> ThreadStateSpace {
+        pipeline.get_thread_diagnostics().into_iter().find(|diagnostic| diagnostic.id == id).unwrap().thread_state
+    }
+
+    #[test]
+    fn test_multiplexer_branch_pause() {
+        initialize_logger();
+        // short timeouts so the demultiplexer gives up quickly on the branch which was selected away
+        let pipeline = ConstructingPipeline::new(3, 200, 1, 5, 3, 20);
+        let input_pair = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+        let selector = Arc::new(AtomicUsize::new(0));
+        let counters: Vec<(Arc<AtomicUsize>, Arc<AtomicUsize>)> = (0..2).map(|_| (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)))).collect();
+
+        let mut multiplexer = NodeBuilder::start_pipeline("origin", Dummy1 { receiver: input_pair.1 }, &pipeline)
+            .mutltiplexer_begin("multiplexer", selector.clone());
+        let mut demultiplexer = demultiplexer_begin("demultiplexer", selector.clone(), &pipeline);
+
+        for (branch, (starts, pauses)) in counters.iter().enumerate() {
+            let id = if branch == 0 { "branch 0" } else { "branch 1" };
+            multiplexer.multiplexer_add()
+                .attach(id, BranchStep { amount: 10 * (branch as u32 + 1), starts: starts.clone(), pauses: pauses.clone() })
+                .multiplex_branch_end(&mut demultiplexer);
+        }
+        multiplexer.multiplexer_lock(Dummy2a {});
+        demultiplexer.demultiplexer_lock(Dummy2a {})
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        // source +1, multiplexer +1, branch 0 +10, demultiplexer +1
+        input_pair.0.send(1).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 14);
+        assert_eq!(branch_state(&pipeline, "branch 0"), ThreadStateSpace::RUNNING);
+        assert_eq!(branch_state(&pipeline, "branch 1"), ThreadStateSpace::PAUSED);
+        assert_eq!(counters[1].0.load(std::sync::atomic::Ordering::SeqCst), 0, "the unselected branch never starts");
+
+        selector.store(1, std::sync::atomic::Ordering::SeqCst);
+        input_pair.0.send(1).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 24);
+        sleep(std::time::Duration::from_millis(700)); // branch 0 notices once its pending receive times out, like a global pause
+        assert_eq!(branch_state(&pipeline, "branch 0"), ThreadStateSpace::PAUSED);
+        assert_eq!(counters[0].1.load(std::sync::atomic::Ordering::SeqCst), 1, "the branch selected away is paused");
+        assert_eq!(counters[1].0.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(pipeline.is_running());
+
+        // an error pause inside the active branch stays in the branch, the rest of the pipeline keeps running
+        input_pair.0.send(98).unwrap();
+        sleep(std::time::Duration::from_millis(200));
+        assert_eq!(branch_state(&pipeline, "branch 1"), ThreadStateSpace::PAUSED);
+        assert!(pipeline.is_running());
+
+        // selecting the other branch clears it, so it resumes cleanly when selected again
+        selector.store(0, std::sync::atomic::Ordering::SeqCst);
+        input_pair.0.send(2).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 15);
+        selector.store(1, std::sync::atomic::Ordering::SeqCst);
+        input_pair.0.send(3).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 26);
+        assert_eq!(counters[1].0.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        pipeline.kill();
+    }
 }
-    
\ No newline at end of file
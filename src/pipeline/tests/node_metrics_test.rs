@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use std::thread::sleep;
+    use std::time::Duration;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+
+
+    struct Dummy1 {
+        receiver: mpsc::Receiver<Vec<u32>>
+    }
+    impl PipelineStep<(), Vec<u32>> for Dummy1 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<Vec<u32>>, StepError> {
+            match self.receiver.recv_timeout(std::time::Duration::from_millis(2000)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for Dummy1 {}
+
+    struct SlowStep {
+        delay: Duration
+    }
+    impl PipelineStep<Vec<u32>, Vec<u32>> for SlowStep {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: Vec<u32>) -> Result<ODFormat<Vec<u32>>, StepError> {
+            sleep(self.delay);
+            Ok(ODFormat::Series(vec![input.clone(), input])) // every input goes out twice
+        }
+    }
+
+    struct SlowSink {
+        sender: mpsc::Sender<Vec<u32>>,
+        delay: Duration
+    }
+    impl PipelineStep<Vec<u32>, ()> for SlowSink {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: Vec<u32>) -> Result<ODFormat<()>, StepError> {
+            sleep(self.delay);
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for SlowSink {}
+
+    fn diagnostic(pipeline: &ActivePipeline, id: &str) -> ThreadDiagnostic {
+        pipeline.get_thread_diagnostics().into_iter()
+            .find(|diagnostic| diagnostic.id == id)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_node_metrics() {
+        initialize_logger();
+
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100);
+        let (input_sender, input_receiver) = mpsc::sync_channel(20);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("test_source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .attach("slow step", SlowStep { delay: Duration::from_millis(10) })
+            .cap_pipeline("slow sink", SlowSink { sender: output_sender, delay: Duration::from_millis(40) });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        for _ in 0..10 {
+            input_sender.send(vec![1, 2, 3, 4]).unwrap();
+        }
+        sleep(Duration::from_millis(300));
+
+        let sink = diagnostic(&pipeline, "slow sink").metrics;
+        assert_eq!(sink.queue_capacity, 2);
+        assert!(sink.queue_depth >= 1, "the sink is the bottleneck so its input should be backed up");
+        assert!(diagnostic(&pipeline, "slow step").metrics.send_blocked > Duration::ZERO);
+
+        for _ in 0..20 {
+            output_receiver.recv().unwrap();
+        }
+        sleep(Duration::from_millis(50)); // the sink records its last call after handing the value over
+
+        let step = diagnostic(&pipeline, "slow step").metrics;
+        assert_eq!(step.total_items_in, 10);
+        assert_eq!(step.total_items_out, 20);
+        assert!(step.compute_time_min >= Duration::from_millis(10));
+        assert!(step.compute_time_mean >= step.compute_time_min);
+        assert!(step.compute_time_p99 >= step.compute_time_mean);
+        assert!(step.items_in_per_second > 0.0);
+        assert!((step.samples_out_per_second / step.items_out_per_second - 4.0).abs() < 1e-6, "{:?}", step);
+
+        let sink = diagnostic(&pipeline, "slow sink").metrics;
+        assert_eq!(sink.total_items_in, 20);
+        assert!(sink.compute_time_min >= Duration::from_millis(40));
+        assert!(sink.receive_blocked < sink.compute_time_mean * 20);
+
+        pipeline.kill();
+    }
+}
@@ -0,0 +1,310 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::{mpsc, Arc, Mutex};
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+
+
+    struct Dummy1 {
+        receiver: mpsc::Receiver<u32>
+    }
+    impl PipelineStep<(), u32> for Dummy1 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
+            match self.receiver.recv_timeout(std::time::Duration::from_millis(2000)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for Dummy1 {}
+
+    struct Dummy2 {
+        amount: u32
+    }
+    impl PipelineStep<u32, u32> for Dummy2 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO, StepHandler::MISO, StepHandler::SIMO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + self.amount))
+        }
+
+        fn run_MISO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input.iter().sum()))
+        }
+
+        fn run_SIMO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + self.amount))
+        }
+    }
+
+    struct Dummy3 {
+        sender: mpsc::Sender<u32>,
+    }
+    impl PipelineStep<u32, ()> for Dummy3 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for Dummy3 {}
+
+    struct FloatSink {}
+    impl PipelineStep<f32, ()> for FloatSink {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, _input: f32) -> Result<ODFormat<()>, StepError> {
+            Ok(ODFormat::Standard(()))
+        }
+    }
+
+    fn test_registry() -> (StepRegistry, mpsc::SyncSender<u32>, mpsc::Receiver<u32>) {
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+        let input_receiver = Arc::new(Mutex::new(Some(input_receiver)));
+
+        let mut registry = StepRegistry::new();
+        registry.register::<(), u32, _>("source", move |_| {
+            let receiver = input_receiver.lock().unwrap().take().ok_or(String::from("source can only be used once"))?;
+            Ok(Dummy1 { receiver })
+        });
+        registry.register::<u32, u32, _>("adder", |params| Ok(Dummy2 { amount: params.get_usize("amount")? as u32 }));
+        registry.register::<u32, (), _>("sink", move |_| Ok(Dummy3 { sender: output_sender.clone() }));
+        registry.register::<f32, (), _>("float_sink", |_| Ok(FloatSink {}));
+
+        (registry, input_sender, output_receiver)
+    }
+
+    const SPLIT_DEFINITION: &str = r#"
+[parameters]
+retries = 3
+timeout = 1000
+backpressure_val = 2
+max_infrastructure_errors = 2
+max_compute_errors = 3
+unchanged_state_time = 100
+
+[[nodes]]
+id = "test_source"
+step = "source"
+
+[[nodes]]
+id = "test_split"
+step = "adder"
+params = { amount = 1 }
+
+[[nodes]]
+id = "branch_1"
+step = "adder"
+params = { amount = 1 }
+
+[[nodes]]
+id = "branch_2"
+step = "adder"
+params = { amount = 1 }
+
+[[nodes]]
+id = "test_joint"
+step = "adder"
+params = { amount = 0 }
+
+[[nodes]]
+id = "test_sink"
+step = "sink"
+
+[[connections]]
+from = "test_source"
+to = "test_split"
+
+[[connections]]
+from = "test_split"
+to = "branch_1"
+
+[[connections]]
+from = "test_split"
+to = "branch_2"
+
+[[connections]]
+from = "branch_1"
+to = "test_joint"
+
+[[connections]]
+from = "branch_2"
+to = "test_joint"
+
+[[connections]]
+from = "test_joint"
+to = "test_sink"
+"#;
+
+    const LINEAR_DEFINITION: &str = r#"{
+    "parameters": { "retries": 3, "timeout": 1000, "backpressure_val": 1, "max_infrastructure_errors": 2, "max_compute_errors": 3, "unchanged_state_time": 100 },
+    "nodes": [
+        { "id": "test_source", "step": "source" },
+        { "id": "test_step", "step": "adder", "params": { "amount": 5 } },
+        { "id": "test_sink", "step": "sink" }
+    ],
+    "connections": [
+        { "from": "test_source", "to": "test_step" },
+        { "from": "test_step", "to": "test_sink" }
+    ]
+}"#;
+
+    #[test]
+    fn test_toml_split_pipeline() {
+        initialize_logger();
+
+        let (registry, input_sender, output_receiver) = test_registry();
+        let mut pipeline = pipeline_from_toml(SPLIT_DEFINITION, &registry).unwrap();
+
+        let graph = pipeline.get_graph();
+        assert_eq!(graph.nodes().len(), 6);
+        assert_eq!(graph.edges().len(), 6);
+        assert_eq!(graph.find_node("test_split").unwrap().kind, GraphNodeKind::Split);
+        assert_eq!(graph.find_node("test_joint").unwrap().kind, GraphNodeKind::Joint);
+
+        pipeline.start();
+        for value in 0..5 {
+            input_sender.send(value).unwrap();
+            assert_eq!(output_receiver.recv().unwrap(), 2 * (value + 2));
+        }
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_toml_thread_pool_execution() {
+        initialize_logger();
+
+        let definition = SPLIT_DEFINITION.replace("unchanged_state_time = 100\n", "unchanged_state_time = 100\nexecution_model = { thread_pool = 2 }\n");
+        assert_eq!(PipelineDefinition::from_toml(&definition).unwrap().parameters.execution_model, ExecutionModel::ThreadPool(2));
+
+        let (registry, input_sender, output_receiver) = test_registry();
+        let mut pipeline = pipeline_from_toml(&definition, &registry).unwrap();
+
+        pipeline.start();
+        for value in 0..5 {
+            input_sender.send(value).unwrap();
+            assert_eq!(output_receiver.recv().unwrap(), 2 * (value + 2));
+        }
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_json_definition_from_file() {
+        initialize_logger();
+
+        let path = std::env::temp_dir().join(format!("zubr_pipeline_config_test_{}.json", std::process::id()));
+        std::fs::write(&path, LINEAR_DEFINITION).unwrap();
+
+        let (registry, input_sender, output_receiver) = test_registry();
+        let mut pipeline = load_pipeline(&path, &registry).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        pipeline.start();
+        input_sender.send(1).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 6);
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_definition_errors() {
+        initialize_logger();
+
+        let (registry, _input_sender, _output_receiver) = test_registry();
+
+        let unknown_step = SPLIT_DEFINITION.replace("step = \"sink\"", "step = \"missing\"");
+        match pipeline_from_toml(&unknown_step, &registry) {
+            Err(PipelineConfigError::UnknownStep { line, step, .. }) => { assert_eq!(step, "missing"); assert_eq!(line, Some(36)); },
+            _ => panic!("Unknown step was accepted")
+        }
+
+        let mismatch = SPLIT_DEFINITION.replace("step = \"sink\"", "step = \"float_sink\"");
+        match pipeline_from_toml(&mismatch, &registry) {
+            Err(PipelineConfigError::TypeMismatch { from, to, .. }) => { assert_eq!(from, "test_joint"); assert_eq!(to, "test_sink"); },
+            _ => panic!("Mismatched connection types were accepted")
+        }
+
+        let unknown_node = SPLIT_DEFINITION.replace("to = \"branch_2\"", "to = \"branch_3\"");
+        match pipeline_from_toml(&unknown_node, &registry) {
+            Err(PipelineConfigError::UnknownNode { node_id, line }) => { assert_eq!(node_id, "branch_3"); assert_eq!(line, Some(48)); },
+            _ => panic!("Connection to an undefined node was accepted")
+        }
+
+        let missing_parameter = SPLIT_DEFINITION.replace("params = { amount = 0 }", "");
+        let error = pipeline_from_toml(&missing_parameter, &registry).err().unwrap();
+        assert!(matches!(error, PipelineConfigError::InvalidParameters { .. }));
+        assert!(error.to_string().contains("amount"));
+
+        let broken = SPLIT_DEFINITION.replace("timeout = 1000", "timeout = ");
+        assert_eq!(pipeline_from_toml(&broken, &registry).err().unwrap().line(), Some(4));
+
+        // the steps are prepared before the links are made, so this needs a registry whose source was not used up yet
+        let (registry, _input_sender, _output_receiver) = test_registry();
+        let rendezvous_drop = SPLIT_DEFINITION.replace("backpressure_val = 2", "backpressure_val = 0")
+            .replace("to = \"test_sink\"", "to = \"test_sink\"\nbackpressure = \"drop_oldest\"");
+        let error = pipeline_from_toml(&rendezvous_drop, &registry).err().unwrap();
+        assert!(matches!(&error, PipelineConfigError::InvalidParameters { node_id, .. } if node_id == "test_sink"));
+        assert!(error.to_string().contains("rendezvous"));
+
+        let reassembled_drop = SPLIT_DEFINITION.replace("step = \"sink\"", "step = \"sink\"\nreassemble = 2")
+            .replace("to = \"test_sink\"", "to = \"test_sink\"\nbackpressure = \"drop_newest\"");
+        let error = pipeline_from_toml(&reassembled_drop, &registry).err().unwrap();
+        assert!(error.to_string().contains("reassembler"));
+
+        let reassembled_joint = SPLIT_DEFINITION.replace("params = { amount = 0 }", "params = { amount = 0 }\nreassemble = 2");
+        match pipeline_from_toml(&reassembled_joint, &registry) {
+            Err(PipelineConfigError::InvalidParameters { node_id, line, .. }) => { assert_eq!(node_id, "test_joint"); assert_eq!(line, Some(30)); },
+            _ => panic!("A joint was allowed to reassemble")
+        }
+    }
+
+    const DEFAULT_STEPS_DEFINITION: &str = r#"
+[parameters]
+retries = 3
+timeout = 1000
+backpressure_val = 2
+max_infrastructure_errors = 2
+max_compute_errors = 3
+unchanged_state_time = 100
+
+[[nodes]]
+id = "test_source"
+step = "sinusoid"
+params = { frequency = 440.0, sampling_frequency = 48000.0, phase = 0.0, buffer_size = 1024 }
+
+[[nodes]]
+id = "test_step"
+STEP
+
+[[connections]]
+from = "test_source"
+to = "test_step"
+"#;
+
+    #[test]
+    fn test_default_step_parameters() {
+        initialize_logger();
+
+        let registry = StepRegistry::with_defaults();
+        let invalid_steps = [
+            "step = \"decimator\"\nparams = { sampling_period = 0 }",
+            "step = \"upsampler\"\nparams = { upsample_factor = 0 }",
+            "step = \"convolution\"\nparams = { input_size = 1024, impulse_response = [] }"
+        ];
+        for step in invalid_steps {
+            match pipeline_from_toml(&DEFAULT_STEPS_DEFINITION.replace("STEP", step), &registry) {
+                Err(PipelineConfigError::InvalidParameters { node_id, line, .. }) => { assert_eq!(node_id, "test_step"); assert_eq!(line, Some(16)); },
+                _ => panic!("'{}' was accepted", step)
+            }
+        }
+
+        let fft = DEFAULT_STEPS_DEFINITION.replace("STEP", "step = \"complex_cast\"")
+            + "\n[[nodes]]\nid = \"test_fft\"\nstep = \"fft\"\nparams = { buffer_size = 1000 }\n\n[[connections]]\nfrom = \"test_step\"\nto = \"test_fft\"\n";
+        let error = pipeline_from_toml(&fft, &registry).err().unwrap();
+        assert!(matches!(&error, PipelineConfigError::InvalidParameters { node_id, .. } if node_id == "test_fft"));
+        assert!(error.to_string().contains("power of two"));
+    }
+}
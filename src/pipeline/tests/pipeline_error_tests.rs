@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread::sleep;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+
+
+    struct Dummy1 {
+        receiver: mpsc::Receiver<u32>
+    }
+    impl PipelineStep<(), u32> for Dummy1 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
+            match self.receiver.recv_timeout(std::time::Duration::from_millis(2000)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for Dummy1 {}
+
+    struct ErrorStep {
+        reset_flag: Arc<AtomicBool>
+    }
+    impl PipelineStep<u32, u32> for ErrorStep {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            match input {
+                0 => Err(StepError::fatal("fatal input").with_source(std::fmt::Error)),
+                1 => Err(StepError::needs_reset("reset input")),
+                _ => Ok(ODFormat::Standard(input))
+            }
+        }
+        fn reset_behavior(&mut self) {
+            self.reset_flag.store(true, Ordering::Release);
+        }
+    }
+
+    struct Dummy3{
+        sender: mpsc::Sender<u32>,
+    }
+    impl PipelineStep<u32, ()> for Dummy3 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for Dummy3 {}
+
+    fn build_error_pipeline(reset_flag: Arc<AtomicBool>) -> (ActivePipeline, mpsc::SyncSender<u32>, mpsc::Receiver<u32>) {
+        let pipeline = ConstructingPipeline::new(3, 1000, 1, 2, 3, 100);
+        let input_pair = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("test_source", Dummy1 { receiver: input_pair.1 }, &pipeline)
+            .attach("error step", ErrorStep { reset_flag })
+            .cap_pipeline("test_sink", Dummy3 { sender: output_sender });
+
+        (pipeline.finish_pipeline().unwrap(), input_pair.0, output_receiver)
+    }
+
+    fn error_step_diagnostic(pipeline: &ActivePipeline) -> ThreadDiagnostic {
+        pipeline.get_thread_diagnostics().into_iter()
+            .find(|diagnostic| diagnostic.id == "error step")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_fatal_error_kills_pipeline() {
+        initialize_logger();
+
+        let (mut pipeline, input_sender, output_receiver) = build_error_pipeline(Arc::new(AtomicBool::new(false)));
+        pipeline.start();
+
+        input_sender.send(5).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 5);
+
+        input_sender.send(0).unwrap();
+        sleep(std::time::Duration::from_millis(500));
+
+        assert!(!pipeline.is_running());
+        let diagnostic = error_step_diagnostic(&pipeline);
+        assert_eq!(diagnostic.last_error_kind(), Some(StepErrorKind::Fatal));
+        assert!(std::error::Error::source(diagnostic.last_error.as_ref().unwrap()).is_some());
+
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_needs_reset_error_pauses_pipeline() {
+        initialize_logger();
+
+        let reset_flag = Arc::new(AtomicBool::new(false));
+        let (mut pipeline, input_sender, output_receiver) = build_error_pipeline(reset_flag.clone());
+        pipeline.start();
+
+        input_sender.send(1).unwrap();
+        sleep(std::time::Duration::from_millis(500));
+
+        assert!(reset_flag.load(Ordering::Acquire));
+        assert!(!pipeline.is_running());
+        assert_eq!(error_step_diagnostic(&pipeline).last_error_kind(), Some(StepErrorKind::NeedsReset));
+
+        pipeline.start();
+        input_sender.send(7).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 7);
+
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_invalid_topology_rejected() {
+        initialize_logger();
+
+        let pipeline = ConstructingPipeline::new(3, 1000, 1, 2, 3, 100);
+        let input_pair = mpsc::sync_channel(1);
+        let (output_sender, _output_receiver) = mpsc::channel();
+
+        let mut split = NodeBuilder::start_pipeline("test_source", Dummy1 { receiver: input_pair.1 }, &pipeline)
+            .split_begin("siso only split");
+        let mut joint = joint_begin("siso only joint", &pipeline);
+
+        split.split_add().attach("branch 1", ErrorStep { reset_flag: Arc::new(AtomicBool::new(false)) }).branch_end(&mut joint);
+        split.split_add().attach("branch 2", ErrorStep { reset_flag: Arc::new(AtomicBool::new(false)) }).branch_end(&mut joint);
+        split.split_lock(ErrorStep { reset_flag: Arc::new(AtomicBool::new(false)) });
+
+        joint.joint_lock(ErrorStep { reset_flag: Arc::new(AtomicBool::new(false)) })
+            .cap_pipeline("test_sink", Dummy3 { sender: output_sender });
+
+        match pipeline.finish_pipeline() {
+            Ok(_) => panic!("Pipeline with unsupported node shapes was accepted"),
+            Err(PipelineBuildError::InvalidTopology(violations)) => {
+                let ids: Vec<&str> = violations.iter().map(|violation| violation.node_id.as_str()).collect();
+                assert_eq!(violations.len(), 2);
+                assert!(ids.contains(&"siso only split"));
+                assert!(ids.contains(&"siso only joint"));
+                assert!(violations.iter().any(|violation| violation.required_handler == Some(StepHandler::SIMO)));
+                assert!(violations.iter().any(|violation| violation.required_handler == Some(StepHandler::MISO)));
+            },
+            Err(error) => panic!("Expected a topology error, got {}", error)
+        }
+    }
+}
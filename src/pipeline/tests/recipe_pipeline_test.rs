@@ -0,0 +1,158 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+    use crate::dsp::system_response::discrete_fd_convolution::{OverlapAddFilter, OverlapAddParameterError};
+
+
+    struct Dummy1<T: Sharable> {
+        receiver: mpsc::Receiver<T>
+    }
+    impl<T: Sharable> PipelineStep<(), T> for Dummy1<T> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<T>, StepError> {
+            match self.receiver.recv_timeout(std::time::Duration::from_millis(2000)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl<T: Sharable> Source for Dummy1<T> {}
+
+    struct Dummy2{}
+    impl PipelineStep<u32, u32> for Dummy2 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO, StepHandler::MISO, StepHandler::SIMO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + 1))
+        }
+
+        fn run_MISO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input.iter().sum()))
+        }
+
+        fn run_SIMO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + 1))
+        }
+    }
+
+    struct Dummy3<T: Sharable> {
+        sender: mpsc::Sender<T>,
+    }
+    impl<T: Sharable> PipelineStep<T, ()> for Dummy3<T> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: T) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl<T: Sharable> Sink for Dummy3<T> {}
+
+    struct ParallelAdd {}
+    impl PipelineRecipe<u32, u32> for ParallelAdd { // x -> 2x + 3
+        type Entry = u32;
+
+        fn construct<F: Sharable>(self, builder: NodeBuilder<u32, u32>) -> NodeBuilder<u32, F> {
+            let mut joint = builder.joint_begin("joint");
+            let mut split = builder.split_begin("split");
+
+            split.split_add().attach("branch", Dummy2 {}).branch_end(&mut joint);
+            split.split_add().branch_end(&mut joint);
+            split.split_lock(Dummy2 {});
+
+            joint.joint_lock(Dummy2 {})
+        }
+    }
+
+    struct Chain {}
+    impl PipelineRecipe<u32, u32> for Chain { // x -> 2x + 5
+        type Entry = u32;
+
+        fn construct<F: Sharable>(self, builder: NodeBuilder<u32, u32>) -> NodeBuilder<u32, F> {
+            builder.attach("pre", Dummy2 {})
+                .recipe("inner", ParallelAdd {})
+        }
+    }
+
+    #[test]
+    fn test_nested_recipe_pipeline() {
+        initialize_logger();
+
+        let pipeline = ConstructingPipeline::new(3, 1000, 1, 2, 3, 100);
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("test_source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .recipe("rx", Chain {})
+            .attach("post", Dummy2 {})
+            .cap_pipeline("test_sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+
+        let graph = pipeline.get_graph();
+        for id in ["test_source", "rx/pre", "rx/inner/split", "rx/inner/branch", "rx/inner/joint", "post", "test_sink"] {
+            assert!(graph.find_node(id).is_some(), "missing node {}", id);
+        }
+        assert_eq!(graph.nodes().len(), 7);
+
+        pipeline.start();
+        for value in 0..5 {
+            input_sender.send(value).unwrap();
+            assert_eq!(output_receiver.recv().unwrap(), 2 * value + 6);
+        }
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_overlap_add_recipe() {
+        initialize_logger();
+
+        let impulse_response = vec![1.0, 0.5, -0.5, 2.0];
+        let input: Vec<f32> = (0..20).map(|x| x as f32).collect();
+
+        let mut expected = vec![0.0; input.len() + impulse_response.len() - 1];
+        for (n, x) in input.iter().enumerate() {
+            for (k, h) in impulse_response.iter().enumerate() {
+                expected[n + k] += x * h;
+            }
+        }
+
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100);
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("test_source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .recipe("filter", OverlapAddFilter::new(10, 5, impulse_response).unwrap())
+            .cap_pipeline("test_sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        assert!(pipeline.get_graph().find_node("filter/combiner").is_some());
+        pipeline.start();
+
+        // two blocks, the overlap of the first is carried into the second and the last one is flushed once the stream ends
+        input_sender.send(input[..10].to_vec()).unwrap();
+        input_sender.send(input[10..].to_vec()).unwrap();
+        let mut result: Vec<f32> = output_receiver.recv().unwrap();
+        assert_eq!(result.len(), 10);
+
+        assert!(pipeline.drain_and_stop(std::time::Duration::from_secs(10)));
+        result.extend(output_receiver.try_iter().flatten());
+
+        assert_eq!(result.len(), expected.len());
+        for (actual, expected) in result.iter().zip(expected.iter()) {
+            assert!((actual - expected).abs() < 1e-4, "{} != {}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_overlap_add_rejects_parameters() {
+        let impulse_response = vec![1.0, 0.5, -0.5, 2.0];
+
+        assert!(matches!(OverlapAddFilter::new(12, 5, impulse_response.clone()).err(), Some(OverlapAddParameterError::ChunkSizeError(_))));
+        assert!(matches!(OverlapAddFilter::new(10, 5, vec![1.0; 6]).err(), Some(OverlapAddParameterError::ImpulseResponseError(_))));
+        assert!(matches!(OverlapAddFilter::new(12, 4, impulse_response).err(), Some(OverlapAddParameterError::FftSizeError(_))));
+    }
+}
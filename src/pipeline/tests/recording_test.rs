@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::path::PathBuf;
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant, SystemTime};
+    use num::Complex;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+    use crate::pipeline::sources::vector_source::VectorSource;
+
+
+    struct Gain { // multiplies every sample by a fixed gain
+        gain: f32
+    }
+    impl PipelineStep<Vec<f32>, Vec<f32>> for Gain {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<Vec<f32>>, StepError> {
+            Ok(ODFormat::Standard(input.iter().map(|sample| sample * self.gain).collect()))
+        }
+    }
+
+    struct ChunkSource { // emits each buffer as a series of chunks of two samples
+        buffers: Vec<Vec<Complex<f32>>>
+    }
+    impl PipelineStep<(), Vec<Complex<f32>>> for ChunkSource {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<Vec<Complex<f32>>>, StepError> {
+            if self.buffers.is_empty() { return Err(StepError::end_of_stream()) }
+            Ok(ODFormat::Series(self.buffers.remove(0).chunks(2).map(|chunk| chunk.to_vec()).collect()))
+        }
+    }
+    impl Source for ChunkSource {}
+
+    struct ChunkSum {} // adds the reassembled chunks up sample by sample
+    impl PipelineStep<Vec<Complex<f32>>, Vec<Complex<f32>>> for ChunkSum {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::REASO] }
+
+        fn run_REASO(&mut self, input: Vec<Vec<Complex<f32>>>) -> Result<ODFormat<Vec<Complex<f32>>>, StepError> {
+            Ok(ODFormat::Standard(input.iter().skip(1).fold(input[0].clone(), |sum, chunk| sum.iter().zip(chunk).map(|(a, b)| a + b).collect())))
+        }
+    }
+
+    struct Collector<T> {
+        sender: mpsc::Sender<T>,
+    }
+    impl<T: Sharable> PipelineStep<T, ()> for Collector<T> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: T) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl<T> Sink for Collector<T> {}
+
+    fn recording_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("zubr_recording_test_{}_{}.zrec", name, std::process::id()))
+    }
+
+    fn standard<T: Sharable>(output: Result<ODFormat<T>, StepError>) -> T {
+        match output {
+            Ok(ODFormat::Standard(value)) => value,
+            _ => panic!("replayed step did not return a single value")
+        }
+    }
+
+    #[test]
+    fn test_record_and_replay_step() {
+        initialize_logger();
+        let path = recording_path("gain");
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100).with_execution_model(ExecutionModel::Deterministic);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", VectorSource::new(vec![vec![1.0, 2.0], vec![3.0], vec![4.0, 5.0, 6.0]]), &pipeline)
+            .attach("gain", Gain { gain: 2.0 })
+            .cap_pipeline("sink", Collector { sender: output_sender });
+
+        let mut executor = pipeline.finish_pipeline().unwrap().into_executor();
+        let recorder = executor.record_inputs::<Vec<f32>>("gain", &path).unwrap();
+        executor.run(3);
+        executor.drain();
+        assert_eq!(recorder.stop().unwrap(), RecordingSummary { records: 4, dropped: 0 });
+
+        let recording = Recording::<Vec<f32>>::load(&path).unwrap();
+        assert_eq!(recording.node_id, "gain");
+        assert!(matches!(recording.inputs.last().unwrap().input, ReceiveType::EndOfStream));
+        assert!(recording.inputs.windows(2).all(|pair| pair[0].timestamp <= pair[1].timestamp));
+
+        // the step sees exactly what it saw in the pipeline, so it gives exactly the same outputs
+        let replayed: Vec<Vec<f32>> = replay_step(&mut Gain { gain: 2.0 }, &recording).into_iter().map(standard).collect();
+        assert_eq!(replayed, output_receiver.try_iter().collect::<Vec<Vec<f32>>>());
+        assert_eq!(replayed, vec![vec![2.0, 4.0], vec![6.0], vec![8.0, 10.0, 12.0]]);
+        executor.kill();
+
+        assert!(matches!(Recording::<Vec<u8>>::load(&path), Err(RecordingError::Format(_))));
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(matches!(Recording::<Vec<f32>>::read_from(&bytes[..bytes.len() - 3]), Err(RecordingError::Format(_))));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_replay_reassembled_into_pipeline() {
+        initialize_logger();
+        let path = recording_path("sum");
+        let buffers = vec![
+            vec![Complex::new(1.0, 0.0), Complex::new(0.0, 1.0), Complex::new(2.0, 0.0), Complex::new(0.0, 2.0)],
+            vec![Complex::new(0.5, 0.5), Complex::new(1.0, 1.0), Complex::new(0.5, 0.5), Complex::new(1.0, 1.0)]
+        ];
+
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100).with_execution_model(ExecutionModel::Deterministic);
+        let (output_sender, output_receiver) = mpsc::channel();
+        NodeBuilder::start_pipeline("source", ChunkSource { buffers }, &pipeline)
+            .add_reassembler(2)
+            .attach("sum", ChunkSum {})
+            .cap_pipeline("sink", Collector { sender: output_sender });
+
+        let mut executor = pipeline.finish_pipeline().unwrap().into_executor();
+        assert!(matches!(executor.record_inputs::<Vec<f32>>("sum", &path), Err(RecordingError::Tap(TapError::WrongType { .. }))));
+        let recorder = executor.record_inputs::<Vec<Complex<f32>>>("sum", &path).unwrap();
+        executor.run(2);
+        executor.drain();
+        recorder.stop().unwrap();
+        let original: Vec<Vec<Complex<f32>>> = output_receiver.try_iter().collect();
+        executor.kill();
+
+        let recording = Recording::<Vec<Complex<f32>>>::load(&path).unwrap();
+        assert!(matches!(&recording.inputs[0].input, ReceiveType::Reassembled(chunks) if chunks.len() == 2));
+
+        // a fresh pipeline around a fresh step, fed from the recording instead of the original source
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100).with_execution_model(ExecutionModel::Deterministic);
+        let (replay_sender, replay_receiver) = mpsc::channel();
+        NodeBuilder::start_pipeline("replay", ReplaySource::new(recording), &pipeline)
+            .add_reassembler(2)
+            .attach("sum", ChunkSum {})
+            .cap_pipeline("sink", Collector { sender: replay_sender });
+
+        let mut executor = pipeline.finish_pipeline().unwrap().into_executor();
+        executor.run(3);
+        assert_eq!(replay_receiver.try_iter().collect::<Vec<Vec<Complex<f32>>>>(), original);
+        assert_eq!(original, vec![vec![Complex::new(3.0, 0.0), Complex::new(0.0, 3.0)], vec![Complex::new(1.0, 1.0), Complex::new(2.0, 2.0)]]);
+        executor.kill();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_paced_replay_keeps_the_gaps() {
+        let record = |millis: u64, value: f32| RecordedInput { timestamp: Duration::from_millis(millis), input: ReceiveType::Single(vec![value]) };
+        let recording = Recording { node_id: String::from("gain"), started: SystemTime::now(), inputs: vec![record(2000, 1.0), record(2050, 2.0)] };
+        let mut replay = ReplaySource::new(recording).with_pacing();
+
+        let start = Instant::now();
+        assert_eq!(standard(replay.run_DISO()), vec![1.0]);
+        assert!(start.elapsed() < Duration::from_millis(500), "the wait before the first record is not replayed");
+        let second = Instant::now();
+        assert_eq!(standard(replay.run_DISO()), vec![2.0]);
+        assert!(second.elapsed() >= Duration::from_millis(40));
+    }
+}
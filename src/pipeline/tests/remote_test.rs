@@ -0,0 +1,227 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpStream;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::{mpsc, Arc};
+    use std::thread::sleep;
+    use std::time::Duration;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+
+
+    fn loopback() -> RemoteAddress {
+        RemoteAddress::Tcp("127.0.0.1:0".parse().unwrap())
+    }
+
+    struct Dummy1 {
+        receiver: mpsc::Receiver<Vec<f32>>
+    }
+    impl PipelineStep<(), Vec<f32>> for Dummy1 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<Vec<f32>>, StepError> {
+            match self.receiver.recv_timeout(Duration::from_millis(100)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for Dummy1 {}
+
+    struct Gain { // scales its input, the gain is set through control messages
+        gain: f32
+    }
+    impl PipelineStep<Vec<f32>, Vec<f32>> for Gain {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<Vec<f32>>, StepError> {
+            Ok(ODFormat::Standard(input.iter().map(|value| value * self.gain).collect()))
+        }
+        fn handle_control(&mut self, message: &ControlMessage) -> Result<(), String> {
+            match message.key.as_str() {
+                "gain" => { self.gain = message.get_f32()?; Ok(()) },
+                key => Err(format!("unknown control '{}'", key))
+            }
+        }
+    }
+
+    struct Dummy3 {
+        sender: mpsc::Sender<Vec<f32>>,
+    }
+    impl PipelineStep<Vec<f32>, ()> for Dummy3 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: Vec<f32>) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for Dummy3 {}
+
+    fn build() -> (ActivePipeline, mpsc::SyncSender<Vec<f32>>, mpsc::Receiver<Vec<f32>>) {
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100);
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .attach("gain", Gain { gain: 1.0 })
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        (pipeline.finish_pipeline().unwrap(), input_sender, output_receiver)
+    }
+
+    #[test]
+    fn test_remote_lifecycle() {
+        initialize_logger();
+        let (pipeline, input_sender, output_receiver) = build();
+        let server = RemoteServer::bind(&[loopback()]).unwrap().serve(pipeline);
+        let mut client = RemoteClient::connect(&server.addresses()[0]).unwrap();
+
+        assert_eq!(client.status().unwrap(), RemoteStatus { running: false, killed: false, nodes: 3 });
+        client.start().unwrap();
+        assert!(client.status().unwrap().running);
+
+        input_sender.send(vec![1.0, 2.0]).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), vec![1.0, 2.0]);
+
+        client.send_control("gain", ControlMessage::new("gain", ControlValue::Float(2.0))).unwrap();
+        input_sender.send(vec![1.0, 2.0]).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), vec![2.0, 4.0]);
+        assert!(matches!(client.send_control("gain", ControlMessage::new("volume", ControlValue::Flag)), Err(RemoteError::Rejected { .. })));
+        assert!(matches!(client.send_control("missing", ControlMessage::new("gain", ControlValue::Float(1.0))), Err(RemoteError::Rejected { .. })));
+
+        let diagnostics = client.diagnostics().unwrap();
+        let gain = diagnostics.iter().find(|diagnostic| diagnostic.id == "gain").unwrap();
+        assert_eq!(gain.total_items_in, 2);
+        assert_eq!(client.graph().unwrap()["nodes"].as_array().unwrap().len(), 3);
+
+        client.stop().unwrap();
+        assert!(!client.status().unwrap().running);
+
+        client.kill().unwrap();
+        assert!(client.status().unwrap().killed);
+        assert!(matches!(client.start(), Err(RemoteError::Rejected { .. })));
+        assert!(server.is_killed());
+        assert!(server.shutdown().is_none());
+    }
+
+    #[test]
+    fn test_remote_tap_over_unix_socket() {
+        initialize_logger();
+        let path = std::env::temp_dir().join(format!("zubr-remote-{}.sock", std::process::id()));
+        let (pipeline, input_sender, _output_receiver) = build();
+        let server = RemoteServer::bind(&[RemoteAddress::Unix(path.clone())]).unwrap()
+            .with_tap::<Vec<f32>>("gain")
+            .serve(pipeline);
+        let mut client = RemoteClient::connect(&RemoteAddress::Unix(path.clone())).unwrap();
+
+        assert!(client.subscribe::<Vec<f32>>("sink", TapOptions::default()).is_err(), "only exposed nodes can be tapped");
+        assert!(matches!(client.subscribe::<Vec<u8>>("gain", TapOptions::default()), Err(RemoteError::Protocol(_))));
+        assert!(matches!(client.subscribe::<Vec<f32>>("gain", TapOptions::new(usize::MAX)), Err(RemoteError::Rejected { .. })));
+
+        let tap = client.subscribe::<Vec<f32>>("gain", TapOptions::new(2)).unwrap();
+        client.start().unwrap();
+        for value in 0..4 {
+            input_sender.send(vec![value as f32, 0.5]).unwrap();
+        }
+        sleep(Duration::from_millis(200));
+
+        let batch = client.read_tap(&tap, 16).unwrap();
+        let buffers: Vec<Vec<f32>> = batch.buffers.into_iter().map(|buffer| buffer.unwrap_standard()).collect();
+        assert_eq!(buffers, vec![vec![2.0, 0.5], vec![3.0, 0.5]]); // the oldest were discarded once the subscription was full
+        assert_eq!(batch.dropped, 2);
+        client.unsubscribe(tap).unwrap();
+
+        let pipeline = server.shutdown().unwrap();
+        pipeline.kill();
+        assert!(!path.exists(), "the socket file is removed on shutdown");
+    }
+
+    #[test]
+    fn test_remote_select_branch() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 200, 1, 5, 0, 20);
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+        let selector = Arc::new(AtomicUsize::new(0));
+
+        let mut multiplexer = NodeBuilder::start_pipeline("source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .mutltiplexer_begin("multiplexer", selector.clone());
+        let mut demultiplexer = demultiplexer_begin("demultiplexer", selector.clone(), &pipeline);
+        for (id, gain) in [("branch 0", 1.0), ("branch 1", 10.0)] {
+            multiplexer.multiplexer_add()
+                .attach(id, Gain { gain })
+                .multiplex_branch_end(&mut demultiplexer);
+        }
+        multiplexer.multiplexer_lock(Gain { gain: 1.0 });
+        demultiplexer.demultiplexer_lock(Gain { gain: 1.0 })
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let server = RemoteServer::bind(&[loopback()]).unwrap().serve(pipeline.finish_pipeline().unwrap());
+        let mut client = RemoteClient::connect(&server.addresses()[0]).unwrap();
+        client.start().unwrap();
+
+        input_sender.send(vec![1.0]).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), vec![1.0]);
+
+        client.select_branch("multiplexer", 1).unwrap();
+        assert_eq!(server.with_pipeline(|pipeline| pipeline.selected_branch("multiplexer").unwrap()), Some(1));
+        input_sender.send(vec![1.0]).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), vec![10.0]);
+
+        assert!(matches!(client.select_branch("multiplexer", 2), Err(RemoteError::Rejected { .. })));
+        assert!(matches!(client.select_branch("sink", 0), Err(RemoteError::Rejected { .. })));
+        server.shutdown().unwrap().kill();
+    }
+
+    #[test]
+    fn test_raw_protocol() {
+        initialize_logger();
+        let (pipeline, _input_sender, _output_receiver) = build();
+        let server = RemoteServer::bind(&[loopback()]).unwrap().serve(pipeline);
+        let address = match &server.addresses()[0] {
+            RemoteAddress::Tcp(address) => *address,
+            _ => unreachable!()
+        };
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut exchange = |request: &str| {
+            stream.write_all(format!("{}\n", request).as_bytes()).unwrap();
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            serde_json::from_str::<serde_json::Value>(&line).unwrap()
+        };
+
+        let reply = exchange(r#"{"command": "status"}"#);
+        assert_eq!(reply["ok"], true);
+        assert_eq!(reply["result"]["nodes"], 3);
+        assert_eq!(exchange(r#"{"command": "start"}"#)["ok"], true);
+
+        let reply = exchange(r#"{"command": "control", "node": "gain", "key": "gain", "value": {"Float": 0.5}}"#);
+        assert_eq!(reply["ok"], true);
+
+        let reply = exchange(r#"{"command": "launch"}"#);
+        assert_eq!(reply["ok"], false);
+        assert!(reply["error"].as_str().unwrap().starts_with("invalid request"));
+
+        server.shutdown().unwrap().kill();
+    }
+
+    #[test]
+    fn test_only_loopback() {
+        let address = RemoteAddress::Tcp("0.0.0.0:0".parse().unwrap());
+        assert!(matches!(RemoteServer::bind(&[address]), Err(RemoteError::NotLoopback(_))));
+    }
+
+    #[test]
+    fn test_unix_path_not_a_socket() {
+        let path = std::env::temp_dir().join(format!("zubr-remote-file-{}.sock", std::process::id()));
+        std::fs::write(&path, b"not a socket").unwrap();
+
+        assert!(matches!(RemoteServer::bind(&[RemoteAddress::Unix(path.clone())]), Err(RemoteError::AddressInUse(_))));
+        assert_eq!(std::fs::read(&path).unwrap(), b"not a socket", "a file which is not a socket is never removed");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,177 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+
+
+    struct Dummy1 {
+        receiver: mpsc::Receiver<u32>
+    }
+    impl PipelineStep<(), u32> for Dummy1 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
+            match self.receiver.recv_timeout(std::time::Duration::from_millis(200)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for Dummy1 {}
+
+    struct Dummy2{}
+    impl PipelineStep<u32, u32> for Dummy2 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO, StepHandler::MISO, StepHandler::SIMO, StepHandler::REASO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Series(vec![input, input + 1]))
+        }
+
+        fn run_MISO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input.iter().sum()))
+        }
+
+        fn run_SIMO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + 1))
+        }
+
+        fn run_REASO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input.iter().sum()))
+        }
+    }
+
+    struct Dummy4{}
+    impl PipelineStep<u32, u32> for Dummy4 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + 1))
+        }
+    }
+
+    struct Dummy3 {
+        sender: mpsc::Sender<u32>,
+    }
+    impl PipelineStep<u32, ()> for Dummy3 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for Dummy3 {}
+
+    #[test]
+    fn test_pool_split_pipeline() {
+        initialize_logger();
+        // 7 nodes on 2 workers, the source holds one worker while it waits for input so everything else shares the other
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100).with_execution_model(ExecutionModel::ThreadPool(2));
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        let mut split = NodeBuilder::start_pipeline("test_source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .split_begin("test_split");
+        let mut joint = joint_begin("test_joint", &pipeline);
+
+        split.split_add().attach("series node", Dummy2 {}).add_reassembler(2).attach("sum node", Dummy2 {}).branch_end(&mut joint);
+        split.split_add().branch_end(&mut joint);
+        split.split_lock(Dummy2 {});
+
+        joint.joint_lock(Dummy2 {})
+            .cap_pipeline("test_sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        for value in 0..20 {
+            input_sender.send(value).unwrap();
+            assert_eq!(output_receiver.recv().unwrap(), 3 * value + 4);
+        }
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_pool_feedback_pipeline() {
+        initialize_logger();
+        // same loop as the feedback pipeline test, the feedback input must count as ready before anything was fed back
+        // compute errors are unlimited since the source keeps timing out while the joint waits out its first feedback receive
+        let mut pipeline = ConstructingPipeline::new(3, 1000, 1, 2, 0, 100).with_execution_model(ExecutionModel::ThreadPool(3));
+
+        let input_pair = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        let mut feedback_joint = joint_feedback_begin("Test Feedback Joint", &mut pipeline);
+
+        NodeBuilder::start_pipeline("test_source", Dummy1 { receiver: input_pair.1 }, &mut pipeline)
+            .attach("step 1", Dummy4 {})
+            .branch_end(&mut feedback_joint);
+
+        let lazy_node = feedback_joint.joint_add_lazy();
+
+        let mut test_split = feedback_joint.joint_lock(Dummy2 {})
+            .split_begin("Test Split");
+
+        test_split.split_add()
+            .cap_pipeline("Exit", Dummy3 { sender: output_sender });
+
+        lazy_node.joint_link_lazy("Feedback Node", Dummy4 {}, test_split.split_add());
+
+        test_split.split_lock(Dummy2 {});
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        // 1 -> 2, joined with the default 0 and split to 3
+        input_pair.0.send(1).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 3);
+
+        // the feedback node turns 3 into 4, joined with the next 2 then split to 7
+        input_pair.0.send(1).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 7);
+
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_pool_pause_resume_and_diagnostics() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100).with_execution_model(ExecutionModel::ThreadPool(2));
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("test_source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .attach("series node", Dummy2 {})
+            .cap_pipeline("test_sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        input_sender.send(5).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 5);
+        assert_eq!(output_receiver.recv().unwrap(), 6);
+
+        pipeline.stop();
+        std::thread::sleep(Duration::from_millis(500)); // the source may be inside its receive timeout when the pause arrives
+        assert!(!pipeline.is_running());
+
+        input_sender.send(7).unwrap();
+        assert!(output_receiver.recv_timeout(Duration::from_millis(500)).is_err(), "paused pipeline should not process input");
+
+        let diagnostics = pipeline.get_thread_diagnostics();
+        assert_eq!(diagnostics.len(), 3);
+        for diagnostic in diagnostics.iter() {
+            assert_eq!(diagnostic.thread_state, ThreadStateSpace::PAUSED);
+        }
+        let sink = diagnostics.iter().find(|diagnostic| diagnostic.id == "test_sink").unwrap();
+        assert_eq!(sink.metrics.total_items_in, 2);
+
+        pipeline.start();
+        assert_eq!(output_receiver.recv().unwrap(), 7);
+        assert_eq!(output_receiver.recv().unwrap(), 8);
+
+        pipeline.kill();
+    }
+}
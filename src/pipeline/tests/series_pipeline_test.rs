@@ -13,10 +13,12 @@ mod pipeline_tests {
         receiver: mpsc::Receiver<u32>
     }
     impl PipelineStep<(), u32> for Dummy1 {
-        fn run_DISO(&mut self) -> Result<ODFormat<u32>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
             match self.receiver.recv_timeout(std::time::Duration::from_millis(2000)) {
                 Ok(val) => Ok(ODFormat::Standard(val + 1)),
-                Err(_) => Err("Timeout error".to_string())
+                Err(_) => Err(StepError::recoverable("Timeout error"))
             }
         }
     }
@@ -24,18 +26,22 @@ mod pipeline_tests {
 
     struct Dummy2a{}
     impl PipelineStep<u32, u32> for Dummy2a {
-        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO, StepHandler::REASO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
             Ok(ODFormat::Standard(input + 1))
         }
 
-        fn run_REASO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, String> {
+        fn run_REASO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
             Ok(ODFormat::Standard(input.iter().sum()))
         }
     }
 
     struct Dummy2b{}
     impl PipelineStep<u32, u32> for Dummy2b {
-        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
             Ok(ODFormat::Series(vec![input + 1, input + 2, input + 3]))
         }
     }
@@ -44,7 +50,9 @@ mod pipeline_tests {
         sender: mpsc::Sender<u32>,
     }
     impl PipelineStep<u32, ()> for Dummy3 {
-        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
             self.sender.send(input).unwrap();
             Ok(ODFormat::Standard(()))
         }
@@ -69,7 +77,7 @@ mod pipeline_tests {
             .attach("Series aggregator", Dummy2a {})
             .cap_pipeline("Series test sink", Dummy3 {sender: output_sender});
 
-        let mut pipeline = pipeline.finish_pipeline();
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
         log_message(format!("Finished pipeline construction"), Level::Debug);
         
         pipeline.start();
@@ -12,10 +12,12 @@ mod pipeline_tests {
         receiver: mpsc::Receiver<Vec<u32>>
     }
     impl PipelineStep<(), Vec<u32>> for Dummy1a {
-        fn run_DISO(&mut self) -> Result<ODFormat<Vec<u32>>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<Vec<u32>>, StepError> {
             match self.receiver.recv_timeout(std::time::Duration::from_millis(2000)) {
                 Ok(val) => Ok(ODFormat::Standard(val)),
-                Err(_) => Err("Timeout error".to_string())
+                Err(_) => Err(StepError::recoverable("Timeout error"))
             }
         }
     }
@@ -25,10 +27,12 @@ mod pipeline_tests {
         receiver: mpsc::Receiver<u32>
     }
     impl PipelineStep<(), u32> for Dummy1b {
-        fn run_DISO(&mut self) -> Result<ODFormat<u32>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
             match self.receiver.recv_timeout(std::time::Duration::from_millis(2000)) {
                 Ok(val) => Ok(ODFormat::Standard(val + 1)),
-                Err(_) => Err("Timeout error".to_string())
+                Err(_) => Err(StepError::recoverable("Timeout error"))
             }
         }
     }
@@ -36,14 +40,18 @@ mod pipeline_tests {
 
     struct Dummy2a{}
     impl PipelineStep<Vec<u32>, u32> for Dummy2a {
-        fn run_SIMO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIMO] }
+
+        fn run_SIMO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
             Ok(ODFormat::Decompose(input))
         }
     }
 
     struct Dummy2b{}
     impl PipelineStep<u32, u32> for Dummy2b {
-        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
             Ok(ODFormat::Repeat(input + 1, 3))
         }
     }
@@ -52,7 +60,9 @@ mod pipeline_tests {
         sender: mpsc::Sender<u32>,
     }
     impl PipelineStep<u32, ()> for Dummy3 {
-        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, String> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
             self.sender.send(input).unwrap();
             Ok(ODFormat::Standard(()))
         }
@@ -85,7 +95,7 @@ mod pipeline_tests {
 
         log_message("Pipeline Path Designed".to_string(), Level::Debug);
 
-        let mut pipeline = pipeline.finish_pipeline();
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
 
         log_message("Pipeline finished construction".to_string(), Level::Debug);
 
@@ -132,7 +142,7 @@ mod pipeline_tests {
 
         log_message("Pipeline Path Designed".to_string(), Level::Debug);
 
-        let mut pipeline = pipeline.finish_pipeline();
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
 
         log_message("Pipeline finished construction".to_string(), Level::Debug);
 
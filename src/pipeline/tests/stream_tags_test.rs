@@ -0,0 +1,219 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use crate::dsp::sampling::decimation::Decimator;
+    use crate::dsp::sampling::upsample::Upsampler;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+    use crate::pipeline::sources::vector_source::VectorSource;
+
+
+    struct FrameMarker { // tags the first sample of every value it passes on with the count of values seen so far
+        count: i64
+    }
+    impl<T: Sharable> PipelineStep<T, T> for FrameMarker {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: T) -> Result<ODFormat<T>, StepError> {
+            Ok(ODFormat::Standard(input))
+        }
+
+        fn tag_behavior(&mut self, tags: &mut Vec<StreamTag>) {
+            tags.push(StreamTag::new(tag_keys::START_OF_FRAME, 0, TagValue::Integer(self.count)));
+            self.count += 1;
+        }
+    }
+
+    struct SeriesStep{}
+    impl PipelineStep<u32, u32> for SeriesStep {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Series(vec![input + 1, input + 2, input + 3]))
+        }
+
+        fn tag_behavior(&mut self, tags: &mut Vec<StreamTag>) { // marks the last value of the series
+            tags.push(StreamTag::flag("last", 2));
+        }
+    }
+
+    struct Dummy2 {
+        branch_tag: Option<&'static str>
+    }
+    impl PipelineStep<u32, u32> for Dummy2 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO, StepHandler::MISO, StepHandler::SIMO, StepHandler::REASO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + 1))
+        }
+
+        fn run_MISO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input.iter().sum()))
+        }
+
+        fn run_SIMO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input + 1))
+        }
+
+        fn run_REASO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input.iter().sum()))
+        }
+
+        fn tag_behavior(&mut self, tags: &mut Vec<StreamTag>) {
+            if let Some(key) = self.branch_tag {
+                tags.push(StreamTag::flag(key, 0));
+            }
+        }
+    }
+
+    struct TagRecorder<T: Sharable> { // sends every value it consumes along with the tags it arrived with
+        sender: mpsc::Sender<(T, Vec<StreamTag>)>,
+        tags: Vec<StreamTag>
+    }
+    impl<T: Sharable> PipelineStep<T, ()> for TagRecorder<T> {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: T) -> Result<ODFormat<()>, StepError> {
+            self.sender.send((input, std::mem::take(&mut self.tags))).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+
+        fn tag_behavior(&mut self, tags: &mut Vec<StreamTag>) {
+            self.tags = tags.clone();
+        }
+    }
+
+    struct OddRejecter {} // fails on odd values, which loses them along with their tags
+    impl PipelineStep<u32, u32> for OddRejecter {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            match input % 2 {
+                0 => Ok(ODFormat::Standard(input)),
+                _ => Err(StepError::recoverable("odd value"))
+            }
+        }
+    }
+    impl<T: Sharable> Sink for TagRecorder<T> {}
+
+    fn frame(count: i64) -> StreamTag {
+        StreamTag::new(tag_keys::START_OF_FRAME, 0, TagValue::Integer(count))
+    }
+
+    #[test]
+    fn test_tags_through_series_and_reassembler() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", VectorSource::new(vec![1u32, 2]), &pipeline)
+            .attach("marker", FrameMarker { count: 0 })
+            .attach("series expander", SeriesStep {})
+            .add_reassembler(3)
+            .attach("series sum", Dummy2 { branch_tag: None })
+            .cap_pipeline("sink", TagRecorder { sender: output_sender, tags: Vec::new() });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+        assert!(pipeline.wait_for_end_of_stream(Duration::from_secs(10)));
+
+        // the frame tag stays on the first value of the series and the last tag on the third, so both come back at the same offsets once reassembled
+        let received = output_receiver.try_iter().collect::<Vec<(u32, Vec<StreamTag>)>>();
+        assert_eq!(received, vec![
+            (9, vec![frame(0), StreamTag::flag("last", 2)]),
+            (12, vec![frame(1), StreamTag::flag("last", 2)]),
+        ]);
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_tags_merged_at_joint() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        let mut split = NodeBuilder::start_pipeline("source", VectorSource::new(vec![1u32, 2]), &pipeline)
+            .attach("marker", FrameMarker { count: 0 })
+            .split_begin("split");
+        let mut joint = joint_begin("joint", &pipeline);
+
+        split.split_add().attach("branch 1", Dummy2 { branch_tag: Some("branch 1") }).branch_end(&mut joint);
+        split.split_add().attach("branch 2", Dummy2 { branch_tag: None }).branch_end(&mut joint);
+        split.split_lock(Dummy2 { branch_tag: None });
+
+        joint.joint_lock(Dummy2 { branch_tag: None })
+            .cap_pipeline("sink", TagRecorder { sender: output_sender, tags: Vec::new() });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+        assert!(pipeline.wait_for_end_of_stream(Duration::from_secs(10)));
+
+        // the frame tag went down both branches but reaches the sink once, next to the tag only one branch added
+        let received = output_receiver.try_iter().collect::<Vec<(u32, Vec<StreamTag>)>>();
+        assert_eq!(received, vec![
+            (6, vec![frame(0), StreamTag::flag("branch 1", 0)]),
+            (8, vec![frame(1), StreamTag::flag("branch 1", 0)]),
+        ]);
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_tags_dropped_with_failed_call() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", VectorSource::new(vec![1u32, 2, 3, 4]), &pipeline)
+            .attach("marker", FrameMarker { count: 0 })
+            .attach("rejecter", OddRejecter {})
+            .cap_pipeline("sink", TagRecorder { sender: output_sender, tags: Vec::new() });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+        assert!(pipeline.wait_for_end_of_stream(Duration::from_secs(10)));
+
+        // the frames of the rejected values never reach the sink, so they cannot land on the values that follow
+        let received = output_receiver.try_iter().collect::<Vec<(u32, Vec<StreamTag>)>>();
+        assert_eq!(received, vec![(2, vec![frame(1)]), (4, vec![frame(3)])]);
+        pipeline.kill();
+    }
+
+    struct OffsetMarker {} // tags the sample at offset 6 of every buffer
+    impl PipelineStep<Vec<f32>, Vec<f32>> for OffsetMarker {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<Vec<f32>>, StepError> {
+            Ok(ODFormat::Standard(input))
+        }
+
+        fn tag_behavior(&mut self, tags: &mut Vec<StreamTag>) {
+            tags.push(StreamTag::new(tag_keys::TIMESTAMP, 6, TagValue::Float(1.5)));
+        }
+    }
+
+    #[test]
+    fn test_sampling_steps_rescale_tags() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100);
+        let (output_sender, output_receiver) = mpsc::channel();
+        let buffer = (0..12).map(|sample| sample as f32).collect::<Vec<f32>>();
+
+        NodeBuilder::start_pipeline("source", VectorSource::new(vec![buffer]), &pipeline)
+            .attach("marker", OffsetMarker {})
+            .attach("decimator", Decimator::new(3).unwrap())
+            .attach("upsampler", Upsampler::new(2).unwrap())
+            .cap_pipeline("sink", TagRecorder { sender: output_sender, tags: Vec::new() });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+        assert!(pipeline.wait_for_end_of_stream(Duration::from_secs(10)));
+
+        // sample 6 is the third one kept by the decimator, which the upsampler then moves to offset 4
+        let (samples, tags) = output_receiver.try_iter().next().unwrap();
+        assert_eq!(samples, vec![0.0, 0.0, 3.0, 0.0, 6.0, 0.0, 9.0, 0.0]);
+        assert_eq!(tags, vec![StreamTag::new(tag_keys::TIMESTAMP, 4, TagValue::Float(1.5))]);
+        assert_eq!(samples[tags[0].offset], 6.0);
+        pipeline.kill();
+    }
+}
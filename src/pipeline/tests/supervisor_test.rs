@@ -0,0 +1,145 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use std::thread::sleep;
+    use std::time::{Duration, Instant};
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+    use crate::pipeline::sources::vector_source::VectorSource;
+
+
+    struct Dummy1 {
+        receiver: mpsc::Receiver<u32>
+    }
+    impl PipelineStep<(), u32> for Dummy1 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
+            match self.receiver.recv_timeout(Duration::from_millis(100)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for Dummy1 {}
+
+    struct FaultyStep { // panics on one value and takes a long time over another
+        panic_on: u32,
+        stall_on: u32
+    }
+    impl PipelineStep<u32, u32> for FaultyStep {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            if input == self.panic_on { panic!("bad value {}", input) }
+            if input == self.stall_on { sleep(Duration::from_millis(1000)) }
+            Ok(ODFormat::Standard(input))
+        }
+    }
+
+    struct Dummy3 {
+        sender: mpsc::Sender<u32>,
+    }
+    impl PipelineStep<u32, ()> for Dummy3 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for Dummy3 {}
+
+    fn diagnostic(diagnostics: Vec<ThreadDiagnostic>, id: &str) -> ThreadDiagnostic {
+        diagnostics.into_iter().find(|diagnostic| diagnostic.id == id).unwrap()
+    }
+
+    fn wait_until(timeout: Duration, condition: impl Fn() -> bool) -> bool {
+        let start = Instant::now();
+        while !condition() {
+            if start.elapsed() > timeout { return false }
+            sleep(Duration::from_millis(10));
+        }
+        true
+    }
+
+    #[test]
+    fn test_deterministic_panic_restart() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100).with_execution_model(ExecutionModel::Deterministic);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", VectorSource::new(vec![1, 2, 3, 4]), &pipeline)
+            .attach("faulty", Supervised::new(SupervisionPolicy::Restart, || FaultyStep { panic_on: 3, stall_on: 0 }))
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut executor = pipeline.finish_pipeline().unwrap().into_executor();
+        executor.run(4);
+
+        // only the value the step panicked on is lost, the rest of the pipeline never noticed
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![1, 2, 4]);
+        assert!(executor.is_running());
+
+        let faulty = diagnostic(executor.get_thread_diagnostics(), "faulty");
+        assert_eq!(faulty.restarts, 1);
+        assert_eq!(faulty.panic_message, Some("bad value 3".to_string()));
+        assert_eq!(faulty.last_error_kind(), Some(StepErrorKind::Fatal));
+        executor.kill();
+    }
+
+    #[test]
+    fn test_panic_kills_pipeline_by_default() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100);
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .attach("faulty", FaultyStep { panic_on: 2, stall_on: 0 })
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        input_sender.send(1).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 1);
+        input_sender.send(2).unwrap();
+
+        assert!(wait_until(Duration::from_secs(5), || !pipeline.is_running()));
+        let faulty = diagnostic(pipeline.get_thread_diagnostics(), "faulty");
+        assert_eq!(faulty.thread_state, ThreadStateSpace::KILLED);
+        assert_eq!(faulty.panic_message, Some("bad value 2".to_string()));
+        assert_eq!(faulty.restarts, 0);
+        pipeline.kill(); // every thread, the one which panicked included, still exits and joins
+    }
+
+    #[test]
+    fn test_pool_stall_pauses_pipeline() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100)
+            .with_execution_model(ExecutionModel::ThreadPool(2))
+            .with_stall_timeout(400);
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .attach("faulty", Supervised::new(SupervisionPolicy::PausePipeline, || FaultyStep { panic_on: 0, stall_on: 2 }))
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        input_sender.send(2).unwrap();
+        // the stall is flagged just before the policy is applied, so wait on both
+        assert!(wait_until(Duration::from_secs(5), || diagnostic(pipeline.get_thread_diagnostics(), "faulty").stalled && !pipeline.is_running()));
+
+        // the step is restarted once the stalled call returns, and the pipeline carries on when it is started again
+        assert!(wait_until(Duration::from_secs(5), || diagnostic(pipeline.get_thread_diagnostics(), "faulty").restarts == 1));
+        pipeline.start();
+        input_sender.send(3).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), 2);
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), 3);
+        assert!(!diagnostic(pipeline.get_thread_diagnostics(), "faulty").stalled);
+        pipeline.kill();
+    }
+}
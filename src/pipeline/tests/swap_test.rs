@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{mpsc, Arc};
+    use std::time::Duration;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+    use crate::pipeline::sources::vector_source::VectorSource;
+
+
+    struct Dummy1 {
+        receiver: mpsc::Receiver<u32>
+    }
+    impl PipelineStep<(), u32> for Dummy1 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
+            match self.receiver.recv_timeout(Duration::from_millis(100)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for Dummy1 {}
+
+    struct Scale { // multiplies every value, and notes when it was started or killed
+        factor: u32,
+        started: Arc<AtomicBool>,
+        killed: Arc<AtomicBool>
+    }
+    impl Scale {
+        fn new(factor: u32) -> Self {
+            Scale { factor, started: Arc::new(AtomicBool::new(false)), killed: Arc::new(AtomicBool::new(false)) }
+        }
+    }
+    impl PipelineStep<u32, u32> for Scale {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input * self.factor))
+        }
+        fn start_behavior(&mut self) { self.started.store(true, Ordering::Release) }
+        fn kill_behavior(&mut self) { self.killed.store(true, Ordering::Release) }
+    }
+
+    struct Batched; // only handles reassembled input, which a single input node never gives it
+    impl PipelineStep<u32, u32> for Batched {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::REASO] }
+
+        fn run_REASO(&mut self, input: Vec<u32>) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input.iter().sum()))
+        }
+    }
+
+    struct Dummy3 {
+        sender: mpsc::Sender<u32>,
+    }
+    impl PipelineStep<u32, ()> for Dummy3 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for Dummy3 {}
+
+    #[test]
+    fn test_swap_running_node() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100);
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+        let original = Scale::new(1);
+        let original_killed = original.killed.clone();
+
+        NodeBuilder::start_pipeline("source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .attach("scale", original)
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+        input_sender.send(3).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), 3);
+
+        // the node is blocked waiting on input, the swap is taken in once its receive times out
+        let replacement = Scale::new(10);
+        let replacement_started = replacement.started.clone();
+        pipeline.swap_step("scale", replacement).unwrap().wait(Duration::from_secs(5)).unwrap();
+        assert!(original_killed.load(Ordering::Acquire));
+        assert!(replacement_started.load(Ordering::Acquire));
+
+        input_sender.send(3).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), 30);
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_swap_rejected() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100);
+        let (_input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, _output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .attach("scale", Scale::new(1))
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        let mismatch = pipeline.swap_step("sink", Scale::new(2)).err();
+        assert!(matches!(mismatch, Some(SwapError::TypeMismatch { node_id, .. }) if node_id == "sink"));
+        let unsupported = pipeline.swap_step("scale", Batched).err();
+        assert!(matches!(unsupported, Some(SwapError::UnsupportedHandler(violation)) if violation.required_handler == Some(StepHandler::SISO)));
+        assert_eq!(pipeline.swap_step("missing", Scale::new(2)).err(), Some(SwapError::UnknownNode { node_id: "missing".to_string() }));
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_deterministic_swap_between_calls() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100).with_execution_model(ExecutionModel::Deterministic);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", VectorSource::new(vec![1, 2, 3]), &pipeline)
+            .attach("scale", Scale::new(1))
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut executor = pipeline.finish_pipeline().unwrap().into_executor();
+        executor.run(1);
+
+        let ticket = executor.swap_step("scale", Scale::new(2)).unwrap();
+        assert!(ticket.try_reply().is_none());
+        executor.run(2);
+        assert_eq!(ticket.try_reply(), Some(Ok(())));
+
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![1, 4, 6]);
+        executor.kill();
+    }
+}
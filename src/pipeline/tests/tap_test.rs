@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+    use crate::pipeline::sources::vector_source::VectorSource;
+
+
+    struct Dummy1 {
+        receiver: mpsc::Receiver<u32>
+    }
+    impl PipelineStep<(), u32> for Dummy1 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
+            match self.receiver.recv_timeout(Duration::from_millis(200)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for Dummy1 {}
+
+    struct Doubler {}
+    impl PipelineStep<u32, u32> for Doubler {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> {
+            Ok(ODFormat::Standard(input * 2))
+        }
+    }
+
+    struct Dummy3 {
+        sender: mpsc::Sender<u32>,
+    }
+    impl PipelineStep<u32, ()> for Dummy3 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for Dummy3 {}
+
+    fn values(buffers: Vec<ODFormat<u32>>) -> Vec<u32> {
+        buffers.into_iter().map(|buffer| match buffer {
+            ODFormat::Standard(value) => value,
+            _ => panic!("tap returned a buffer in a different format than the step")
+        }).collect()
+    }
+
+    #[test]
+    fn test_builder_tap_with_decimation() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100).with_execution_model(ExecutionModel::Deterministic);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        let builder = NodeBuilder::start_pipeline("source", VectorSource::new(vec![1, 2, 3, 4, 5, 6]), &pipeline);
+        let every_buffer = builder.with_tap(TapOptions::new(8));
+        let every_other = builder.with_tap(TapOptions::new(8).with_decimation(2));
+        builder.attach("double", Doubler {})
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut executor = pipeline.finish_pipeline().unwrap().into_executor();
+        executor.run(6);
+
+        // the tapped buffers are copies, the pipeline itself still gets every value
+        assert_eq!(output_receiver.try_iter().collect::<Vec<u32>>(), vec![2, 4, 6, 8, 10, 12]);
+        assert_eq!(values(every_buffer.drain()), vec![2, 4, 6, 8, 10, 12]);
+        assert_eq!(values(every_other.drain()), vec![2, 6, 10]);
+        executor.kill();
+    }
+
+    #[test]
+    fn test_slow_subscriber_and_detach() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 3, 100).with_execution_model(ExecutionModel::Deterministic);
+        let (output_sender, _output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", VectorSource::new((1..10).collect()), &pipeline)
+            .attach("double", Doubler {})
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut executor = pipeline.finish_pipeline().unwrap().into_executor();
+        let mut tap = executor.tap::<u32>("source", TapOptions::new(2)).unwrap();
+        executor.run(5);
+
+        // a subscriber which fell behind only keeps the newest buffers
+        assert_eq!(tap.dropped(), 3);
+        assert_eq!(values(tap.drain()), vec![4, 5]);
+
+        tap.detach();
+        executor.run(2);
+        assert!(tap.try_recv().is_none());
+        tap.reattach();
+        executor.run(2);
+        assert_eq!(values(tap.drain()), vec![8, 9]);
+
+        assert_eq!(executor.tap::<u32>("missing", TapOptions::default()).err(), Some(TapError::UnknownNode { node_id: "missing".to_string() }));
+        assert!(matches!(executor.tap::<f32>("double", TapOptions::default()), Err(TapError::WrongType { .. })));
+        executor.kill();
+    }
+
+    #[test]
+    fn test_tap_running_pipeline() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100);
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", Dummy1 { receiver: input_receiver }, &pipeline)
+            .attach("double", Doubler {})
+            .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+
+        input_sender.send(1).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 2);
+
+        let tap = pipeline.tap::<u32>("double", TapOptions::new(4)).unwrap();
+        input_sender.send(2).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 4);
+        assert_eq!(values(tap.recv_timeout(Duration::from_secs(5)).into_iter().collect()), vec![4]);
+
+        drop(tap); // dropping the handle detaches it, the node carries on untouched
+        input_sender.send(3).unwrap();
+        assert_eq!(output_receiver.recv().unwrap(), 6);
+        pipeline.kill();
+    }
+}
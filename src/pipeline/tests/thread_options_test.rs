@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+
+
+    struct Dummy1 {
+        receiver: mpsc::Receiver<u32>
+    }
+    impl PipelineStep<(), u32> for Dummy1 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> {
+            match self.receiver.recv_timeout(Duration::from_millis(100)) {
+                Ok(val) => Ok(ODFormat::Standard(val)),
+                Err(_) => Err(StepError::recoverable("Timeout error"))
+            }
+        }
+    }
+    impl Source for Dummy1 {}
+
+    struct ThreadNameSink { // reports the name of the thread it is called on
+        sender: mpsc::Sender<String>,
+    }
+    impl PipelineStep<u32, ()> for ThreadNameSink {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: u32) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(thread::current().name().unwrap_or("").to_string()).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for ThreadNameSink {}
+
+    fn build(pipeline: &ConstructingPipeline, sink_options: ThreadOptions) -> (mpsc::SyncSender<u32>, mpsc::Receiver<String>) {
+        let (input_sender, input_receiver) = mpsc::sync_channel(1);
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        NodeBuilder::start_pipeline("source", Dummy1 { receiver: input_receiver }, pipeline)
+            .thread_options(sink_options)
+            .cap_pipeline("sink", ThreadNameSink { sender: output_sender });
+
+        (input_sender, output_receiver)
+    }
+
+    #[test]
+    fn test_thread_names() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100);
+        let (input_sender, output_receiver) = build(&pipeline, ThreadOptions::new().with_name("audio-out").pinned_to(&[0]));
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+        input_sender.send(1).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), "audio-out");
+
+        assert!(pipeline.get_thread_diagnostics().iter().all(|diagnostic| diagnostic.scheduling_error.is_none()));
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_unapplied_options_fail_the_build() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100)
+            .with_node_thread_options("source", ThreadOptions::new().pinned_to(&[100_000]));
+        let _channels = build(&pipeline, ThreadOptions::new());
+
+        match pipeline.finish_pipeline() {
+            Err(PipelineBuildError::Scheduling(errors)) => {
+                assert_eq!(errors.len(), 1);
+                assert!(matches!(&errors[0], SchedulingError::Affinity { node_id, .. } if node_id == "source"));
+            },
+            _ => panic!("expected the pinning of the source to fail the build")
+        }
+    }
+
+    #[test]
+    fn test_best_effort_options_are_reported() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100)
+            .with_thread_options(ThreadOptions::new().pinned_to(&[100_000]).best_effort());
+        let (input_sender, output_receiver) = build(&pipeline, ThreadOptions::new().with_name("sink-thread"));
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+        input_sender.send(1).unwrap();
+        assert_eq!(output_receiver.recv_timeout(Duration::from_secs(5)).unwrap(), "sink-thread"); // builder options replace the defaults
+
+        let diagnostics = pipeline.get_thread_diagnostics();
+        let source = diagnostics.iter().find(|diagnostic| diagnostic.id == "source").unwrap();
+        let sink = diagnostics.iter().find(|diagnostic| diagnostic.id == "sink").unwrap();
+        assert!(matches!(source.scheduling_error, Some(SchedulingError::Affinity { .. })));
+        assert!(sink.scheduling_error.is_none());
+        pipeline.kill();
+    }
+
+    #[test]
+    fn test_options_without_node_threads() {
+        initialize_logger();
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100)
+            .with_execution_model(ExecutionModel::Deterministic);
+        let _channels = build(&pipeline, ThreadOptions::new().with_priority(10));
+
+        match pipeline.finish_pipeline() {
+            Err(PipelineBuildError::Scheduling(errors)) => assert!(matches!(&errors[..], [SchedulingError::Unsupported { node_id, .. }] if node_id == "sink")),
+            _ => panic!("expected the priority of the sink to be unsupported")
+        }
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use std::thread::sleep;
+    use std::time::{Duration, Instant};
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+    use crate::pipeline::sources::vector_source::VectorSource;
+
+
+    fn buffers(count: usize, length: usize) -> VectorSource<Vec<f32>> {
+        VectorSource::new(vec![vec![0.0; length]; count])
+    }
+
+    struct SlowSource { // takes longer to produce a buffer than the buffer lasts
+        delay: Duration
+    }
+    impl PipelineStep<(), Vec<f32>> for SlowSource {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+
+        fn run_DISO(&mut self) -> Result<ODFormat<Vec<f32>>, StepError> {
+            sleep(self.delay);
+            Ok(ODFormat::Standard(vec![0.0; 10]))
+        }
+    }
+    impl Source for SlowSource {}
+
+    struct TimingSink {
+        sender: mpsc::Sender<Instant>
+    }
+    impl PipelineStep<Vec<f32>, ()> for TimingSink {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: Vec<f32>) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(Instant::now()).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for TimingSink {}
+
+    #[test]
+    fn test_source_is_paced() {
+        let clock = SampleClock::new(10_000.0);
+        let mut source = Paced::new(buffers(5, 100), &clock); // 10ms per buffer
+        let monitor = source.monitor();
+
+        let start = Instant::now();
+        for _ in 0..5 {
+            source.run_DISO().unwrap();
+        }
+        let elapsed = start.elapsed();
+
+        assert!(elapsed >= Duration::from_millis(40), "5 buffers took {:?}", elapsed); // the first buffer is due immediately
+        assert!(elapsed < Duration::from_millis(200), "5 buffers took {:?}", elapsed);
+        assert_eq!(monitor.samples(), 500);
+        assert_eq!(monitor.slips(), 0);
+        assert!(matches!(source.run_DISO(), Err(error) if error.kind() == StepErrorKind::EndOfStream));
+    }
+
+    #[test]
+    fn test_shared_clock_aligns_sources() {
+        let clock = SampleClock::new(10_000.0);
+        let mut first = Paced::new(buffers(4, 200), &clock);
+        let mut second = Paced::new(buffers(4, 200), &clock);
+
+        for _ in 0..3 {
+            first.run_DISO().unwrap();
+        }
+        sleep(Duration::from_millis(20)); // the second source starts late, but its buffers are due on the same clock
+
+        let mut second_release = Instant::now();
+        for _ in 0..4 {
+            second.run_DISO().unwrap();
+            second_release = Instant::now();
+        }
+        first.run_DISO().unwrap();
+        let first_release = Instant::now();
+
+        let gap = if first_release > second_release { first_release - second_release } else { second_release - first_release };
+        assert!(gap < Duration::from_millis(10), "the fourth buffers were released {:?} apart", gap);
+    }
+
+    #[test]
+    fn test_slow_source_slips() {
+        initialize_logger();
+        let clock = SampleClock::new(1_000.0);
+        let mut source = Paced::new(SlowSource { delay: Duration::from_millis(30) }, &clock) // 10ms per buffer
+            .with_max_lag(Duration::from_millis(15))
+            .with_name("slow");
+        let monitor = source.monitor();
+
+        for _ in 0..4 {
+            source.run_DISO().unwrap();
+        }
+
+        assert!(monitor.slips() >= 2);
+        assert!(monitor.max_drift() >= Duration::from_millis(15));
+        assert!(monitor.drift() <= monitor.max_drift());
+    }
+
+    #[test]
+    fn test_held_clock_does_not_burst() {
+        let clock = SampleClock::new(10_000.0);
+        let mut source = Paced::new(buffers(3, 100), &clock);
+
+        source.run_DISO().unwrap();
+        source.pause_behavior();
+        sleep(Duration::from_millis(50));
+        source.start_behavior();
+
+        let resumed = Instant::now();
+        source.run_DISO().unwrap();
+        source.run_DISO().unwrap();
+
+        assert!(resumed.elapsed() >= Duration::from_millis(15), "the buffers owed for the pause were released at once");
+        assert_eq!(source.monitor().slips(), 0);
+    }
+
+    #[test]
+    fn test_paced_pipeline() {
+        initialize_logger();
+        let clock = SampleClock::new(10_000.0);
+        let source = Paced::new(buffers(6, 100), &clock);
+        let monitor = source.monitor();
+        let (sender, receiver) = mpsc::channel();
+
+        let pipeline = ConstructingPipeline::new(3, 1000, 2, 2, 0, 100);
+        NodeBuilder::start_pipeline("source", source, &pipeline)
+            .cap_pipeline("sink", TimingSink { sender });
+
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
+        pipeline.start();
+        let arrivals: Vec<Instant> = (0..6).map(|_| receiver.recv_timeout(Duration::from_secs(5)).unwrap()).collect();
+        pipeline.kill();
+
+        assert!(arrivals[5] - arrivals[0] >= Duration::from_millis(45), "6 buffers arrived within {:?}", arrivals[5] - arrivals[0]);
+        assert_eq!(monitor.samples(), 600);
+    }
+}
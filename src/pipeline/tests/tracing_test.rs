@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod pipeline_tests {
+    use std::sync::mpsc;
+    use serde_json::Value;
+    use tracing_subscriber::layer::SubscriberExt;
+    use crate::pipeline::api::*;
+    use crate::pipeline::logging::initialize_logger;
+    use crate::pipeline::sources::vector_source::VectorSource;
+
+
+    struct Doubler {}
+    impl PipelineStep<Vec<f32>, Vec<f32>> for Doubler {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+
+        fn run_SISO(&mut self, input: Vec<f32>) -> Result<ODFormat<Vec<f32>>, StepError> {
+            Ok(ODFormat::Standard(input.iter().map(|value| value * 2.0).collect()))
+        }
+    }
+
+    struct Dummy3 {
+        sender: mpsc::Sender<Vec<f32>>,
+    }
+    impl PipelineStep<Vec<f32>, ()> for Dummy3 {
+        fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+
+        fn run_SIDO(&mut self, input: Vec<f32>) -> Result<ODFormat<()>, StepError> {
+            self.sender.send(input).unwrap();
+            Ok(ODFormat::Standard(()))
+        }
+    }
+    impl Sink for Dummy3 {}
+
+    fn complete_events<'a>(events: &'a [Value], category: &str) -> Vec<&'a Value> {
+        events.iter().filter(|event| event["ph"] == "X" && event["cat"] == category).collect()
+    }
+
+    #[test]
+    fn test_chrome_trace_of_node_calls() {
+        initialize_logger();
+        let path = std::env::temp_dir().join(format!("zubr-trace-{}.json", std::process::id()));
+        let (layer, guard) = ChromeTraceLayer::to_file(&path).unwrap();
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), || {
+            // the deterministic executor calls every node on this thread, where the subscriber is the default
+            let pipeline = ConstructingPipeline::new(3, 1000, 1, 2, 3, 100).with_execution_model(ExecutionModel::Deterministic);
+            NodeBuilder::start_pipeline("source", VectorSource::new(vec![vec![1.0; 4], vec![2.0; 4]]), &pipeline)
+                .attach("doubler", Doubler {})
+                .cap_pipeline("sink", Dummy3 { sender: output_sender });
+
+            let mut executor = pipeline.finish_pipeline().unwrap().into_executor();
+            executor.run(2);
+            executor.kill();
+        });
+        guard.finish().unwrap();
+        assert_eq!(output_receiver.try_iter().count(), 2);
+
+        let events: Vec<Value> = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let calls = complete_events(&events, "node_call");
+        let doubler: Vec<&&Value> = calls.iter().filter(|event| event["name"] == "doubler").collect();
+        assert_eq!(doubler.len(), 2);
+        assert_eq!(doubler[0]["args"]["receive"], "Single");
+        assert_eq!(doubler[0]["args"]["samples"], 4);
+        assert_eq!(doubler[0]["args"]["outcome"], "success");
+
+        // every phase is a child of its call, on the same track and within its time
+        for phase in ["receive", "compute", "send"] {
+            let phases = complete_events(&events, phase);
+            assert!(phases.len() >= 6, "expected a {} span for every call", phase);
+            for event in phases {
+                let ts = event["ts"].as_f64().unwrap();
+                assert!(calls.iter().any(|call| {
+                    let start = call["ts"].as_f64().unwrap();
+                    call["tid"] == event["tid"] && start <= ts && ts <= start + call["dur"].as_f64().unwrap()
+                }));
+            }
+        }
+        assert!(events.iter().any(|event| event["ph"] == "M" && event["name"] == "thread_name"));
+    }
+
+    #[test]
+    fn test_empty_trace_is_valid() {
+        let (layer, guard) = ChromeTraceLayer::to_writer(Vec::new());
+        drop(layer);
+        guard.finish().unwrap();
+
+        let path = std::env::temp_dir().join(format!("zubr-empty-trace-{}.json", std::process::id()));
+        let (_layer, guard) = ChromeTraceLayer::to_file(&path).unwrap();
+        drop(guard);
+        let events: Vec<Value> = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(events.is_empty());
+    }
+}
@@ -0,0 +1,46 @@
+// compile_fail case for pipeline!, run by dsl_test::test_compile_errors. The fixture builds the crate's modules itself, the
+// package has no library target to link against
+#![feature(trait_alias)]
+#![feature(mpmc_channel, portable_simd, test)]
+#![feature(let_chains)]
+#![allow(warnings)]
+
+#[path = "../../mod.rs"] mod pipeline;
+#[path = "../../../dsp/mod.rs"] mod dsp;
+#[path = "../../../ddp/mod.rs"] mod ddp;
+#[path = "../../../general/mod.rs"] mod general;
+
+use pipeline::api::*;
+
+
+struct Count {}
+impl PipelineStep<(), u32> for Count {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+    fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> { Ok(ODFormat::Standard(1)) }
+}
+impl Source for Count {}
+
+struct Pass {}
+impl PipelineStep<u32, u32> for Pass {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO, StepHandler::SIMO] }
+    fn run_SISO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> { Ok(ODFormat::Standard(input)) }
+    fn run_SIMO(&mut self, input: u32) -> Result<ODFormat<u32>, StepError> { Ok(ODFormat::Standard(input)) }
+}
+
+struct Drain {}
+impl PipelineStep<u32, ()> for Drain {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+    fn run_SIDO(&mut self, _input: u32) -> Result<ODFormat<()>, StepError> { Ok(ODFormat::Standard(())) }
+}
+impl Sink for Drain {}
+
+
+fn main() {
+    let pipeline = ConstructingPipeline::new(3, 1000, 1, 2, 3, 1000);
+    pipeline!(&pipeline;
+        "count" (Count {}) -> "split" (Pass {}) -> [
+            "drain" (Drain {}),
+            _
+        ]
+    );
+}
@@ -0,0 +1,42 @@
+// compile_fail case for pipeline!, run by dsl_test::test_compile_errors. The fixture builds the crate's modules itself, the
+// package has no library target to link against
+#![feature(trait_alias)]
+#![feature(mpmc_channel, portable_simd, test)]
+#![feature(let_chains)]
+#![allow(warnings)]
+
+#[path = "../../mod.rs"] mod pipeline;
+#[path = "../../../dsp/mod.rs"] mod dsp;
+#[path = "../../../ddp/mod.rs"] mod ddp;
+#[path = "../../../general/mod.rs"] mod general;
+
+use pipeline::api::*;
+
+
+struct Count {}
+impl PipelineStep<(), u32> for Count {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::DISO] }
+    fn run_DISO(&mut self) -> Result<ODFormat<u32>, StepError> { Ok(ODFormat::Standard(1)) }
+}
+impl Source for Count {}
+
+struct Halve {}
+impl PipelineStep<f32, f32> for Halve {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SISO] }
+    fn run_SISO(&mut self, input: f32) -> Result<ODFormat<f32>, StepError> { Ok(ODFormat::Standard(input / 2.0)) }
+}
+
+struct Drain {}
+impl PipelineStep<f32, ()> for Drain {
+    fn handlers(&self) -> Vec<StepHandler> { vec![StepHandler::SIDO] }
+    fn run_SIDO(&mut self, _input: f32) -> Result<ODFormat<()>, StepError> { Ok(ODFormat::Standard(())) }
+}
+impl Sink for Drain {}
+
+
+fn main() {
+    let pipeline = ConstructingPipeline::new(3, 1000, 1, 2, 3, 1000);
+    pipeline!(&pipeline;
+        "count" (Count {}) -> "halve" (Halve {}) -> "drain" (Drain {})
+    );
+}
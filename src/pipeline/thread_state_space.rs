@@ -1,5 +1,7 @@
 use num_enum::TryFromPrimitive;
 use std::string::ToString;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
 
 
 #[repr(u8)]
@@ -17,4 +19,77 @@ impl ThreadStateSpace {
             Self::KILLED => 2,
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone)]
+pub struct BranchState {
+    /*
+    The state of one branch between a multiplexer and its demultiplexer. The branch only runs while the channel selector points at it,
+    otherwise its nodes are paused (and their pause_behavior called) while the rest of the pipeline keeps running.
+    A pause requested by a node inside the branch (eg after too many compute errors) only pauses this branch, and is cleared once
+    the selector moves to another branch so the branch starts clean when it is selected again
+     */
+    selector: Arc<AtomicUsize>,
+    branch: usize,
+    error_paused: Arc<AtomicBool>,
+    parent: Option<Arc<BranchState>>, // set for a multiplexer nested inside another multiplexed branch
+}
+impl BranchState {
+    pub fn is_selected(&self) -> bool {
+        self.selector.load(Ordering::Acquire) == self.branch && self.parent.as_ref().map_or(true, |parent| parent.is_selected())
+    }
+    pub fn requested_state(&self) -> ThreadStateSpace {
+        if !self.is_selected() || self.error_paused.load(Ordering::Acquire) { ThreadStateSpace::PAUSED }
+        else { ThreadStateSpace::RUNNING }
+    }
+    pub fn clear_pause_if_deselected(&self) { // the branch starts clean the next time it is selected
+        if !self.is_selected() {
+            self.error_paused.store(false, Ordering::Release);
+        }
+    }
+    pub fn request_pause(&self) {
+        self.error_paused.store(true, Ordering::Release);
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct NodeStateCommunicator {
+    /*
+    Everything a node needs to follow the pipeline state. The global state comes from the CommandStatePasser, and the messenger reports
+    pause/kill requests back to its manager thread. Nodes inside a multiplexed branch additionally follow their branch state.
+    The drain flag tells sources to end their stream, so the pipeline can flush everything already in flight before it is killed
+     */
+    pub global_state: Arc<AtomicU8>,
+    pub messenger: mpsc::Sender<ThreadStateSpace>,
+    pub drain: Arc<AtomicBool>,
+    pub branch: Option<BranchState>,
+}
+impl NodeStateCommunicator {
+    pub fn new(global_state: Arc<AtomicU8>, messenger: mpsc::Sender<ThreadStateSpace>, drain: Arc<AtomicBool>) -> Self {
+        Self { global_state, messenger, drain, branch: None }
+    }
+    pub fn for_branch(&self, selector: Arc<AtomicUsize>, branch: usize) -> Self {
+        let parent = self.branch.clone().map(Arc::new);
+        let branch = BranchState { selector, branch, error_paused: Arc::new(AtomicBool::new(false)), parent };
+        Self { global_state: self.global_state.clone(), messenger: self.messenger.clone(), drain: self.drain.clone(), branch: Some(branch) }
+    }
+    pub fn drain_requested(&self) -> bool {
+        self.drain.load(Ordering::Acquire)
+    }
+    pub fn global_requested_state(&self) -> ThreadStateSpace {
+        ThreadStateSpace::try_from(self.global_state.load(Ordering::Acquire)).unwrap()
+    }
+    pub fn clear_branch_pause_if_deselected(&self) {
+        if let Some(branch) = &self.branch {
+            branch.clear_pause_if_deselected();
+        }
+    }
+    pub fn requested_state(&self) -> ThreadStateSpace {
+        // the branch can only hold its nodes back further, a paused or killed pipeline stays paused or killed
+        match (self.global_requested_state(), &self.branch) {
+            (ThreadStateSpace::RUNNING, Some(branch)) => branch.requested_state(),
+            (global_state, _) => global_state
+        }
+    }
+}
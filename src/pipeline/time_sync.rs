@@ -0,0 +1,177 @@
+// Sample clock pacing, so a source which could produce as fast as the cpu allows (a sinusoid, a file) runs at its real sample rate.
+// A SampleClock maps sample positions to wall clock instants from an epoch taken when the first paced source asks for it. Paced wraps a
+// source step and holds each buffer back until the clock reaches the position of its first sample, so buffer n of every source sharing
+// one clock is released at the same instant, which keeps multi channel sources aligned.
+// Pausing the pipeline holds the clock, so resuming carries on from where it stopped instead of bursting to catch up. A source which
+// still falls further behind than its max lag (it is slower than real time, or blocked on a full link) is moved up to the present
+// and the slip is counted, its drift is reported through a DriftMonitor either way.
+
+use std::sync::atomic::{AtomicI64, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use super::pipeline_comms::ODFormat;
+use super::pipeline_errors::StepError;
+use super::pipeline_step::{PipelineStep, StepWrapper, wrapped_step};
+use super::pipeline_traits::{Sharable, Source};
+use super::logging::{log_message, Level};
+
+
+const DEFAULT_MAX_LAG: Duration = Duration::from_millis(100);
+
+
+#[derive(Debug, Default)]
+struct ClockState {
+    epoch: Option<Instant>, // instant of sample 0, None until the first paced source starts
+    held_since: Option<Instant>,
+    holders: usize // paced sources currently paused, the clock only moves again once all of them resumed
+}
+
+
+#[derive(Debug, Clone)]
+pub struct SampleClock {
+    sample_rate: f64,
+    state: Arc<Mutex<ClockState>>
+}
+impl SampleClock {
+    pub fn new(sample_rate: f64) -> Self {
+        assert!(sample_rate > 0.0);
+        Self { sample_rate, state: Arc::new(Mutex::new(ClockState::default())) }
+    }
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    fn epoch(&self) -> Instant {
+        *self.state.lock().unwrap().epoch.get_or_insert_with(Instant::now)
+    }
+    pub fn instant_of(&self, sample: u64) -> Instant { // when the sample is due, starts the clock if nothing has yet
+        self.epoch() + Duration::from_secs_f64(sample as f64 / self.sample_rate)
+    }
+    pub fn current_sample(&self) -> u64 {
+        (self.epoch().elapsed().as_secs_f64() * self.sample_rate) as u64
+    }
+
+    pub fn hold(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.holders == 0 {
+            state.held_since = Some(Instant::now());
+        }
+        state.holders += 1;
+    }
+    pub fn release(&self) { // the epoch moves forward by the time the clock was held, so no samples are owed for it
+        let mut state = self.state.lock().unwrap();
+        if state.holders == 0 { return } // the first start of a source, which was never paused
+
+        state.holders -= 1;
+        if state.holders == 0 {
+            let held_for = state.held_since.take().map(|held_since| held_since.elapsed()).unwrap_or_default();
+            state.epoch = state.epoch.map(|epoch| epoch + held_for);
+        }
+    }
+}
+
+
+#[derive(Debug, Default)]
+struct DriftState {
+    drift: AtomicI64, // microseconds the last buffer was released after it was due, never negative while pacing works
+    max_drift: AtomicI64,
+    samples: AtomicU64,
+    slips: AtomicUsize
+}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct DriftMonitor {
+    // a view of the pacing of one source, kept by whoever watches the pipeline after the step was moved into it
+    state: Arc<DriftState>
+}
+impl DriftMonitor {
+    pub fn drift(&self) -> Duration {
+        Duration::from_micros(self.state.drift.load(Ordering::Acquire).max(0) as u64)
+    }
+    pub fn max_drift(&self) -> Duration {
+        Duration::from_micros(self.state.max_drift.load(Ordering::Acquire).max(0) as u64)
+    }
+    pub fn samples(&self) -> u64 { // samples released since the source started
+        self.state.samples.load(Ordering::Acquire)
+    }
+    pub fn slips(&self) -> usize { // times the source fell behind by more than its max lag and was moved up to the present
+        self.state.slips.load(Ordering::Acquire)
+    }
+}
+
+
+pub struct Paced<S> {
+    // wraps a source step and releases its output at the sample rate of the clock
+    step: S,
+    clock: SampleClock,
+    position: u64, // the clock sample the next buffer is due at
+    max_lag: Duration,
+    monitor: DriftMonitor,
+    id: String
+}
+impl<S> Paced<S> {
+    pub fn new(step: S, clock: &SampleClock) -> Self {
+        Self { step, clock: clock.clone(), position: 0, max_lag: DEFAULT_MAX_LAG, monitor: DriftMonitor::default(), id: String::from("paced source") }
+    }
+    pub fn with_max_lag(mut self, max_lag: Duration) -> Self {
+        self.max_lag = max_lag;
+        self
+    }
+    pub fn with_name(mut self, id: &str) -> Self { // used in the drift warnings
+        self.id = id.to_string();
+        self
+    }
+    pub fn monitor(&self) -> DriftMonitor {
+        self.monitor.clone()
+    }
+
+    fn wait_for_position(&mut self) {
+        let due = self.clock.instant_of(self.position);
+        let now = Instant::now();
+
+        if now < due {
+            sleep(due - now);
+            self.monitor.state.drift.store(0, Ordering::Release);
+            return;
+        }
+
+        let late = now - due;
+        self.monitor.state.drift.store(late.as_micros() as i64, Ordering::Release);
+        self.monitor.state.max_drift.fetch_max(late.as_micros() as i64, Ordering::AcqRel);
+
+        if late > self.max_lag {
+            log_message(format!("{}: {:?} behind the sample clock, skipping ahead to the present", &self.id, late), Level::Warn);
+            self.position = self.clock.current_sample();
+            self.monitor.state.slips.fetch_add(1, Ordering::AcqRel);
+        }
+    }
+}
+
+fn paced_samples<O: Sharable>(output: &ODFormat<O>) -> u64 {
+    // samples of one channel, the channels of a decomposed output are played out side by side
+    match output {
+        ODFormat::Decompose(channels) => channels.first().map(|channel| channel.sample_count()).unwrap_or(0) as u64,
+        _ => output.counts().1 as u64
+    }
+}
+
+impl<O: Sharable, S: PipelineStep<(), O>> StepWrapper<(), O> for Paced<S> {
+    fn inner(&mut self) -> &mut dyn PipelineStep<(), O> { &mut self.step }
+    fn inner_ref(&self) -> &dyn PipelineStep<(), O> { &self.step }
+    fn around_call(&mut self, call: impl FnOnce(&mut dyn PipelineStep<(), O>) -> Result<ODFormat<O>, StepError>) -> Result<ODFormat<O>, StepError> {
+        self.wait_for_position();
+
+        let output = call(&mut self.step)?;
+        let samples = paced_samples(&output);
+        self.position += samples;
+        self.monitor.state.samples.fetch_add(samples, Ordering::AcqRel);
+
+        Ok(output)
+    }
+    fn before_pause(&mut self) { self.clock.hold() }
+    fn before_start(&mut self) { self.clock.release() }
+}
+wrapped_step!([O: Sharable, S: PipelineStep<(), O>] Paced<S>, (), O);
+impl<S: Source> Source for Paced<S> {}
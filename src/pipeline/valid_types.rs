@@ -1,8 +1,17 @@
+use std::iter::Sum;
 use num::Complex;
-use super::pipeline_traits::HasDefault;
+use num::traits::{Float, FloatConst, FromPrimitive, NumAssign};
+use rustfft::FftNum;
+use super::byte_codec::{ByteReader, ByteWriter};
+use super::pipeline_traits::{HasDefault, HasEncoding, HasSampleCount, Sharable};
 use num::traits;
 
-pub trait ValidFloat {}
+pub trait ValidFloat: Float + FloatConst + FromPrimitive + NumAssign + Sum + FftNum + Sharable {
+    // the sample types the dsp steps are generic over, f32 for speed and f64 where f32 loses too much precision
+    fn constant(value: f64) -> Self { // for literals and counts inside generic code, exact for f64 and rounded for f32
+        Self::from_f64(value).unwrap()
+    }
+}
 impl ValidFloat for f32 {}
 impl ValidFloat for f64 {}
 
@@ -27,14 +36,9 @@ impl HasDefault for u32 {
         0
     }
 }
-impl HasDefault for Complex<f32> {
-    fn default() -> Self {
-        Complex::new(0.0, 0.0)
-    }
-}
-impl HasDefault for Complex<f64> {
+impl<T: ValidFloat> HasDefault for Complex<T> {
     fn default() -> Self {
-        Complex::new(0.0, 0.0)
+        Complex::new(T::zero(), T::zero())
     }
 }
 impl HasDefault for bool {
@@ -61,4 +65,62 @@ impl HasDefault for f64 {
     fn default() -> Self {
         0.0
     }
-}
\ No newline at end of file
+}
+
+impl HasSampleCount for u8 {}
+impl HasSampleCount for u32 {}
+impl<T: ValidFloat> HasSampleCount for Complex<T> {}
+impl HasSampleCount for bool {}
+impl HasSampleCount for f32 {}
+impl HasSampleCount for f64 {}
+impl HasSampleCount for () {
+    fn sample_count(&self) -> usize { 0 }
+}
+impl<T: HasSampleCount> HasSampleCount for Vec<T> {
+    fn sample_count(&self) -> usize {
+        self.iter().map(|value| value.sample_count()).sum()
+    }
+}
+
+impl HasEncoding for u8 {
+    fn write_to(&self, out: &mut ByteWriter) -> Result<(), String> { Ok(out.put_u8(*self)) }
+    fn read_from(reader: &mut ByteReader) -> Result<Self, String> { reader.u8() }
+}
+impl HasEncoding for u32 {
+    fn write_to(&self, out: &mut ByteWriter) -> Result<(), String> { Ok(out.put_u32(*self)) }
+    fn read_from(reader: &mut ByteReader) -> Result<Self, String> { reader.u32() }
+}
+impl HasEncoding for bool {
+    fn write_to(&self, out: &mut ByteWriter) -> Result<(), String> { Ok(out.put_bool(*self)) }
+    fn read_from(reader: &mut ByteReader) -> Result<Self, String> { reader.bool() }
+}
+impl HasEncoding for f32 {
+    fn write_to(&self, out: &mut ByteWriter) -> Result<(), String> { Ok(out.put_f32(*self)) }
+    fn read_from(reader: &mut ByteReader) -> Result<Self, String> { reader.f32() }
+}
+impl HasEncoding for f64 {
+    fn write_to(&self, out: &mut ByteWriter) -> Result<(), String> { Ok(out.put_f64(*self)) }
+    fn read_from(reader: &mut ByteReader) -> Result<Self, String> { reader.f64() }
+}
+impl HasEncoding for () {
+    fn write_to(&self, _out: &mut ByteWriter) -> Result<(), String> { Ok(()) }
+    fn read_from(_reader: &mut ByteReader) -> Result<Self, String> { Ok(()) }
+}
+impl<T: ValidFloat> HasEncoding for Complex<T> {
+    fn write_to(&self, out: &mut ByteWriter) -> Result<(), String> {
+        self.re.write_to(out)?;
+        self.im.write_to(out)
+    }
+    fn read_from(reader: &mut ByteReader) -> Result<Self, String> {
+        Ok(Complex::new(T::read_from(reader)?, T::read_from(reader)?))
+    }
+}
+impl<T: HasEncoding> HasEncoding for Vec<T> {
+    fn write_to(&self, out: &mut ByteWriter) -> Result<(), String> {
+        out.put_u32(self.len() as u32);
+        self.iter().try_for_each(|value| value.write_to(out))
+    }
+    fn read_from(reader: &mut ByteReader) -> Result<Self, String> {
+        (0..reader.u32()?).map(|_| T::read_from(reader)).collect()
+    }
+}
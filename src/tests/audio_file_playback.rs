@@ -27,13 +27,12 @@ mod end_to_end_tests {
             .cap_pipeline(
             "audio sink", AudioSink::new(1, 40000, sink, true)
         );
-        let mut pipeline = pipeline.finish_pipeline();
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
         pipeline.start();
 
         let time = std::time::Instant::now();
-        while pipeline.is_running() {
-            std::thread::sleep(std::time::Duration::from_millis(100));
-        }
+        assert!(pipeline.wait_for_end_of_stream(std::time::Duration::from_secs(300)));
         assert!(time.elapsed() >= std::time::Duration::from_secs(99));
+        pipeline.kill();
     }
 }
\ No newline at end of file
@@ -53,17 +53,16 @@ mod end_to_end_tests {
             "test audio source",
             AudioFileSource::new("/home/malinkyzubr/Documents/ZubrDSP/src/pipeline/sources/tests/starstest.wav", 2048, 3),
             &mut pipeline)
-            .attach("convolution", DiscreteConvolution::new(2048, lpf_impulse_resp_norm.len(), Some(lpf_impulse_resp_norm)))
+            .attach("convolution", DiscreteConvolution::new(2048, lpf_impulse_resp_norm.len(), Some(lpf_impulse_resp_norm)).unwrap())
         .cap_pipeline(
             "audio sink", AudioSink::new(2, 48000, sink, true)
         );
-        let mut pipeline = pipeline.finish_pipeline();
+        let mut pipeline = pipeline.finish_pipeline().unwrap();
         pipeline.start();
 
         let time = std::time::Instant::now();
-        while pipeline.is_running() {
-            std::thread::sleep(std::time::Duration::from_millis(100));
-        }
+        assert!(pipeline.wait_for_end_of_stream(std::time::Duration::from_secs(300)));
         assert!(time.elapsed() >= std::time::Duration::from_secs(99));
+        pipeline.kill();
     }
 }
\ No newline at end of file